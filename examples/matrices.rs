@@ -11,8 +11,8 @@ fn main() {
     println!();
     let mut state = [[0.0_f64; 4]; 4];
     let mut i = 20.0;
-    for (_, row) in state.iter_mut().enumerate() {
-        for (_, e) in row.iter_mut().enumerate() {
+    for row in state.iter_mut() {
+        for e in row.iter_mut() {
             *e = i;
             i += 5.1;
         }
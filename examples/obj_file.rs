@@ -1,5 +1,6 @@
 use lab_raytracing_rs::{
     camera::Camera,
+    canvas::BitDepth,
     lights::Pointlight,
     materials::Material,
     matrices::identity_matrix,
@@ -10,7 +11,7 @@ use lab_raytracing_rs::{
     tuples::{color, point, vector},
     world::World,
 };
-use std::{env, f64::consts::PI, fs, io};
+use std::{env, f64::consts::PI, io, path::Path};
 
 fn main() -> io::Result<()> {
     coz::thread_init();
@@ -21,8 +22,12 @@ fn main() -> io::Result<()> {
         "z" => rotation_x(-PI / 2.0),
         _ => panic!("upwards axis undefined"),
     };
-    let width = args.get(2).expect("width is missing").parse::<usize>().expect("width is not a number");
-    let height  = (width as f64 * 9.0 / 16.0) as usize;
+    let width = args
+        .get(2)
+        .expect("width is missing")
+        .parse::<usize>()
+        .expect("width is not a number");
+    let height = (width as f64 * 9.0 / 16.0) as usize;
     let file = args.get(3).expect("obj file missing");
 
     let mut world = World::default();
@@ -58,13 +63,19 @@ fn main() -> io::Result<()> {
     world.add_object(wall);
 
     eprintln!("load teapot");
-    let content = fs::read_to_string(file).expect("could not read file");
-    let mut teapot = Parser::parse_obj_file(&content).to_group();
-
-    eprintln!("set material");
-    let mut material = Material::default();
-    material.color = color(255.0 / 250.0, 215.0 / 250.0, 0.0 / 250.0);
-    teapot.set_material(&material);
+    let parser = Parser::load_obj_file_as_meshes(Path::new(file)).expect("could not load file");
+    for warning in parser.warnings.iter() {
+        eprintln!("{}: {}", file, warning);
+    }
+    let mut teapot = parser.to_group();
+
+    // models without materials of their own are painted gold
+    if parser.materials.is_empty() {
+        eprintln!("set material");
+        let mut material = Material::default();
+        material.color = color(255.0 / 250.0, 215.0 / 250.0, 0.0 / 250.0);
+        teapot.set_material(&material);
+    }
 
     eprintln!("center");
     teapot.set_transform(rotate.clone());
@@ -82,9 +93,6 @@ fn main() -> io::Result<()> {
 
     eprintln!("bounds: {:?}", teapot.bounds().clone().unwrap());
 
-    eprintln!("regroup aabb");
-    let teapot = teapot.regroup_aabb();
-
     world.add_group(teapot);
 
     eprintln!("setup light and lighting");
@@ -100,12 +108,14 @@ fn main() -> io::Result<()> {
         &vector(0.0, 1.0, 0.0),
     ));
 
+    eprintln!("bvh: {}", world.build_bvh());
+
     eprintln!("rendering");
     let canvas = camera.render(&world);
 
     let file = &mut io::stdout();
     let writer = &mut io::BufWriter::with_capacity(1024 * 128, file);
-    canvas.ppm(writer)?;
+    canvas.ppm_binary(writer, BitDepth::Eight)?;
 
     Ok(())
 }
@@ -53,6 +53,7 @@ fn main() -> io::Result<()> {
                     &eye,
                     &normal,
                     false,
+                    &(hit.u, hit.v),
                 );
                 canvas.set(x, y, color);
             };
@@ -0,0 +1,216 @@
+Feature: Bézier patch files
+
+    Scenario: Parsing patches with their own control points
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            1 0 0
+            2 0 0
+            3 0 0
+            0 0 1
+            1 1 1
+            2 1 1
+            3 0 1
+            0 0 2
+            1 1 2
+            2 1 2
+            3 0 2
+            0 0 3
+            1 0 3
+            2 0 3
+            3 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        Then patches.count = 1
+        And patches[0].points[0] = point(0, 0, 0)
+        And patches[0].points[5] = point(1, 1, 1)
+        And patches[0].points[15] = point(3, 0, 3)
+
+    Scenario: Parsing patches that share indexed vertices
+        Given file ← a file containing:
+            """
+            2
+            1,2,3,4,8,9,10,11,15,16,17,18,22,23,24,25
+            4,5,6,7,11,12,13,14,18,19,20,21,25,26,27,28
+            28
+            0,0,0
+            1,0,0
+            2,0,0
+            3,0,0
+            4,0,0
+            5,0,0
+            6,0,0
+            0,0,1
+            1,1,1
+            2,1,1
+            3,1,1
+            4,0,1
+            5,0,1
+            6,0,1
+            0,0,2
+            1,1,2
+            2,1,2
+            3,1,2
+            4,0,2
+            5,0,2
+            6,0,2
+            0,0,3
+            1,0,3
+            2,0,3
+            3,0,3
+            4,0,3
+            5,0,3
+            6,0,3
+            """
+        When patches ← parse_bezier_file(file)
+        Then patches.count = 2
+        And patches[0].points[7] = point(3, 1, 1)
+        And patches[1].points[4] = point(3, 1, 1)
+        And patches[1].points[15] = point(6, 0, 3)
+
+    Scenario: Reporting a patch that is not bicubic
+        Given file ← a file containing:
+            """
+            1
+            2 3
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            15 0 0
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "line 2: only bicubic patches are supported"
+
+    Scenario: Reporting a control point with two coordinates
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            15 0
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "line 17: expected 3 coordinates, got 2"
+
+    Scenario: Reporting a control point that is not a number
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            15 0 z
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "line 17: invalid number 'z'"
+
+    Scenario: Reporting a missing control point
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "missing a control point"
+
+    Scenario: Reporting a patch with too many indices
+        Given file ← a file containing:
+            """
+            1
+            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            15 0 0
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "line 2: expected 16 vertex indices, got 17"
+
+    Scenario: Reporting a vertex index out of range
+        Given file ← a file containing:
+            """
+            1
+            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 17
+            16
+            1 0 0
+            2 0 0
+            3 0 0
+            4 0 0
+            5 0 0
+            6 0 0
+            7 0 0
+            8 0 0
+            9 0 0
+            10 0 0
+            11 0 0
+            12 0 0
+            13 0 0
+            14 0 0
+            15 0 0
+            16 0 0
+            """
+        When patches ← parse_bezier_file(file)
+        Then parsing the bezier file failed with "line 2: vertex index 17 is out of range"
@@ -0,0 +1,172 @@
+Feature: Bounding volume hierarchies
+
+    Scenario: Building a hierarchy over a row of spheres
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(-5, 0, 0), vector(1, 0, 0))
+        When stats ← build_bvh(w)
+        And xs ← intersect_world(w, r)
+        Then stats.trees = 1
+        And stats.members = 8
+        And stats.unbounded = 0
+        And stats.nodes = 7
+        And stats.leaves = 4
+        And stats.min_leaf = 2
+        And stats.max_leaf = 2
+        And stats.depth = 3
+        And stats.cost = 4
+        And xs.count = 16
+        And xs[0].t = 7
+        And xs[15].t = 30
+
+    Scenario: A single member needs no hierarchy
+        Given w ← world()
+        And 1 spheres in a row 3 apart are added to w
+        When stats ← build_bvh(w)
+        Then stats.nodes = 1
+        And stats.leaves = 1
+        And stats.depth = 1
+        And stats.cost = 1
+
+    Scenario: Planes stay next to the hierarchy
+        Given w ← world()
+        And a plane is added to w
+        And 4 spheres in a row 3 apart are added to w
+        When stats ← build_bvh(w)
+        Then stats.unbounded = 1
+        And stats.members = 4
+        And member 1 of w is a plane
+
+    Scenario: CSG nodes are kept whole
+        Given s1 ← sphere()
+        And s2 ← sphere()
+        And set_transform(s2, translation(0, 0, 0.5))
+        And csg ← csg("difference", s1, s2)
+        And w ← world()
+        And csg is added to w
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When stats ← build_bvh(w)
+        And xs ← intersect_world(w, r)
+        Then stats.members = 9
+        And w holds the csg whole
+        And xs.count = 2
+        And xs[0].t = 4
+        And xs[1].t = 4.5
+
+    Scenario: Large groups get a hierarchy of their own
+        Given g ← group()
+        And set_transform(g, scaling(2, 2, 2))
+        And 20 spheres in a row 3 apart are added to g
+        And w ← world()
+        And g is added to w
+        And r ← ray(point(-5, 0, 0), vector(1, 0, 0))
+        When stats ← build_bvh(w)
+        And xs ← intersect_world(w, r)
+        Then stats.trees = 2
+        And stats.members = 21
+        And member 1 of w is a group of 2 with the transform of g
+        And xs.count = 40
+        And xs[0].t = 9
+
+    Scenario: A group placed by several instances gets a hierarchy once
+        Given g ← group()
+        And 20 spheres in a row 3 apart are added to g
+        And i1 ← instance(g)
+        And i2 ← instance of the member of i1
+        And set_transform(i2, translation(0, 5, 0))
+        And w ← world()
+        And i1 is added to w
+        And i2 is added to w
+        And r ← ray(point(-5, 5, 0), vector(1, 0, 0))
+        When stats ← build_bvh(w)
+        And xs ← intersect_world(w, r)
+        Then stats.trees = 2
+        And stats.members = 22
+        And the instances in w share a group of 2
+        And xs.count = 40
+        And xs[0].t = 7
+
+    Scenario: The default world is intersected as before
+        Given w ← default_world()
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When stats ← build_bvh(w)
+        And xs ← intersect_world(w, r)
+        Then xs.count = 4
+        And xs[0].t = 4
+        And xs[1].t = 4.5
+        And xs[2].t = 5.5
+        And xs[3].t = 6
+
+    Scenario: The hierarchy is laid out in one array of nodes
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        When stats ← build_bvh(w)
+        Then w is laid out in 7 nodes over 8 members
+
+    Scenario: The hierarchy is built without asking for it
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        Then w is laid out in 7 nodes over 8 members
+
+    Scenario: Groups only add a node to the layout
+        Given g ← group()
+        And set_transform(g, scaling(2, 2, 2))
+        And 3 spheres in a row 3 apart are added to g
+        And w ← world()
+        And a plane is added to w
+        And g is added to w
+        And r ← ray(point(-5, 0, 0), vector(1, 0, 0))
+        When xs ← intersect_world(w, r)
+        Then w is laid out in 3 nodes over 4 members
+        And xs.count = 6
+        And xs[0].t = 9
+        And xs[5].t = 25
+
+    Scenario Outline: Nearer leaves are visited first
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(<x>, 0, 0), vector(<direction>, 0, 0))
+        When stats ← build_bvh(w)
+        Then the leaves of w along r begin at x = <leaves>
+
+        Examples:
+            | x  | direction | leaves         |
+            | -5 | 1         | 2, 8, 14, 20   |
+            | 30 | -1        | 20, 14, 8, 2   |
+            | 10 | 1         | 2, 8, 14, 20   |
+
+    Scenario Outline: Leaves behind the closest hit are skipped
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(<x>, 0, 0), vector(<direction>, 0, 0))
+        When stats ← build_bvh(w)
+        Then the leaves of w along r up to the first hit begin at x = <leaves>
+
+        Examples:
+            | x  | direction | leaves |
+            | -5 | 1         | 2      |
+            | 30 | -1        | 20     |
+            | 10 | 1         | 8      |
+
+    Scenario Outline: Any hit ends the traversal
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(<x>, 0, 0), vector(<direction>, 0, 0))
+        When stats ← build_bvh(w)
+        Then the leaves of w along r up to any hit begin at x = <leaves>
+        And any_hit(w, r) is true
+
+        Examples:
+            | x  | direction | leaves |
+            | -5 | 1         | 2      |
+            | 30 | -1        | 20     |
+            | 11 | 1         | 8      |
+
+    Scenario: Any hit finds nothing when the ray misses
+        Given w ← world()
+        And 8 spheres in a row 3 apart are added to w
+        And r ← ray(point(-5, 0.9, 0.9), vector(1, 0, 0))
+        When stats ← build_bvh(w)
+        Then the leaves of w along r up to any hit begin at x = 2, 8, 14, 20
+        And any_hit(w, r) is false
@@ -46,3 +46,33 @@ Feature: Camera
         And c.transform ← view_transform(from, to, up)
         When image ← render(c, w)
         Then pixel_at(image, 5, 5) = color(0.38066, 0.47583, 0.2855)
+
+    Scenario Outline: Parsing antialiasing modes
+        When antialiasing ← parse_antialiasing("<value>")
+        Then antialiasing = <mode>
+
+        Examples:
+            | value   | mode      |
+            | off     | off       |
+            | fast    | fast      |
+            | Dynamic | dynamic   |
+            | 3       | raster(3) |
+
+    Scenario Outline: Parsing render threads
+        When renderer ← parse_renderer("<value>")
+        Then renderer = <renderer>
+
+        Examples:
+            | value | renderer          |
+            | off   | single threaded   |
+            | 4     | multithreaded(4)  |
+
+    Scenario Outline: Rejecting invalid render settings
+        Then parsing "<value>" as <setting> fails
+
+        Examples:
+            | setting      | value  |
+            | antialiasing | 0      |
+            | antialiasing | smooth |
+            | renderer     | -2     |
+            | renderer     | all    |
@@ -54,3 +54,267 @@ Feature: Canvas
         Given c ← canvas(5, 3)
         When ppm ← canvas_to_ppm(c)
         Then ppm ends with a newline character
+
+    Scenario: Constructing the PNG header
+        Given c ← canvas(5, 3)
+        When png ← canvas_to_png(c)
+        Then bytes 1-33 of png are
+            """
+            89 50 4e 47 0d 0a 1a 0a
+            00 00 00 0d 49 48 44 52 00 00 00 05 00 00 00 03 08 02 00 00 00 d4 54 52 af
+            """
+
+    Scenario: Constructing a PNG header with 16 bits per channel and alpha
+        Given c ← canvas(5, 3)
+        When png ← canvas_to_png(c, 16 bits, alpha)
+        Then bytes 9-33 of png are
+            """
+            00 00 00 0d 49 48 44 52 00 00 00 05 00 00 00 03 10 06 00 00 00 0b a6 19 bb
+            """
+
+    Scenario: Constructing the PNG pixel data
+        Given c ← canvas(2, 1)
+        And c1 ← color(1.5, 0, 0)
+        And c2 ← color(0, 0.5, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And png ← canvas_to_png(c)
+        Then bytes 34-60 of png are
+            """
+            00 00 00 0f 49 44 41 54
+            78 9c 63 f8 cf c0 c0 d0 f0 1f 00 08 00 02 7f
+            9c 45 40 4e
+            """
+
+    Scenario: Writing the alpha channel of a canvas to PNG
+        Given c ← canvas(2, 1)
+        And c1 ← color(1, 1, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_alpha(c, 1, 0, 0.5)
+        And png ← canvas_to_png(c, 8 bits, alpha)
+        Then bytes 34-60 of png are
+            """
+            00 00 00 0f 49 44 41 54
+            78 9c 63 f8 0f 04 0c 0c 0c 0d 00 1a 6f 04 7d
+            af f6 77 74
+            """
+
+    Scenario: PNG files are terminated by an IEND chunk
+        Given c ← canvas(5, 3)
+        When png ← canvas_to_png(c)
+        Then png ends with
+            """
+            00 00 00 00 49 45 4e 44 ae 42 60 82
+            """
+
+    Scenario Outline: Reading a PNG file written by a canvas
+        Given c ← canvas(4, 3)
+        And c1 ← color(1, 0.2, 0.6)
+        And c2 ← color(0, 0.4, 0.8)
+        When every pixel of c is set to color(0.2, 0.2, 0.2)
+        And write_pixel(c, 3, 0, c1)
+        And write_pixel(c, 1, 2, c2)
+        And write_alpha(c, 1, 2, 0.6)
+        And png ← canvas_to_png(c, <depth> bits, alpha)
+        And image ← canvas_from_png(png)
+        Then image.width = 4
+        And image.height = 3
+        And pixel_at(image, 3, 0) = c1
+        And pixel_at(image, 1, 2) = c2
+        And alpha_at(image, 1, 2) = 0.6
+        And alpha_at(image, 0, 0) = 1
+
+        Examples:
+            | depth |
+            | 8     |
+            | 16    |
+
+    Scenario: Reading a grayscale PNG file compressed by another encoder
+        Given c1 ← color(0.04706, 0.04706, 0.04706)
+        When image ← load_canvas("heightmap.png")
+        Then image.width = 16
+        And image.height = 16
+        And pixel_at(image, 10, 8) = c1
+
+    Scenario: Constructing the PFM header
+        Given c ← canvas(5, 3)
+        When pfm ← canvas_to_pfm(c)
+        Then pfm starts with
+            """
+            PF
+            5 3
+            -1.0
+            """
+
+    Scenario: PFM rows are stored from bottom to top
+        Given c ← canvas(1, 2)
+        And c1 ← color(1, 0, 0)
+        When write_pixel(c, 0, 1, c1)
+        And pfm ← canvas_to_pfm(c)
+        Then bytes 13-24 of pfm are
+            """
+            00 00 80 3f 00 00 00 00 00 00 00 00
+            """
+
+    Scenario: Reading a PFM file keeps colors brighter than white
+        Given c ← canvas(3, 2)
+        And c1 ← color(1.5, 0, 0)
+        And c2 ← color(0, 20, 0.25)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 2, 1, c2)
+        And pfm ← canvas_to_pfm(c)
+        And image ← canvas_from_pfm(pfm)
+        Then image.width = 3
+        And image.height = 2
+        And pixel_at(image, 0, 0) = c1
+        And pixel_at(image, 2, 1) = c2
+
+    Scenario: Constructing the Radiance header
+        Given c ← canvas(5, 3)
+        When hdr ← canvas_to_hdr(c)
+        Then hdr starts with
+            """
+            #?RADIANCE
+            FORMAT=32-bit_rle_rgbe
+
+            -Y 3 +X 5
+            """
+
+    Scenario: Encoding colors with a shared exponent
+        Given c ← canvas(1, 1)
+        And c1 ← color(0, 20, 0.25)
+        When write_pixel(c, 0, 0, c1)
+        And hdr ← canvas_to_hdr(c)
+        Then hdr ends with
+            """
+            00 a0 02 85
+            """
+
+    Scenario: Reading a Radiance file keeps colors brighter than white
+        Given c ← canvas(3, 2)
+        And c1 ← color(1.5, 0, 0)
+        And c2 ← color(0, 20, 0.25)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 2, 1, c2)
+        And hdr ← canvas_to_hdr(c)
+        And image ← canvas_from_hdr(hdr)
+        Then image.width = 3
+        And image.height = 2
+        And pixel_at(image, 0, 0) = c1
+        And pixel_at(image, 2, 1) = c2
+
+    Scenario: Run length encoding Radiance scanlines
+        Given c ← canvas(40, 3)
+        And c1 ← color(0.5, 4, 0.125)
+        When every pixel of c is set to color(1, 0.5, 0.25)
+        And write_pixel(c, 7, 1, c1)
+        And hdr ← canvas_to_hdr(c)
+        And image ← canvas_from_hdr(hdr)
+        Then every pixel of image equals c
+
+    Scenario Outline: Reporting invalid float images
+        Then reading "<content>" as <format> fails with "<message>"
+
+        Examples:
+            | format | content                                           | message                            |
+            | pfm    | P6\n1 1\n255\n                                    | unknown pfm type 'P6'              |
+            | pfm    | PF\n2 2\n-1.0\n                                   | pfm file is truncated              |
+            | hdr    | P6\n1 1\n255\n                                    | not a radiance file                |
+            | hdr    | #?RADIANCE\n\n+Y 1 +X 1\n                         | unsupported resolution '+Y 1 +X 1' |
+            | hdr    | #?RADIANCE\n\n-Y 2 +X 1\n\0\0\0\0                 | radiance file is truncated         |
+            | hdr    | #?RADIANCE\n\n-Y 2 +X 100000000\n\0\0\0\0\0\0\0\0 | a 100000000x2 image is too large   |
+            | ppm    | P7\n1 1\n255\n                                    | unknown ppm type 'P7'              |
+            | ppm    | P3\n1 1\n255\n0 300 0\n                           | sample 300 exceeds maxval          |
+            | ppm    | P6\n2 2\n255\n\0\0\0                              | ppm file is truncated              |
+            | png    | P6\n1 1\n255\n                                    | not a png file                     |
+
+    Scenario: Constructing the binary PPM header
+        Given c ← canvas(5, 3)
+        When ppm ← canvas_to_binary_ppm(c, 8 bits)
+        Then ppm starts with
+            """
+            P6
+            5 3
+            255
+            """
+
+    Scenario: Constructing the binary PPM pixel data
+        Given c ← canvas(2, 1)
+        And c1 ← color(1.5, 0, 0)
+        And c2 ← color(0, 0.5, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And ppm ← canvas_to_binary_ppm(c, 8 bits)
+        Then bytes 12-17 of ppm are
+            """
+            ff 00 00 00 80 ff
+            """
+
+    Scenario: Constructing binary PPM pixel data with 16 bits per channel
+        Given c ← canvas(2, 1)
+        And c1 ← color(1.5, 0, 0)
+        And c2 ← color(0, 0.5, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And ppm ← canvas_to_binary_ppm(c, 16 bits)
+        Then ppm starts with
+            """
+            P6
+            2 1
+            65535
+            """
+        And bytes 14-25 of ppm are
+            """
+            ff ff 00 00 00 00 00 00 80 00 ff ff
+            """
+
+    Scenario: Reading an ascii PPM file
+        Given ppm ← a file containing:
+            """
+            P3
+            # created by hand
+            2 2
+            10
+            10 0 0  0 5 0
+            0 0 0   2 4 10
+            """
+        And c1 ← color(0, 0.5, 0)
+        And c2 ← color(0.2, 0.4, 1)
+        When image ← canvas_from_ppm(ppm)
+        Then image.width = 2
+        And image.height = 2
+        And pixel_at(image, 1, 0) = c1
+        And pixel_at(image, 1, 1) = c2
+
+    Scenario: Reading an ascii graymap
+        Given ppm ← a file containing:
+            """
+            P2
+            3 1
+            4
+            0 1 4
+            """
+        And c1 ← color(0.25, 0.25, 0.25)
+        And c2 ← color(1, 1, 1)
+        When image ← canvas_from_ppm(ppm)
+        Then pixel_at(image, 1, 0) = c1
+        And pixel_at(image, 2, 0) = c2
+
+    Scenario Outline: Reading binary PPM files
+        Given c ← canvas(4, 3)
+        And c1 ← color(1, 0.2, 0.6)
+        And c2 ← color(0, 0.4, 0.8)
+        When every pixel of c is set to color(0.2, 0.2, 0.2)
+        And write_pixel(c, 3, 0, c1)
+        And write_pixel(c, 1, 2, c2)
+        And ppm ← canvas_to_binary_ppm(c, <depth> bits)
+        And image ← canvas_from_ppm(ppm)
+        Then image.width = 4
+        And image.height = 3
+        And pixel_at(image, 3, 0) = c1
+        And pixel_at(image, 1, 2) = c2
+
+        Examples:
+            | depth |
+            | 8     |
+            | 16    |
@@ -0,0 +1,63 @@
+Feature: Comparing images
+
+    Scenario: Identical images do not differ
+        Given c ← canvas(10, 10)
+        And image ← canvas(10, 10)
+        When every pixel of c is set to color(0.2, 0.4, 0.6)
+        And every pixel of image is set to color(0.2, 0.4, 0.6)
+        Then rmse(c, image) = 0
+        And psnr(c, image) = infinity
+        And ssim(c, image) = 1
+
+    Scenario: The difference of two images
+        Given c ← canvas(2, 1)
+        And image ← canvas(2, 1)
+        And c1 ← color(1, 0.5, 0)
+        And c2 ← color(0.5, 0.5, 0.25)
+        And c3 ← color(0.5, 0, 0.25)
+        And black ← color(0, 0, 0)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(image, 0, 0, c2)
+        And c ← difference(c, image)
+        Then pixel_at(c, 0, 0) = c3
+        And pixel_at(c, 1, 0) = black
+
+    Scenario: The error of a single differing channel
+        Given c ← canvas(2, 1)
+        And image ← canvas(2, 1)
+        And c1 ← color(0.5, 0, 0)
+        When write_pixel(c, 1, 0, c1)
+        Then rmse(c, image) = 0.20412
+        And psnr(c, image) = 13.80211
+
+    Scenario: Inverting an image destroys its structure
+        Given c ← canvas(16, 16)
+        And image ← canvas(16, 16)
+        And c1 ← color(1, 1, 1)
+        When the left half of c is set to c1
+        And the right half of image is set to c1
+        Then ssim(c, image) < 0.1
+
+    Scenario: Blurring an image keeps most of its structure
+        Given c ← canvas(16, 16)
+        And image ← canvas(16, 16)
+        And c1 ← color(1, 1, 1)
+        And c2 ← color(0.8, 0.8, 0.8)
+        When the left half of c is set to c1
+        And the left half of image is set to c2
+        Then ssim(c, image) > 0.9
+
+    Scenario: A heatmap marks the largest difference white
+        Given c ← canvas(3, 1)
+        And image ← canvas(3, 1)
+        And c1 ← color(1, 0, 0)
+        And c2 ← color(0, 0.5, 0)
+        And c3 ← color(1, 1, 1)
+        And red ← color(1, 0, 0)
+        And black ← color(0, 0, 0)
+        When write_pixel(image, 0, 0, c1)
+        And write_pixel(image, 1, 0, c2)
+        And c ← heatmap(c, image)
+        Then pixel_at(c, 0, 0) = c3
+        And pixel_at(c, 1, 0) = red
+        And pixel_at(c, 2, 0) = black
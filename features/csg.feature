@@ -77,3 +77,17 @@ Feature: Constructive Solid Geometry (CSG)
         And xs[0].object = s1
         And xs[1].t = 6.5
         And xs[1].object = s2
+
+    Scenario: A CSG object of two instances of the same group
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And i2 ← instance of the member of i1
+        And set_transform(i2, translation(0, 0, 0.5))
+        And csg ← csg("difference", i1, i2)
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When xs ← local_intersect(csg, r)
+        Then xs.count = 2
+        And xs[0].t = 4
+        And xs[1].t = 4.5
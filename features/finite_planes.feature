@@ -0,0 +1,69 @@
+Feature: Disks, rectangles and annuli
+
+    Scenario Outline: A ray intersects a finite planar shape
+        Given shape ← <shape>
+        And direction ← normalize(<direction>)
+        And r ← ray(<origin>, direction)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = <count>
+
+        Examples:
+            | shape        | origin             | direction        | count |
+            | disk()       | point(0, 1, 0)     | vector(0, -1, 0) | 1     |
+            | disk()       | point(0.7, 1, 0.7) | vector(0, -1, 0) | 1     |
+            | disk()       | point(0.8, 1, 0.8) | vector(0, -1, 0) | 0     |
+            | disk()       | point(0, 0, -5)    | vector(0, 0, 1)  | 0     |
+            | rectangle()  | point(0.8, 1, 0.8) | vector(0, -1, 0) | 1     |
+            | rectangle()  | point(-1, -1, 1)   | vector(0, 1, 0)  | 1     |
+            | rectangle()  | point(1.1, 1, 0)   | vector(0, -1, 0) | 0     |
+            | rectangle()  | point(0, 1, -2)    | vector(0, -1, 1) | 1     |
+            | annulus(0.5) | point(0, 1, 0)     | vector(0, -1, 0) | 0     |
+            | annulus(0.5) | point(0, 1, 0.75)  | vector(0, -1, 0) | 1     |
+            | annulus(0.5) | point(-0.5, 1, 0)  | vector(0, -1, 0) | 1     |
+            | annulus(0.5) | point(0.8, 1, 0.8) | vector(0, -1, 0) | 0     |
+
+    Scenario: The hit on a disk is where the ray crosses the plane
+        Given shape ← disk()
+        And r ← ray(point(0.5, 2, 0), vector(0, -1, 0))
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 1
+        And xs[0].t = 2
+
+    Scenario Outline: The normal of a finite planar shape is constant
+        Given shape ← <shape>
+        When n ← local_normal_at(shape, <point>)
+        Then normalize(n) = vector(0, 1, 0)
+
+        Examples:
+            | shape        | point            |
+            | disk()       | point(0, 0, 0)   |
+            | rectangle()  | point(1, 0, -1)  |
+            | annulus(0.5) | point(0.6, 0, 0) |
+
+    Scenario Outline: Finite planar shapes have tight bounding boxes
+        Given shape ← <shape>
+        When box ← bounds_of(shape)
+        Then box.min = point(-1, 0, -1)
+        And box.max = point(1, 0, 1)
+
+        Examples:
+            | shape        |
+            | disk()       |
+            | rectangle()  |
+            | annulus(0.5) |
+
+    Scenario Outline: Texture coordinates on finite planar shapes
+        Given shape ← <shape>
+        When (u, v) ← uv_at(shape, <point>)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | shape        | point             | u    | v    |
+            | disk()       | point(0, 0, 0)    | 0.5  | 0.5  |
+            | disk()       | point(-1, 0, 0)   | 0    | 0.5  |
+            | rectangle()  | point(-1, 0, -1)  | 0    | 0    |
+            | rectangle()  | point(1, 0, 0.5)  | 1    | 0.75 |
+            | annulus(0.5) | point(0, 0, 0.5)  | 0.5  | 0    |
+            | annulus(0.5) | point(0, 0, 0.75) | 0.5  | 0.5  |
+            | annulus(0.5) | point(1, 0, 0)    | 0.25 | 1    |
@@ -0,0 +1,13 @@
+Feature: Golden images
+
+    # set UPDATE_GOLDEN=1 to replace the reference images after intended changes
+    Scenario Outline: Rendering reference scenes
+        Given w, c ← the scene file "scenes/<scene>.yml" at 80x45
+        When image ← render(c, w)
+        Then image matches "tests/golden/<scene>.ppm" with rmse below 0.01 and ssim above 0.98
+
+        Examples:
+            | scene      |
+            | cover      |
+            | csg        |
+            | refraction |
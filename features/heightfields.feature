@@ -0,0 +1,71 @@
+Feature: Heightfields
+
+    Scenario: A ray strikes a flat heightfield from above
+        Given shape ← heightfield(2, 2, 0.5, 0.5, 0.5, 0.5)
+        And r ← ray(point(0.3, 2, 0.6), vector(0, -1, 0))
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 1
+        And xs[0].t = 1.5
+
+    Scenario Outline: A ray misses a heightfield
+        Given shape ← heightfield(2, 2, 0.5, 0.5, 0.5, 0.5)
+        And r ← ray(<origin>, <direction>)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 0
+
+        Examples:
+            | origin               | direction          |
+            | point(1.5, 2, 0.5)   | vector(0, -1, 0)   |
+            | point(-1, 1, 0.5)    | vector(1, 0, 0)    |
+            | point(0.5, 0.2, -1)  | vector(0, 0.1, 1)  |
+
+    Scenario: A ray walks through the cells of a heightfield
+        Given shape ← heightfield(3, 3, 0, 0, 0, 0, 1, 0, 0, 0, 0)
+        And r ← ray(point(-1, 0.25, 0.3), vector(1, 0, 0))
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 2
+        And xs[0].t = 1.125
+        And xs[1].t = 1.675
+
+    Scenario Outline: The normal on a heightfield is interpolated
+        Given shape ← heightfield(<columns>, 2, <heights>)
+        And r ← ray(<origin>, vector(0, -1, 0))
+        When xs ← local_intersect(shape, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then comps.normalv = <normal>
+
+        Examples:
+            | columns | heights                 | origin               | normal                        |
+            | 2       | 0.5, 0.5, 0.5, 0.5      | point(0.3, 2, 0.6)   | vector(0, 1, 0)               |
+            | 2       | 0, 1, 0, 1              | point(0.3, 2, 0.6)   | vector(-0.70711, 0.70711, 0)  |
+            | 3       | 0, 0.5, 0, 0, 0.5, 0    | point(0.25, 2, 0.2)  | vector(-0.38268, 0.92388, 0)  |
+
+    Scenario Outline: A heightfield needs at least one cell and a height per sample
+        Then heightfield(<columns>, <rows>, <heights>) fails with "<error>"
+
+        Examples:
+            | columns | rows | heights       | error                                       |
+            | 0       | 2    | 0             | a heightfield needs at least 2 by 2 samples |
+            | 1       | 2    | 0, 0          | a heightfield needs at least 2 by 2 samples |
+            | 2       | 2    | 0, 0, 0       | expected 4 heights, got 3                   |
+
+    Scenario: The bounds of a heightfield
+        Given shape ← heightfield(3, 3, 0, 0, 0, 0, 1, 0, 0, 0.2, 0)
+        When box ← bounds_of(shape)
+        Then box.min = point(0, 0, 0)
+        And box.max = point(1, 1, 1)
+
+    Scenario: The first row of the heights is at the top of the texture
+        Given shape ← heightfield(2, 2, 0, 0, 0, 0)
+        When (u, v) ← uv_at(shape, point(0.25, 0, 0.2))
+        Then u = 0.25
+        And v = 0.8
+
+    Scenario: A heightfield from the brightness of a canvas
+        Given c ← canvas(3, 2)
+        And c1 ← color(1, 1, 1)
+        When write_pixel(c, 1, 0, c1)
+        And shape ← heightfield_from_canvas(c)
+        And box ← bounds_of(shape)
+        Then shape is a heightfield with 3 by 2 samples
+        And box.max = point(1, 1, 1)
@@ -0,0 +1,108 @@
+Feature: Instances
+
+    Scenario: Instances share the group they place
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And i2 ← instance of the member of i1
+        Then i1 and i2 share their group
+
+    Scenario: Intersecting a ray with a transformed instance
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, translation(5, 0, 0))
+        And r ← ray(point(5, 0, -5), vector(0, 0, 1))
+        When xs ← intersect(i1, r)
+        Then xs.count = 2
+        And xs[0].t = 4
+        And xs[1].t = 6
+        And xs[0] was found through i1
+
+    Scenario: The closest hit in a world remembers nested instances innermost first
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, translation(5, 0, 0))
+        And i2 ← instance(i1)
+        And set_transform(i2, translation(0, 5, 0))
+        And w ← world()
+        And i2 is added to w
+        And r ← ray(point(5, 5, -5), vector(0, 0, 1))
+        When i ← closest_hit(w, r)
+        Then i.t = 4
+        And i was found through i1 then i2
+
+    Scenario: Instances cannot be nested deeper than a hit remembers
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And i1 ← instance(i1) 7 times
+        Then instance(i1) is rejected
+
+    Scenario: A ray misses a transformed instance
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, translation(5, 0, 0))
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When xs ← intersect(i1, r)
+        Then xs.count = 0
+
+    Scenario: The transform of a group applies to the instances in it
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, translation(5, 0, 0))
+        And g2 ← group()
+        And set_transform(g2, scaling(2, 2, 2))
+        And add_child(g2, i1)
+        And r ← ray(point(10, 0, -5), vector(0, 0, 1))
+        When xs ← intersect(g2, r)
+        Then xs.count = 2
+        And xs[0].t = 3
+        And xs[1].t = 7
+
+    Scenario: The normal on a scaled instance
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, scaling(1, 0.5, 1))
+        And r ← ray(point(0, 0.25, -5), vector(0, 0, 1))
+        When xs ← intersect(i1, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then comps.normalv = vector(0, 0.75593, -0.65465)
+
+    Scenario: The bounds of a transformed instance
+        Given g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And i1 ← instance(g)
+        And set_transform(i1, translation(5, 0, 0))
+        When box ← bounds_of(i1)
+        Then box.min = point(4, -1, -1)
+        And box.max = point(6, 1, 1)
+
+    Scenario: The material of an instance replaces the material of its objects
+        Given w ← world()
+        And w.light ← point_light(point(-10, 10, -10), color(1, 1, 1))
+        And g ← group()
+        And s ← sphere()
+        And add_child(g, s)
+        And m ← material()
+        And m.ambient ← 1
+        And m.diffuse ← 0
+        And m.specular ← 0
+        And i1 ← instance(g)
+        And set_material(i1, m)
+        And i1 is added to w
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When c ← color_at(w, r)
+        Then c = color(1, 1, 1)
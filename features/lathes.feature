@@ -0,0 +1,61 @@
+Feature: Lathes
+
+    Scenario Outline: A ray strikes a lathe
+        Given shape ← lathe(<profile>)
+        And r ← ray(<origin>, <direction>)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = <count>
+        And xs[0].t = <t0>
+
+        Examples:
+            | profile                  | origin              | direction          | count | t0  |
+            | (1, 0), (1, 2)           | point(0, 1, -5)     | vector(0, 0, 1)    | 2     | 4   |
+            | (1, 0), (0, 1)           | point(0, 0.5, -5)   | vector(0, 0, 1)    | 2     | 4.5 |
+            | (0, 0), (1, 0)           | point(0.5, 5, 0)    | vector(0, -1, 0)   | 1     | 5   |
+            | (1, 0), (2, 1), (1, 2)   | point(0, 1.5, -5)   | vector(0, 0, 1)    | 2     | 3.5 |
+
+    Scenario Outline: A ray misses a lathe
+        Given shape ← lathe((1, 0), (1, 2))
+        And r ← ray(<origin>, <direction>)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 0
+
+        Examples:
+            | origin            | direction         |
+            | point(0, 3, -5)   | vector(0, 0, 1)   |
+            | point(0, -1, -5)  | vector(0, 0, 1)   |
+            | point(0, 1, 0)    | vector(0, 1, 0)   |
+            | point(2, 1, -5)   | vector(0, 0, 1)   |
+
+    Scenario Outline: The normal on a lathe
+        Given shape ← lathe(<profile>)
+        And r ← ray(<origin>, vector(0, 0, 1))
+        When xs ← local_intersect(shape, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then comps.normalv = <normal>
+
+        Examples:
+            | profile                  | origin            | normal                         |
+            | (1, 0), (1, 2)           | point(0, 1, -5)   | vector(0, 0, -1)               |
+            | (1, 0), (0, 1)           | point(0, 0.5, -5) | vector(0, 0.70711, -0.70711)   |
+            | (1, 0), (1.5, 1), (1, 2) | point(0, 1, -5)   | vector(0, 0.44721, -0.89443)   |
+
+    Scenario: The normals of a spline lathe follow the curve
+        Given shape ← spline_lathe(8, (1, 0), (1.5, 1), (1, 2))
+        And r ← ray(point(0, 1, -5), vector(0, 0, 1))
+        When xs ← local_intersect(shape, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then xs[0].t = 3.5
+        And comps.normalv = vector(0, 0, -1)
+
+    Scenario: The texture v runs along the profile
+        Given shape ← lathe((0, 0), (1, 0), (1, 2), (0, 2))
+        And r ← ray(point(0, 1, -5), vector(0, 0, 1))
+        When xs ← local_intersect(shape, r)
+        Then xs[0].v = 0.5
+
+    Scenario: The bounds of a lathe
+        Given shape ← lathe((0, 0), (2, 1), (1, 3))
+        When box ← bounds_of(shape)
+        Then box.min = point(-2, 0, -2)
+        And box.max = point(2, 3, 2)
@@ -0,0 +1,169 @@
+Feature: Meshes
+
+    Scenario: The faces of a group become a single mesh
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 2 0
+
+            f 1 2 3 4 5
+            """
+        When parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        Then g has 1 children
+        And t1 is a mesh with 3 faces
+
+    Scenario: The meshes of a file share their buffers
+        Given file ← the file "triangles.obj"
+        When parser ← parse_obj_file_as_meshes(file)
+        And g1 ← "FirstGroup" from parser
+        And g2 ← "SecondGroup" from parser
+        And t1 ← first child of g1
+        And t2 ← first child of g2
+        Then t1 is a mesh with 1 face
+        And t2 is a mesh with 1 face
+        And t1 and t2 share their vertices
+
+    Scenario Outline: Intersecting a ray with the faces of a mesh
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 2 0
+
+            f 1 2 3 4 5
+            """
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(<origin>, vector(0, 0, 1))
+        When xs ← local_intersect(t1, r)
+        Then xs.count = 1
+        And xs[0].t = 2
+        And xs[0].face = <face>
+
+        Examples:
+            | origin               | face |
+            | point(-0.5, 0.5, -2) | 0    |
+            | point(0.5, 0.75, -2) | 1    |
+            | point(0, 1.5, -2)    | 2    |
+
+    Scenario: A ray misses a mesh
+        Given file ← the file "triangles.obj"
+        And parser ← parse_obj_file_as_meshes(file)
+        And g1 ← "FirstGroup" from parser
+        And t1 ← first child of g1
+        And r ← ray(point(0, -0.5, -2), vector(0, 0, 1))
+        When xs ← local_intersect(t1, r)
+        Then xs.count = 0
+
+    Scenario Outline: The hierarchy of a large mesh finds every face
+        Given file ← a grid of 20 by 20 squares
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And direction ← normalize(<direction>)
+        And r ← ray(<origin>, direction)
+        When xs ← local_intersect(t1, r)
+        Then t1 is a mesh with 800 faces
+        And xs.count = <count>
+        And xs[0].t = <t>
+
+        Examples:
+            | origin               | direction         | count | t       |
+            | point(0.25, 1, 0.75) | vector(0, -1, 0)  | 1     | 1       |
+            | point(19.9, 2, 19.5) | vector(0, -1, 0)  | 1     | 2       |
+            | point(7.5, 1, 12.2)  | vector(0, -1, 0)  | 1     | 1       |
+            | point(0.3, 1, 0.1)   | vector(10, -1, 7) | 1     | 12.2474 |
+
+    Scenario: A ray outside of a large mesh misses it
+        Given file ← a grid of 20 by 20 squares
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(point(20.5, 1, 3), vector(0, -1, 0))
+        When xs ← local_intersect(t1, r)
+        Then xs.count = 0
+
+    Scenario: The nearest hits of a large mesh skip the faces behind them
+        Given file ← a stack of 40 squares
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(point(0.75, 50, 0.25), vector(0, -1, 0))
+        When xs ← local_intersect(t1, r)
+        Then xs.count = 40
+        When xs ← local_intersect_nearest(t1, r)
+        Then xs.count is at most 2
+        And the last of xs has t = 11
+
+    Scenario: The nearest hits of a mesh stay inside the interval of the ray
+        Given file ← a stack of 40 squares
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(point(0.75, 50, 0.25), vector(0, -1, 0))
+        And r ← between(r, 20.5, 30)
+        When xs ← local_intersect_nearest(t1, r)
+        Then the last of xs has t = 21
+
+    Scenario: Mesh faces without normals are flat
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            f 1 2 3
+            """
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(point(-0.5, 0.25, -2), vector(0, 0, 1))
+        When xs ← local_intersect(t1, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then comps.normalv = vector(0, 0, -1)
+
+    Scenario: Mesh faces interpolate the normals of their vertices
+        Given file ← a file containing:
+            """
+            v 0 1 0
+            v -1 0 0
+            v 1 0 0
+            vn -1 0 0
+            vn 1 0 0
+            vn 0 1 0
+            f 1//3 2//1 3//2
+            """
+        And parser ← parse_obj_file_as_meshes(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And r ← ray(point(-0.2, 0.5, -2), vector(0, 0, 1))
+        When xs ← local_intersect(t1, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then xs[0].u = 0.35
+        And xs[0].v = 0.15
+        And comps.normalv = vector(-0.37139, 0.92848, 0)
+
+    Scenario: Meshes keep the materials and texture coordinates of their faces
+        When parser ← load_obj_file_as_meshes("textured.obj")
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And t2 ← second child of g
+        And t3 ← third child of g
+        Then t1 is a mesh with 1 face
+        And t2 is a mesh with 1 face
+        When m ← t1.material
+        Then m.color = color(1, 0, 0)
+        When m ← t3.material
+        Then m = material()
+        Given r ← ray(point(0.6, 0.3, -1), vector(0, 0, 1))
+        When xs ← local_intersect(t2, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then comps.u = 0.6
+        And comps.v = 0.3
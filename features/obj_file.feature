@@ -132,3 +132,162 @@ Feature: OBJ File Parser
         And t1.n2 = parser.normals[1]
         And t1.n3 = parser.normals[2]
         And t2 = t1
+
+    Scenario: Vertices in scientific notation separated by tabs and spaces
+        Given file ← a file containing:
+            """
+            v	-1e0   1.0E+0	0
+            v  1.5e-1 -2.5E1  3
+            vn	0   0   1e0
+            """
+        When parser ← parse_obj_file(file)
+        Then parser should have ignored 0 lines
+        And parser.vertices[1] = point(-1, 1, 0)
+        And parser.vertices[2] = point(0.15, -25, 3)
+        And parser.normals[1] = vector(0, 0, 1)
+
+    Scenario: Negative indices count back from the last vertex
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            v 1 1 0
+            vn 0 0 1
+            vn 0 1 0
+            f -4 -3 -2
+            f -4//-2 -2//-1 -1//-1
+            """
+        When parser ← parse_obj_file(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And t2 ← second child of g
+        Then t1.p1 = parser.vertices[1]
+        And t1.p2 = parser.vertices[2]
+        And t1.p3 = parser.vertices[3]
+        And t2.p1 = parser.vertices[1]
+        And t2.p2 = parser.vertices[3]
+        And t2.p3 = parser.vertices[4]
+        And t2.n1 = parser.normals[1]
+        And t2.n2 = parser.normals[2]
+        And t2.n3 = parser.normals[2]
+
+    Scenario: Texture vertex records
+        Given file ← a file containing:
+            """
+            v 0 1 0
+            v -1 0 0
+            v 1 0 0
+            vt 0.5 1
+            vt 0 0 0
+            vt 1
+            f 1/1 2/2 3/3
+            """
+        When parser ← parse_obj_file(file)
+        And g ← parser.default_group
+        And t1 ← first child of g
+        Then parser should have ignored 0 lines
+        And parser.texture_vertices[1] = (0.5, 1)
+        And parser.texture_vertices[2] = (0, 0)
+        And parser.texture_vertices[3] = (1, 0)
+        And t1.p1 = parser.vertices[1]
+        And t1.p2 = parser.vertices[2]
+        And t1.p3 = parser.vertices[3]
+
+    Scenario: Objects are parsed like groups
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            v 1 1 0
+            o head.001
+            f 1 2 3
+            o Body
+            f 1 3 4
+            """
+        When parser ← parse_obj_file(file)
+        And g1 ← "head.001" from parser
+        And g2 ← "Body" from parser
+        And t1 ← first child of g1
+        And t2 ← first child of g2
+        Then parser should have ignored 0 lines
+        And t1.p1 = parser.vertices[1]
+        And t1.p3 = parser.vertices[3]
+        And t2.p2 = parser.vertices[3]
+        And t2.p3 = parser.vertices[4]
+
+    Scenario Outline: Reporting malformed records
+        Given file ← a file containing:
+            """
+            v -1 1 0
+            v -1 0 0
+            v 1 0 0
+            vn 0 0 1
+            """
+        And file ends with the line "<record>"
+        When parser ← parse_obj_file(file)
+        Then parsing the obj file failed with "<message>"
+
+        Examples:
+            | record      | message                                  |
+            | f 1 2 4     | line 5: vertex index 4 is out of range   |
+            | f 1 2 -4    | line 5: vertex index -4 is out of range  |
+            | f 0 1 2     | line 5: vertex index 0 is out of range   |
+            | f 1//2 2 3  | line 5: normal index 2 is out of range   |
+            | f 1 2       | line 5: a face needs at least 3 vertices |
+            | f 1 x 3     | line 5: invalid index 'x'                |
+            | f 1/a/1 2 3 | line 5: invalid index 'a'                |
+            | v 1 2 three | line 5: invalid number 'three'           |
+            | vn 1 2      | line 5: expected 3 coordinates           |
+            | g           | line 5: missing group name               |
+
+    Scenario: Material library records
+        Given mtl ← a file containing:
+            """
+            # glass with a faint blue tint
+            newmtl glass
+            Kd 0.8 0.9 1
+            Ka 0 0 0
+            Ks 1 1 1
+            Ns 300
+            d 0.25
+            Ni 1.5
+            illum 7
+            """
+        When m ← material "glass" from parse_mtl_file(mtl)
+        Then m.color = color(0.8, 0.9, 1)
+        And m.ambient = 0
+        And m.diffuse = 0.9
+        And m.specular = 0.9
+        And m.shininess = 300
+        And m.transparency = 0.75
+        And m.refractive_index = 1.5
+        And m.reflective = 1
+
+    Scenario: Material records before the first material are rejected
+        Given mtl ← a file containing:
+            """
+            Kd 1 0 0
+            """
+        Then parse_mtl_file(mtl) fails with "line 1: 'Kd' before the first newmtl"
+
+    Scenario: Faces use the materials of their library
+        When parser ← load_obj_file("textured.obj")
+        And g ← parser.default_group
+        And t1 ← first child of g
+        And t2 ← second child of g
+        And t3 ← third child of g
+        Then parser.material_libraries = textured.mtl, missing.mtl
+        And parser should have ignored 2 lines
+        And parser warned "line 1: skipped material library ./features/missing.mtl"
+        When m ← t1.material
+        Then m.color = color(1, 0, 0)
+        And m.ambient = 0.05
+        And m.specular = 0
+        And m.shininess = 10
+        When m ← t3.material
+        Then m = material()
+        And the texture of t2 at hit_uv(0.25, 0.25) = color(1, 1, 1)
+        And the texture of t2 at hit_uv(0.75, 0.25) = color(0, 0, 0)
+        And the texture of t2 at hit_uv(0.25, 0.75) = color(0, 0, 0)
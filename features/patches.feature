@@ -0,0 +1,186 @@
+Feature: Bézier patches
+
+    Scenario: Points and normals of a patch
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            1 0 0
+            2 0 0
+            3 0 0
+            0 0 1
+            1 1 1
+            2 1 1
+            3 0 1
+            0 0 2
+            1 1 2
+            2 1 2
+            3 0 2
+            0 0 3
+            1 0 3
+            2 0 3
+            3 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        Then point_at(patches[0], 0, 0) = point(0, 0, 0)
+        And point_at(patches[0], 1, 1) = point(3, 0, 3)
+        And point_at(patches[0], 0.5, 0.5) = point(1.5, 0.5625, 1.5)
+        And normal_at(patches[0], 0.5, 0.5) = vector(0, -1, 0)
+        And normal_at(patches[0], 0, 0.5) = vector(0.6, -0.8, 0)
+
+    Scenario: The normal where a side of a patch collapses into a point
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            0 0 0
+            0 0 0
+            0 0 0
+            -1.5 0 1
+            -0.5 0 1
+            0.5 0 1
+            1.5 0 1
+            -3 0 2
+            -1 0 2
+            1 0 2
+            3 0 2
+            -4.5 0 3
+            -1.5 0 3
+            1.5 0 3
+            4.5 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        Then point_at(patches[0], 0.5, 0) = point(0, 0, 0)
+        And normal_at(patches[0], 0.5, 0) = vector(0, -1, 0)
+        And normal_at(patches[0], 0, 0) = vector(0, -1, 0)
+        And normal_at(patches[0], 0.5, 0.5) = vector(0, -1, 0)
+
+    Scenario Outline: A flatter tessellation has fewer faces
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            1 0 0
+            2 0 0
+            3 0 0
+            0 0 1
+            1 1 1
+            2 1 1
+            3 0 1
+            0 0 2
+            1 1 2
+            2 1 2
+            3 0 2
+            0 0 3
+            1 0 3
+            2 0 3
+            3 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        And shape ← mesh(tessellate(patches, <tolerance>))
+        Then shape has <faces> faces
+
+        Examples:
+            | tolerance | faces |
+            | 10        | 4     |
+            | 0.1       | 20    |
+            | 0.01      | 290   |
+
+    Scenario: A flat patch needs only a few faces
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            0 0 0
+            0 0 0
+            0 0 0
+            -1.5 0 1
+            -0.5 0 1
+            0.5 0 1
+            1.5 0 1
+            -3 0 2
+            -1 0 2
+            1 0 2
+            3 0 2
+            -4.5 0 3
+            -1.5 0 3
+            1.5 0 3
+            4.5 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        And shape ← mesh(tessellate(patches, 0.01))
+        Then shape has 3 faces
+
+    Scenario: Patches sharing a curved side leave no cracks
+        Given file ← a file containing:
+            """
+            2
+            1,2,3,4,8,9,10,11,15,16,17,18,22,23,24,25
+            4,5,6,7,11,12,13,14,18,19,20,21,25,26,27,28
+            28
+            0,0,0
+            1,0,0
+            2,0,0
+            3,0,0
+            4,0,0
+            5,0,0
+            6,0,0
+            0,0,1
+            1,1,1
+            2,1,1
+            3,1,1
+            4,0,1
+            5,0,1
+            6,0,1
+            0,0,2
+            1,1,2
+            2,1,2
+            3,1,2
+            4,0,2
+            5,0,2
+            6,0,2
+            0,0,3
+            1,0,3
+            2,0,3
+            3,0,3
+            4,0,3
+            5,0,3
+            6,0,3
+            """
+        When patches ← parse_bezier_file(file)
+        And shape ← mesh(tessellate(patches, 0.001))
+        Then shape has 6 open edges
+
+    Scenario: A tessellated patch is shaded with the normals of the patch
+        Given file ← a file containing:
+            """
+            1
+            3 3
+            0 0 0
+            1 0 0
+            2 0 0
+            3 0 0
+            0 0 1
+            1 1 1
+            2 1 1
+            3 0 1
+            0 0 2
+            1 1 2
+            2 1 2
+            3 0 2
+            0 0 3
+            1 0 3
+            2 0 3
+            3 0 3
+            """
+        When patches ← parse_bezier_file(file)
+        And shape ← mesh(tessellate(patches, 0.012))
+        And r ← ray(point(1.5, 5, 1.5), vector(0, -1, 0))
+        And xs ← local_intersect(shape, r)
+        And comps ← prepare_computations(xs[0], r, xs)
+        Then xs[0].t = 4.4375
+        And comps.normalv = vector(0, 1, 0)
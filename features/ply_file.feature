@@ -0,0 +1,120 @@
+Feature: PLY File Parser
+
+    Scenario: Parsing an ascii file with normals
+        Given ply ← a file containing:
+            """
+            ply
+            format ascii 1.0
+            comment a square in the xy plane
+            element vertex 4
+            property float x
+            property float y
+            property float z
+            property float nx
+            property float ny
+            property float nz
+            element face 1
+            property list uchar int vertex_indices
+            element edge 1
+            property int vertex1
+            property int vertex2
+            end_header
+            -1 1 0 0 0 -1
+            -1 0 0 0 0 -1
+            1 0 0 0 0 -1
+            1 1 0   0 0 -1
+            4 0 1 2 3
+            0 2
+            """
+        When g ← parse_ply_file(ply)
+        And t1 ← first child of g
+        And t2 ← second child of g
+        Then g has 2 children
+        And t1 is a smooth triangle
+        And t1.p1 = point(-1, 1, 0)
+        And t1.p2 = point(-1, 0, 0)
+        And t1.p3 = point(1, 0, 0)
+        And t1.n1 = vector(0, 0, -1)
+        And t2.p1 = point(-1, 1, 0)
+        And t2.p2 = point(1, 0, 0)
+        And t2.p3 = point(1, 1, 0)
+
+    Scenario: Parsing a binary file with vertex colors
+        Given ply ← a file containing:
+            """
+            ply
+            format binary_little_endian 1.0
+            element vertex 3
+            property float x
+            property float y
+            property float z
+            property uchar red
+            property uchar green
+            property uchar blue
+            element face 1
+            property list uchar int vertex_index
+            end_header
+            """
+        And ply continues with the bytes:
+            """
+            00 00 00 00  00 00 80 3f  00 00 00 00  ff 00 00
+            00 00 80 bf  00 00 00 00  00 00 00 00  00 ff 00
+            00 00 80 3f  00 00 00 00  00 00 00 00  00 00 ff
+            03  00 00 00 00  01 00 00 00  02 00 00 00
+            """
+        When g ← parse_ply_file(ply)
+        And t1 ← first child of g
+        Then g has 1 children
+        And t1 is a triangle
+        And t1.p1 = point(0, 1, 0)
+        And t1.p2 = point(-1, 0, 0)
+        And t1.p3 = point(1, 0, 0)
+        And t1.material.color = color(0.33333, 0.33333, 0.33333)
+
+    Scenario: Reporting a face with an unknown vertex
+        Given ply ← a file containing:
+            """
+            ply
+            format ascii 1.0
+            element vertex 3
+            property float x
+            property float y
+            property float z
+            element face 1
+            property list uchar int vertex_indices
+            end_header
+            0 1 0
+            -1 0 0
+            1 0 0
+            3 0 1 3
+            """
+        When g ← parse_ply_file(ply)
+        Then parsing the ply file failed with "vertex index 3 is out of range"
+
+    Scenario: Reporting a truncated binary file
+        Given ply ← a file containing:
+            """
+            ply
+            format binary_little_endian 1.0
+            element vertex 1
+            property float x
+            property float y
+            property float z
+            end_header
+            """
+        And ply continues with the bytes:
+            """
+            00 00 00 00  00 00 80 3f
+            """
+        When g ← parse_ply_file(ply)
+        Then parsing the ply file failed with "ply file is truncated"
+
+    Scenario: Big endian files are not supported
+        Given ply ← a file containing:
+            """
+            ply
+            format binary_big_endian 1.0
+            end_header
+            """
+        When g ← parse_ply_file(ply)
+        Then parsing the ply file failed with "unsupported format 'binary_big_endian'"
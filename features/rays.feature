@@ -27,3 +27,16 @@ Feature: Rays
         When r2 ← transform(r, m)
         Then r2.origin = point(2, 6, 12)
         And r2.direction = vector(0, 3, 0)
+
+    Scenario: A ray covers the whole line
+        Given r ← ray(point(1, 2, 3), vector(0, 1, 0))
+        Then r.t_min = -infinity
+        And r.t_max = infinity
+
+    Scenario: Transforming a ray keeps its interval
+        Given r ← ray(point(1, 2, 3), vector(0, 1, 0))
+        And r ← between(r, 1, 10)
+        And m ← scaling(2, 3, 4)
+        When r2 ← transform(r, m)
+        Then r2.t_min = 1
+        And r2.t_max = 10
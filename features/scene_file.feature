@@ -0,0 +1,356 @@
+Feature: Scene Files
+
+    Scenario: Adding a camera
+        Given scene ← a file containing:
+            """
+            - add: camera
+              width: 100
+              height: 50
+              field-of-view: 0.785
+              from: [ 0, 1.5, -5 ]
+              to: [ 0, 1, 0 ]
+              up: [ 0, 1, 0 ]
+            """
+        When w ← parse_scene_file(scene)
+        Then c.hsize = 100
+        And c.vsize = 50
+        And c.transform = view_transform(point(0, 1.5, -5), point(0, 1, 0), vector(0, 1, 0))
+
+    Scenario: Adding a light
+        Given scene ← a file containing:
+            """
+            - add: camera
+              width: 10
+              height: 10
+              field-of-view: 1
+              from: [ 0, 0, -5 ]
+              to: [ 0, 0, 0 ]
+              up: [ 0, 1, 0 ]
+            - add: light
+              at: [ 50, 100, -50 ]
+              intensity: [ 1, 0.5, 1 ]
+            """
+        When w ← parse_scene_file(scene)
+        Then w.light = point_light(point(50, 100, -50), color(1, 0.5, 1))
+
+    Scenario: Adding a shape with a material and transformations
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: sphere
+              material:
+                color: [ 1, 0.2, 1 ]
+                diffuse: 0.7
+              transform:
+                - [ scale, 0.5, 0.5, 0.5 ]
+                - [ translate, 1, 2, 3 ]
+            """
+        When w ← parse_scene_file(scene)
+        Then w contains 1 objects
+        And object 1 of w is a sphere
+        And object 1 of w has material.diffuse = 0.7
+        And object 1 of w has material.color = color(1, 0.2, 1)
+        And object 1 of w has transform translation(1, 2, 3) * scaling(0.5, 0.5, 0.5)
+
+    Scenario: Cylinders and cones can be truncated and closed
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: cylinder
+              min: 0
+              max: 1
+              closed: true
+            - add: cone
+              min: -1
+              max: 0
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a cylinder(0, 1, true)
+        And object 2 of w is a cone(-1, 0, false)
+
+    Scenario: Defining a torus
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: torus
+              major: 2
+              minor: 0.5
+            - add: torus
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a torus(2, 0.5)
+        And object 2 of w is a torus(1, 0.25)
+
+    Scenario: Defining a lathe
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: lathe
+              profile: [[0, 0], [1, 0], [1, 2], [0, 2]]
+            - add: lathe
+              profile: [[1, 0], [1.5, 1], [1, 2]]
+              spline: true
+              steps: 4
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a lathe with 3 segments
+        And object 2 of w is a lathe with 8 segments
+
+    Scenario: Defining a heightfield
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: heightfield
+              file: heightmap.png
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a heightfield with 16 by 16 samples
+
+    Scenario: Defining a surface of Bézier patches
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: bezier
+              file: bump.bpt
+            - add: bezier
+              file: bump.bpt
+              tolerance: 0.1
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a mesh with 290 faces
+        And object 2 of w is a mesh with 20 faces
+
+    Scenario: Defining a signed distance field
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: sdf
+              distance:
+                smooth-union:
+                  - { sphere: 1 }
+                  - translate: [2, 0, 0]
+                    of: { twist: 0.5, of: { box: [1, 2, 1] } }
+                k: 0.25
+            - add: sdf
+              distance:
+                repeat: [2, 0, 2]
+                count: [1, 0, 1]
+                of:
+                  subtraction: [ { cylinder: [1, 1] }, { torus: [1, 0.5] }, { sphere: 0.5 } ]
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is an sdf smooth_union(sphere(1), translate(twist(box(1, 2, 1), 0.5), 2, 0, 0), 0.25)
+        And object 2 of w is an sdf repeat(subtraction(subtraction(cylinder(1, 1), torus(1, 0.5)), sphere(0.5)), 2, 0, 2, 1, 0, 1)
+
+    Scenario: Defining finite planar shapes
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: disk
+            - add: rectangle
+            - add: annulus
+              inner: 0.25
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a disk
+        And object 2 of w is a rectangle
+        And object 3 of w is an annulus(0.25)
+
+    Scenario: Defining and extending materials
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - define: white-material
+              value:
+                color: [ 1, 1, 1 ]
+                diffuse: 0.7
+                ambient: 0.2
+            - define: blue-material
+              extend: white-material
+              value:
+                color: [ 0.5, 0.8, 0.9 ]
+            - add: cube
+              material: blue-material
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a cube
+        And object 1 of w has material.color = color(0.5, 0.8, 0.9)
+        And object 1 of w has material.diffuse = 0.7
+        And object 1 of w has material.ambient = 0.2
+
+    Scenario: Reusing defined transformations
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - define: standard-transform
+              value:
+                - [ translate, 1, 2, 3 ]
+            - add: plane
+              transform:
+                - [ scale, 2, 2, 2 ]
+                - standard-transform
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a plane
+        And object 1 of w has transform translation(1, 2, 3) * scaling(2, 2, 2)
+
+    Scenario: Groups pass their material on to their children
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: group
+              material:
+                ambient: 0.4
+              transform:
+                - [ translate, 0, 1, 0 ]
+              children:
+                - add: sphere
+                - add: cube
+                  material:
+                    ambient: 0.6
+            """
+        When w ← parse_scene_file(scene)
+        Then object 1 of w is a group with 2 children
+        And child 1 of object 1 of w has material.ambient = 0.4
+        And child 2 of object 1 of w has material.ambient = 0.6
+        And child 1 of object 1 of w has transform translation(0, 1, 0) * scaling(1, 1, 1)
+
+    Scenario: Reporting an unknown shape
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+
+            - add: sphre
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 3: unknown shape 'sphre'"
+
+    Scenario: Reporting an empty image size
+        Given scene ← a file containing:
+            """
+            - add: sphere
+            - { add: camera, width: 0, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 2: '0' is not a positive integer"
+
+    Scenario: Reporting an invalid number
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: sphere
+              material:
+                color: [ 1, 0.2, 1 ]
+                diffuse: lots
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 5: 'lots' is not a number"
+
+    Scenario: Reporting a transformation without an inverse
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: group
+              transform:
+                - [ translate, 0, 1, 0 ]
+                - [ scale, 0, 1, 1 ]
+              children:
+                - add: sphere
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 5: transformation is not invertible"
+
+    Scenario: Reporting an undefined material
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: sphere
+              material: glass
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 3: 'glass' is not defined"
+
+    Scenario: Reporting an annulus without a hole
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: annulus
+              inner: 1
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 3: inner radius must be at least 0 and less than 1"
+
+    Scenario: Reporting a torus with a tube wider than its ring
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: torus
+              major: 1
+              minor: 1.5
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 4: minor radius must be positive and at most the major radius"
+
+    Scenario: Reporting a heightfield without an image
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: heightfield
+              file: heightmap.gif
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 3: could not read ./features/heightmap.gif: unsupported image format 'gif'"
+
+    Scenario: Reporting a lathe profile with a negative radius
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: lathe
+              profile:
+                - [1, 0]
+                - [-1, 2]
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 5: radius must not be negative"
+
+    Scenario: Reporting an unknown distance function
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: sdf
+              distance:
+                union:
+                  - { sphere: 1 }
+                  - { cone: 1 }
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 6: unknown distance function 'cone'"
+
+    Scenario: Reporting a Bézier surface without a tolerance
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: bezier
+              file: bump.bpt
+              tolerance: 0
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 2: tolerance must be positive"
+
+    Scenario: Reporting inconsistent indentation
+        Given scene ← a file containing:
+            """
+            - add: light
+              at: [ 50, 100, -50 ]
+                intensity: [ 1, 1, 1 ]
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "line 3: unexpected indentation"
+
+    Scenario: A scene needs a camera
+        Given scene ← a file containing:
+            """
+            - add: sphere
+            """
+        When w ← parse_scene_file(scene)
+        Then parsing the scene failed with "scene does not add a camera"
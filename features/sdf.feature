@@ -0,0 +1,92 @@
+Feature: Signed distance fields
+
+    Scenario Outline: A ray strikes a distance field
+        Given shape ← sdf(<sdf>)
+        And r ← ray(<origin>, <direction>)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = <count>
+        And xs[0].t = <t0>
+        And xs[1].t = <t1>
+
+        Examples:
+            | sdf                                         | origin             | direction         | count | t0      | t1      |
+            | sphere(1)                                   | point(0, 0, -5)    | vector(0, 0, 1)   | 2     | 4       | 6       |
+            | box(1, 1, 1)                                | point(5, 0.5, 0)   | vector(-1, 0, 0)  | 2     | 4       | 6       |
+            | torus(1, 0.25)                              | point(0, 0, -5)    | vector(0, 0, 1)   | 4     | 3.75    | 4.25    |
+            | cylinder(1, 1)                              | point(0, 5, 0.5)   | vector(0, -1, 0)  | 2     | 4       | 6       |
+            | scale(sphere(1), 2)                         | point(0, 0, -5)    | vector(0, 0, 1)   | 2     | 3       | 7       |
+            | translate(sphere(1), 0, 0, 2)               | point(0, 0, -5)    | vector(0, 0, 2)   | 2     | 3       | 4       |
+            | union(sphere(1), translate(sphere(1), 3, 0, 0)) | point(3, 0, -5) | vector(0, 0, 1)  | 2     | 4       | 6       |
+            | subtraction(box(1, 1, 1), sphere(1.2))      | point(0.9, 0.9, -5) | vector(0, 0, 1)  | 2     | 4       | 6       |
+            | twist(box(1, 2, 0.25), 0.7853982)           | point(0.5, 1, -5)  | vector(0, 0, 1)   | 2     | 5.14645 | 5.85355 |
+            | bend(box(2, 0.1, 1), 0.5)                   | point(1, 5, 0)     | vector(0, -1, 0)  | 2     | 4.33975 | 4.56764 |
+            | repeat(sphere(0.5), 2, 0, 0, 1, 0, 0)       | point(-5, 0, 0)    | vector(1, 0, 0)   | 6     | 2.5     | 3.5     |
+
+    Scenario Outline: A ray misses a distance field
+        Given shape ← sdf(<sdf>)
+        And r ← ray(<origin>, <direction>)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 0
+
+        Examples:
+            | sdf                                         | origin              | direction        |
+            | sphere(1)                                   | point(0, 2, -5)     | vector(0, 0, 1)  |
+            | subtraction(box(1, 1, 1), sphere(1.2))      | point(0, 0, -5)     | vector(0, 0, 1)  |
+            | union(sphere(1), translate(sphere(1), 2.2, 0, 0)) | point(1.1, 0, -5) | vector(0, 0, 1) |
+            | repeat(sphere(0.5), 2, 0, 0, 1, 0, 0)       | point(4, 5, 0)      | vector(0, -1, 0) |
+
+    Scenario: A smooth union fills the gap between two shapes
+        Given shape ← sdf(smooth_union(sphere(1), translate(sphere(1), 2.2, 0, 0), 0.5))
+        And r ← ray(point(1.1, 0, -5), vector(0, 0, 1))
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 2
+
+    Scenario Outline: The normal of a distance field is its gradient
+        Given shape ← sdf(<sdf>)
+        When n ← local_normal_at(shape, <point>)
+        Then n = <normal>
+
+        Examples:
+            | sdf                           | point                        | normal                        |
+            | sphere(1)                     | point(0.70711, 0.70711, 0)   | vector(0.70711, 0.70711, 0)   |
+            | box(1, 1, 1)                  | point(1, 0.5, -0.2)          | vector(1, 0, 0)               |
+            | torus(1, 0.25)                | point(0, 0.25, 1)            | vector(0, 1, 0)               |
+            | translate(sphere(1), 0, 2, 0) | point(0, 1, 0)               | vector(0, -1, 0)              |
+
+    Scenario Outline: The bounds of a distance field
+        Given shape ← sdf(<sdf>)
+        When box ← bounds_of(shape)
+        Then box.min = <min>
+        And box.max = <max>
+
+        Examples:
+            | sdf                                                       | min                         | max                      |
+            | smooth_union(sphere(1), translate(sphere(1), 2, 0, 0), 0.5) | point(-1.5, -1.5, -1.5)   | point(3.5, 1.5, 1.5)     |
+            | smooth_subtraction(box(1, 1, 1), sphere(1.2), 0.5)        | point(-1, -1, -1)           | point(1, 1, 1)           |
+            | twist(box(1, 2, 1), 1)                                    | point(-1.41421, -2, -1.41421) | point(1.41421, 2, 1.41421) |
+            | bend(box(1, 0.5, 1), 1)                                   | point(-1.11803, -1.11803, -1) | point(1.11803, 1.11803, 1) |
+            | repeat(sphere(0.5), 2, 0, 0, 1, 0, 0)                     | point(-2.5, -0.5, -0.5)     | point(2.5, 0.5, 0.5)     |
+
+    Scenario: A distance field in a CSG difference
+        Given s1 ← sdf(box(1, 1, 1))
+        And s2 ← sphere()
+        And set_transform(s2, translation(0, 0, -1))
+        And csg ← csg("difference", s1, s2)
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When xs ← local_intersect(csg, r)
+        Then xs.count = 2
+        And xs[0].t = 5
+        And xs[0].object = s2
+        And xs[1].t = 6
+        And xs[1].object = s1
+
+    Scenario: A distance field in a transformed group
+        Given g ← group()
+        And set_transform(g, scaling(2, 2, 2))
+        And s ← sdf(translate(sphere(1), 5, 0, 0))
+        And add_child(g, s)
+        When r ← ray(point(10, 0, -10), vector(0, 0, 1))
+        And xs ← intersect(g, r)
+        Then xs.count = 2
+        And xs[0].t = 8
+        And xs[1].t = 12
@@ -30,6 +30,17 @@ Feature: Spheres
         And xs[0] = -1.0
         And xs[1] = 1.0
 
+    Scenario: Intersecting into a buffer keeps what it already holds
+        Given r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        And s ← sphere()
+        When xs ← intersect(s, r)
+        And xs ← intersect_into(s, r, xs)
+        Then xs.count = 4
+        And xs[0] = 4.0
+        And xs[1] = 6.0
+        And xs[2] = 4.0
+        And xs[3] = 6.0
+
     Scenario: A sphere is behind a ray
         Given r ← ray(point(0, 0, 5), vector(0, 0, 1))
         And s ← sphere()
@@ -0,0 +1,110 @@
+Feature: STL Files
+
+    Scenario: Parsing an ascii file
+        Given stl ← a file containing:
+            """
+            solid square
+              facet normal 0 0 -1
+                outer loop
+                  vertex -1 1 0
+                  vertex -1 0 0
+                  vertex 1 0 0
+                endloop
+              endfacet
+              facet normal 0 0 -1
+                outer loop
+                  vertex -1 1 0
+                  vertex 1.0e0 0 0
+                  vertex 1 1 0
+                endloop
+              endfacet
+            endsolid square
+            """
+        When g ← parse_stl_file(stl)
+        And t1 ← first child of g
+        And t2 ← second child of g
+        Then g has 2 children
+        And t1 is a triangle
+        And t1.p1 = point(-1, 1, 0)
+        And t1.p2 = point(-1, 0, 0)
+        And t1.p3 = point(1, 0, 0)
+        And t2.p1 = point(-1, 1, 0)
+        And t2.p2 = point(1, 0, 0)
+        And t2.p3 = point(1, 1, 0)
+
+    Scenario: Parsing a binary file
+        Given stl ← an 80 byte header followed by the bytes:
+            """
+            01 00 00 00
+            00 00 00 00  00 00 00 00  00 00 80 3f
+            00 00 00 00  00 00 80 3f  00 00 00 00
+            00 00 80 bf  00 00 00 00  00 00 00 00
+            00 00 80 3f  00 00 00 00  00 00 00 00
+            00 00
+            """
+        When g ← parse_stl_file(stl)
+        And t1 ← first child of g
+        Then g has 1 children
+        And t1.p1 = point(0, 1, 0)
+        And t1.p2 = point(-1, 0, 0)
+        And t1.p3 = point(1, 0, 0)
+
+    Scenario: Reporting a facet with missing vertices
+        Given stl ← a file containing:
+            """
+            solid broken
+              facet normal 0 0 1
+                outer loop
+                  vertex 0 1 0
+                  vertex -1 0 0
+                endloop
+              endfacet
+            endsolid broken
+            """
+        When g ← parse_stl_file(stl)
+        Then parsing the stl file failed with "line 7: a facet needs 3 vertices"
+
+    Scenario: Reporting a truncated binary file
+        Given stl ← an 80 byte header followed by the bytes:
+            """
+            02 00 00 00
+            00 00 00 00  00 00 00 00  00 00 80 3f
+            """
+        When g ← parse_stl_file(stl)
+        Then parsing the stl file failed with "stl file is truncated"
+
+    Scenario: Writing the triangles of a group as ascii
+        Given s ← triangle(point(0, 1, 0), point(-1, 0, 0), point(1, 0, 0))
+        And g ← group()
+        And set_transform(g, scaling(2, 2, 2))
+        And add_child(g, s)
+        When stl ← write_stl_ascii(g)
+        Then stl starts with
+            """
+            solid test
+              facet normal 0 0 1
+                outer loop
+                  vertex 0 2 0
+                  vertex -2 0 0
+                  vertex 2 0 0
+                endloop
+              endfacet
+            endsolid test
+            """
+
+    Scenario: Binary files written from a group can be read back
+        Given s ← triangle(point(0, 1, 0), point(-1, 0, 0), point(1, 0, 0))
+        And g ← group()
+        And set_transform(g, scaling(2, 2, 2))
+        And add_child(g, s)
+        When stl ← write_stl(g)
+        And g ← parse_stl_file(stl)
+        And t1 ← first child of g
+        Then g has 1 children
+        And bytes 81-84 of stl are
+            """
+            01 00 00 00
+            """
+        And t1.p1 = point(0, 2, 0)
+        And t1.p2 = point(-2, 0, 0)
+        And t1.p3 = point(2, 0, 0)
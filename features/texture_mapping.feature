@@ -0,0 +1,274 @@
+Feature: Texture Mapping
+
+    Scenario Outline: Sampling an image with the nearest texel
+        Given c ← canvas(2, 2)
+        And c1 ← color(1, 0, 0)
+        And c2 ← color(0, 1, 0)
+        And c3 ← color(0, 0, 1)
+        And white ← color(1, 1, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And write_pixel(c, 0, 1, c3)
+        And write_pixel(c, 1, 1, white)
+        And pattern ← image_pattern(c, nearest, <wrap>)
+        Then uv_pattern_at(pattern, <u>, <v>) = <color>
+
+        Examples:
+            | wrap   | u     | v    | color          |
+            | repeat | 0.25  | 0.75 | color(1, 0, 0) |
+            | repeat | 0.75  | 0.75 | color(0, 1, 0) |
+            | repeat | 0.25  | 0.25 | color(0, 0, 1) |
+            | repeat | 0.75  | 0.25 | color(1, 1, 1) |
+            | repeat | 1.25  | 0.75 | color(1, 0, 0) |
+            | repeat | -0.25 | 0.25 | color(1, 1, 1) |
+            | clamp  | 1.25  | 0.75 | color(0, 1, 0) |
+            | clamp  | -0.25 | 0.25 | color(0, 0, 1) |
+
+    Scenario Outline: Sampling an image with bilinear filtering
+        Given c ← canvas(2, 2)
+        And c1 ← color(1, 0, 0)
+        And c2 ← color(0, 1, 0)
+        And c3 ← color(0, 0, 1)
+        And white ← color(1, 1, 1)
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And write_pixel(c, 0, 1, c3)
+        And write_pixel(c, 1, 1, white)
+        And pattern ← image_pattern(c, bilinear, <wrap>)
+        Then uv_pattern_at(pattern, <u>, <v>) = <color>
+
+        Examples:
+            | wrap   | u    | v    | color                |
+            | repeat | 0.25 | 0.75 | color(1, 0, 0)       |
+            | repeat | 0.5  | 0.75 | color(0.5, 0.5, 0)   |
+            | repeat | 0.5  | 0.5  | color(0.5, 0.5, 0.5) |
+            | repeat | 0    | 0.75 | color(0.5, 0.5, 0)   |
+            | clamp  | 0    | 0.75 | color(1, 0, 0)       |
+
+    Scenario Outline: Using a spherical mapping on a 3D point
+        Given p ← <point>
+        When (u, v) ← spherical_map(p)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | point                | u    | v    |
+            | point(0, 0, -1)      | 0.0  | 0.5  |
+            | point(1, 0, 0)       | 0.25 | 0.5  |
+            | point(0, 0, 1)       | 0.5  | 0.5  |
+            | point(-1, 0, 0)      | 0.75 | 0.5  |
+            | point(0, 1, 0)       | 0.5  | 1.0  |
+            | point(0, -1, 0)      | 0.5  | 0.0  |
+            | point(√2/2, √2/2, 0) | 0.25 | 0.75 |
+
+    Scenario Outline: Using a planar mapping on a 3D point
+        Given p ← <point>
+        When (u, v) ← planar_map(p)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | point                   | u    | v    |
+            | point(0.25, 0, 0.5)     | 0.25 | 0.5  |
+            | point(0.25, 0, -0.25)   | 0.25 | 0.75 |
+            | point(0.25, 0.5, -0.25) | 0.25 | 0.75 |
+            | point(1.25, 0, 0.5)     | 0.25 | 0.5  |
+            | point(0.25, 0, -1.75)   | 0.25 | 0.25 |
+            | point(1, 0, -1)         | 0.0  | 0.0  |
+            | point(0, 0, 0)          | 0.0  | 0.0  |
+
+    Scenario Outline: Using a cylindrical mapping on a 3D point
+        Given p ← <point>
+        When (u, v) ← cylindrical_map(p)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | point                          | u     | v    |
+            | point(0, 0, -1)                | 0.0   | 0.0  |
+            | point(0, 0.5, -1)              | 0.0   | 0.5  |
+            | point(0, 1, -1)                | 0.0   | 0.0  |
+            | point(0.70711, 0.5, -0.70711)  | 0.125 | 0.5  |
+            | point(1, 0.5, 0)               | 0.25  | 0.5  |
+            | point(0.70711, 0.5, 0.70711)   | 0.375 | 0.5  |
+            | point(0, -0.25, 1)             | 0.5   | 0.75 |
+            | point(-0.70711, 0.5, 0.70711)  | 0.625 | 0.5  |
+            | point(-1, 1.25, 0)             | 0.75  | 0.25 |
+            | point(-0.70711, 0.5, -0.70711) | 0.875 | 0.5  |
+
+    Scenario Outline: Every face of a cube shows the whole texture
+        Given p ← <point>
+        When (u, v) ← cube_map(p)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | point                | u    | v    |
+            | point(-0.5, 0.5, 1)  | 0.25 | 0.75 |
+            | point(0.5, -0.5, 1)  | 0.75 | 0.25 |
+            | point(0.5, 0.5, -1)  | 0.25 | 0.75 |
+            | point(-1, 0.5, -0.5) | 0.25 | 0.75 |
+            | point(1, 0.5, 0.5)   | 0.25 | 0.75 |
+            | point(-0.5, 1, -0.5) | 0.25 | 0.75 |
+            | point(-0.5, -1, 0.5) | 0.25 | 0.75 |
+
+    Scenario: Image patterns on spheres are mapped spherically
+        Given c ← canvas(2, 2)
+        And c1 ← color(1, 0, 0)
+        And c2 ← color(0, 1, 0)
+        And shape ← sphere()
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And pattern ← image_pattern(c, nearest, repeat)
+        Then pattern_at_shape(pattern, shape, point(0, 0.70711, -0.70711), hit_uv(0, 0)) = color(1, 0, 0)
+        And pattern_at_shape(pattern, shape, point(0, 0.70711, 0.70711), hit_uv(0, 0)) = color(0, 1, 0)
+
+    Scenario: Image patterns on triangles use the barycentric coordinates of the hit
+        Given c ← canvas(2, 2)
+        And c1 ← color(1, 0, 0)
+        And c2 ← color(0, 1, 0)
+        And c3 ← color(0, 0, 1)
+        And t ← triangle(point(0, 1, 0), point(-1, 0, 0), point(1, 0, 0))
+        When write_pixel(c, 0, 0, c1)
+        And write_pixel(c, 1, 0, c2)
+        And write_pixel(c, 0, 1, c3)
+        And pattern ← image_pattern(c, nearest, repeat)
+        Then pattern_at_shape(pattern, t, point(0, 0.5, 0), hit_uv(0.25, 0.75)) = color(1, 0, 0)
+        And pattern_at_shape(pattern, t, point(0, 0.5, 0), hit_uv(0.75, 0.75)) = color(0, 1, 0)
+        And pattern_at_shape(pattern, t, point(0, 0.5, 0), hit_uv(0.25, 0.25)) = color(0, 0, 1)
+
+    Scenario Outline: Checkers in UV space
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: plane
+              material:
+                pattern: { type: uv_checkers, width: 2, height: 2, colors: [[0, 0, 0], [1, 1, 1]] }
+            """
+        When w ← parse_scene_file(scene)
+        And pattern ← the pattern of object 1 of w
+        Then uv_pattern_at(pattern, <u>, <v>) = <color>
+
+        Examples:
+            | u   | v   | color          |
+            | 0.0 | 0.0 | color(0, 0, 0) |
+            | 0.5 | 0.0 | color(1, 1, 1) |
+            | 0.0 | 0.5 | color(1, 1, 1) |
+            | 0.5 | 0.5 | color(0, 0, 0) |
+            | 1.0 | 1.0 | color(0, 0, 0) |
+
+    Scenario Outline: Layout of the align check pattern
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: plane
+              material:
+                pattern:
+                  type: align_check
+                  main: [1, 1, 1]
+                  ul: [1, 0, 0]
+                  ur: [1, 1, 0]
+                  bl: [0, 1, 0]
+                  br: [0, 1, 1]
+            """
+        When w ← parse_scene_file(scene)
+        And pattern ← the pattern of object 1 of w
+        Then uv_pattern_at(pattern, <u>, <v>) = <color>
+
+        Examples:
+            | u   | v   | color          |
+            | 0.5 | 0.5 | color(1, 1, 1) |
+            | 0.1 | 0.9 | color(1, 0, 0) |
+            | 0.9 | 0.9 | color(1, 1, 0) |
+            | 0.1 | 0.1 | color(0, 1, 0) |
+            | 0.9 | 0.1 | color(0, 1, 1) |
+
+    Scenario Outline: A pattern can choose its own spherical mapping
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: plane
+              material:
+                pattern: { type: uv_checkers, width: 16, height: 8, colors: [[0, 0, 0], [1, 1, 1]], mapping: spherical }
+            """
+        When w ← parse_scene_file(scene)
+        And pattern ← the pattern of object 1 of w
+        Then pattern_at(pattern, <point>) = <color>
+
+        Examples:
+            | point                            | color          |
+            | point(0.4315, 0.4670, 0.7719)    | color(1, 1, 1) |
+            | point(-0.9654, 0.2552, -0.0534)  | color(0, 0, 0) |
+            | point(0.1039, 0.7090, 0.6975)    | color(1, 1, 1) |
+            | point(-0.4986, -0.7856, -0.3663) | color(0, 0, 0) |
+            | point(-0.0317, -0.9395, 0.3411)  | color(0, 0, 0) |
+            | point(0.4809, -0.7721, 0.4154)   | color(0, 0, 0) |
+            | point(0.0285, -0.9612, -0.2745)  | color(0, 0, 0) |
+            | point(-0.5734, -0.2162, -0.7903) | color(1, 1, 1) |
+            | point(0.7688, -0.1470, 0.6223)   | color(0, 0, 0) |
+            | point(-0.7652, 0.2175, 0.6060)   | color(0, 0, 0) |
+
+    Scenario Outline: Identifying the face of a cube from a point
+        Given p ← <point>
+        Then cube_face(p) = <face>
+
+        Examples:
+            | point                  | face  |
+            | point(-1, 0.5, -0.25)  | left  |
+            | point(1.1, -0.75, 0.8) | right |
+            | point(0.1, 0.6, 0.9)   | front |
+            | point(-0.7, 0, -2)     | back  |
+            | point(0.5, 1, 0.9)     | up    |
+            | point(-0.2, -1.3, 1.1) | down  |
+
+    Scenario Outline: Finding the colors on a mapped cube
+        Given scene ← a file containing:
+            """
+            - { add: camera, width: 10, height: 10, field-of-view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0] }
+            - add: cube
+              material:
+                pattern:
+                  type: cube_map
+                  left: { type: align_check, main: [1, 1, 0], ul: [0, 1, 1], ur: [1, 0, 0], bl: [0, 0, 1], br: [1, 0.5, 0] }
+                  front: { type: align_check, main: [0, 1, 1], ul: [1, 0, 0], ur: [1, 1, 0], bl: [1, 0.5, 0], br: [0, 1, 0] }
+                  right: { type: align_check, main: [1, 0, 0], ul: [1, 1, 0], ur: [1, 0, 1], bl: [0, 1, 0], br: [1, 1, 1] }
+                  back: { type: align_check, main: [0, 1, 0], ul: [1, 0, 1], ur: [0, 1, 1], bl: [1, 1, 1], br: [0, 0, 1] }
+                  up: { type: align_check, main: [1, 0.5, 0], ul: [0, 1, 1], ur: [1, 0, 1], bl: [1, 0, 0], br: [1, 1, 0] }
+                  down: { type: align_check, main: [1, 0, 1], ul: [1, 0.5, 0], ur: [0, 1, 0], bl: [0, 0, 1], br: [1, 1, 1] }
+            """
+        When w ← parse_scene_file(scene)
+        And pattern ← the pattern of object 1 of w
+        Then pattern_at(pattern, <point>) = <color>
+
+        Examples:
+            | point                 | color            |
+            | point(-1, 0, 0)       | color(1, 1, 0)   |
+            | point(-1, 0.9, -0.9)  | color(0, 1, 1)   |
+            | point(-1, 0.9, 0.9)   | color(1, 0, 0)   |
+            | point(-1, -0.9, -0.9) | color(0, 0, 1)   |
+            | point(-1, -0.9, 0.9)  | color(1, 0.5, 0) |
+            | point(0, 0, 1)        | color(0, 1, 1)   |
+            | point(-0.9, 0.9, 1)   | color(1, 0, 0)   |
+            | point(0.9, 0.9, 1)    | color(1, 1, 0)   |
+            | point(-0.9, -0.9, 1)  | color(1, 0.5, 0) |
+            | point(0.9, -0.9, 1)   | color(0, 1, 0)   |
+            | point(1, 0, 0)        | color(1, 0, 0)   |
+            | point(1, 0.9, 0.9)    | color(1, 1, 0)   |
+            | point(1, 0.9, -0.9)   | color(1, 0, 1)   |
+            | point(1, -0.9, 0.9)   | color(0, 1, 0)   |
+            | point(1, -0.9, -0.9)  | color(1, 1, 1)   |
+            | point(0, 0, -1)       | color(0, 1, 0)   |
+            | point(0.9, 0.9, -1)   | color(1, 0, 1)   |
+            | point(-0.9, 0.9, -1)  | color(0, 1, 1)   |
+            | point(0.9, -0.9, -1)  | color(1, 1, 1)   |
+            | point(-0.9, -0.9, -1) | color(0, 0, 1)   |
+            | point(0, 1, 0)        | color(1, 0.5, 0) |
+            | point(-0.9, 1, -0.9)  | color(0, 1, 1)   |
+            | point(0.9, 1, -0.9)   | color(1, 0, 1)   |
+            | point(-0.9, 1, 0.9)   | color(1, 0, 0)   |
+            | point(0.9, 1, 0.9)    | color(1, 1, 0)   |
+            | point(0, -1, 0)       | color(1, 0, 1)   |
+            | point(-0.9, -1, 0.9)  | color(1, 0.5, 0) |
+            | point(0.9, -1, 0.9)   | color(0, 1, 0)   |
+            | point(-0.9, -1, -0.9) | color(0, 0, 1)   |
+            | point(0.9, -1, -0.9)  | color(1, 1, 1)   |
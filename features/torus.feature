@@ -0,0 +1,78 @@
+Feature: Tori
+
+    Scenario Outline: Solving quartic equations
+        When roots ← solve_quartic(<a>, <b>, <c>, <d>, <e>)
+        Then roots = <roots>
+
+        Examples:
+            | a | b   | c  | d    | e  | roots      |
+            | 1 | -10 | 35 | -50  | 24 | 1, 2, 3, 4 |
+            | 1 | 0   | 0  | 0    | -1 | -1, 1      |
+            | 1 | 0   | 0  | 0    | 1  |            |
+            | 1 | 1   | -5 | 1    | -6 | -3, 2      |
+            | 2 | -20 | 70 | -100 | 48 | 1, 2, 3, 4 |
+            | 0 | 1   | -6 | 11   | -6 | 1, 2, 3    |
+            | 0 | 0   | 1  | 0    | -4 | -2, 2      |
+
+    Scenario Outline: A ray strikes a torus
+        Given shape ← torus(1, 0.25)
+        And direction ← normalize(<direction>)
+        And r ← ray(<origin>, direction)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = <count>
+        And xs[0].t = <t0>
+        And xs[<last>].t = <t1>
+
+        Examples:
+            | origin              | direction        | count | t0      | last | t1      |
+            | point(-3, 0, 0)     | vector(1, 0, 0)  | 4     | 1.75    | 3    | 4.25    |
+            | point(0, 5, 1)      | vector(0, -1, 0) | 2     | 4.75    | 1    | 5.25    |
+            | point(1, 0, 0)      | vector(0, 0, 1)  | 2     | -0.75   | 1    | 0.75    |
+            | point(-1000, 0, 0)  | vector(1, 0, 0)  | 4     | 998.75  | 3    | 1001.25 |
+            | point(0, 0.125, -5) | vector(0, 0, 1)  | 4     | 3.78349 | 3    | 6.21651 |
+
+    Scenario Outline: A ray misses a torus
+        Given shape ← torus(1, 0.25)
+        And direction ← normalize(<direction>)
+        And r ← ray(<origin>, direction)
+        When xs ← local_intersect(shape, r)
+        Then xs.count = 0
+
+        Examples:
+            | origin          | direction        |
+            | point(0, 5, 0)  | vector(0, -1, 0) |
+            | point(3, 0, -5) | vector(0, 0, 1)  |
+            | point(0, 1, -5) | vector(0, 0, 1)  |
+
+    Scenario Outline: The normal vector on a torus
+        Given shape ← torus(1, 0.25)
+        When n ← local_normal_at(shape, <point>)
+        Then normalize(n) = <normal>
+
+        Examples:
+            | point               | normal           |
+            | point(1.25, 0, 0)   | vector(1, 0, 0)  |
+            | point(0.75, 0, 0)   | vector(-1, 0, 0) |
+            | point(1, 0.25, 0)   | vector(0, 1, 0)  |
+            | point(0, -0.25, -1) | vector(0, -1, 0) |
+            | point(0, 0, -1.25)  | vector(0, 0, -1) |
+
+    Scenario: A torus has a bounding box
+        Given shape ← torus(2, 0.5)
+        When box ← bounds_of(shape)
+        Then box.min = point(-2.5, -0.5, -2.5)
+        And box.max = point(2.5, 0.5, 2.5)
+
+    Scenario Outline: Texture coordinates on a torus
+        Given shape ← torus(1, 0.25)
+        When (u, v) ← uv_at(shape, <point>)
+        Then u = <u>
+        And v = <v>
+
+        Examples:
+            | point              | u    | v    |
+            | point(0, 0, 1.25)  | 0.5  | 0.5  |
+            | point(0, 0.25, 1)  | 0.5  | 0.75 |
+            | point(0, 0, 0.75)  | 0.5  | 1    |
+            | point(1.25, 0, 0)  | 0.25 | 0.5  |
+            | point(0, -0.25, 1) | 0.5  | 0.25 |
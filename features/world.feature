@@ -28,6 +28,54 @@ Feature: World
         And xs[2].t = 5.5
         And xs[3].t = 6
 
+    Scenario: Hits borrow the objects of the world
+        Given w ← default_world()
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        Then intersect_into(w, r) appends 4 hits on the objects of w
+
+    Scenario: Intersect a world with a ray through the wide end of a cone
+        Given w ← world()
+        And shape ← cone()
+        And shape.minimum ← -1
+        And shape.maximum ← 0
+        And shape is added to w
+        And r ← ray(point(0.8, -0.9, -5), vector(0, 0, 1))
+        When xs ← intersect_world(w, r)
+        Then xs.count = 2
+
+    Scenario: The closest hit of a ray in a world
+        Given w ← default_world()
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        When i ← closest_hit(w, r)
+        Then i.t = 4
+
+    Scenario Outline: The closest hit lies inside the interval of the ray
+        Given w ← default_world()
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        And r ← between(r, <t_min>, <t_max>)
+        When i ← closest_hit(w, r)
+        Then i.t = <t>
+
+        Examples:
+            | t_min     | t_max    | t   |
+            | -infinity | infinity | 4   |
+            | 4.2       | infinity | 4.5 |
+            | 5         | 10       | 5.5 |
+            | 0         | 4.2      | 4   |
+
+    Scenario: There is no closest hit when the interval ends before the world
+        Given w ← default_world()
+        And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+        And r ← between(r, 0, 3)
+        When i ← closest_hit(w, r)
+        Then i is nothing
+
+    Scenario: The closest hit ignores intersections behind the ray
+        Given w ← default_world()
+        And r ← ray(point(0, 0, 0), vector(0, 0, 1))
+        When i ← closest_hit(w, r)
+        Then i.t = 0.5
+
     Scenario: Shading an intersection
         Given w ← default_world()
         And r ← ray(point(0, 0, -5), vector(0, 0, 1))
@@ -91,6 +139,36 @@ Feature: World
         And p ← point(-2, 2, -2)
         Then is_shadowed(w, p) is false
 
+    Scenario: Objects that throw no shadow are ignored by shadow rays
+        Given w ← world()
+        And w.light ← point_light(point(0, 0, -10), color(1, 1, 1))
+        And s1 ← sphere() with:
+            | throws_shadow | false |
+        And s1 is added to w
+        And p ← point(0, 0, 10)
+        Then is_shadowed(w, p) is false
+
+    Scenario: An object behind one that throws no shadow still does
+        Given w ← world()
+        And w.light ← point_light(point(0, 0, -10), color(1, 1, 1))
+        And s1 ← sphere() with:
+            | throws_shadow | false |
+        And s1 is added to w
+        And s2 ← sphere() with:
+            | transform | translation(0, 0, 5) |
+        And s2 is added to w
+        And p ← point(0, 0, 10)
+        Then is_shadowed(w, p) is true
+
+    Scenario: There is no shadow from an object beyond the light
+        Given w ← world()
+        And w.light ← point_light(point(0, 0, -10), color(1, 1, 1))
+        And s1 ← sphere() with:
+            | transform | translation(0, 0, -15) |
+        And s1 is added to w
+        And p ← point(0, 0, 10)
+        Then is_shadowed(w, p) is false
+
     Scenario: shade_hit() is given an intersection in shadow
         Given w ← world()
         And w.light ← point_light(point(0, 0, -10), color(1, 1, 1))
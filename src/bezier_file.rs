@@ -0,0 +1,187 @@
+// Bicubic Bézier patches as text, in either of the two common layouts. The
+// original teapot data counts the patches, lists 16 vertex indices per patch
+// counting from 1, then counts the vertices and lists them. The bpt layout
+// counts the patches and gives each one as its degrees "3 3" followed by its
+// 16 points. Numbers are separated by commas or spaces.
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind, Read, Result},
+    path::Path,
+};
+
+use crate::{
+    patches::BezierPatch,
+    tuples::{point, Tuple},
+};
+
+pub fn load_bezier_file(path: &Path) -> Result<Vec<BezierPatch>> {
+    parse_bezier_file(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn parse_bezier_file(reader: &mut dyn Read) -> Result<Vec<BezierPatch>> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut lines = Lines::new(&text);
+
+    let (number, fields) = lines.next("the number of patches")?;
+    let count = integer(number, &single(number, &fields)?)?;
+    let (number, fields) = lines.peek("a patch")?;
+    if fields.len() == 2 {
+        return bpt(&mut lines, count);
+    }
+    if fields.len() != 16 {
+        return Err(line_error(
+            number,
+            format!("expected 16 vertex indices, got {}", fields.len()),
+        ));
+    }
+
+    let mut indices = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (number, fields) = lines.next("a patch")?;
+        if fields.len() != 16 {
+            return Err(line_error(
+                number,
+                format!("expected 16 vertex indices, got {}", fields.len()),
+            ));
+        }
+        let patch = fields
+            .iter()
+            .map(|f| integer(number, f))
+            .collect::<Result<Vec<_>>>()?;
+        indices.push((number, patch));
+    }
+    let (number, fields) = lines.next("the number of vertices")?;
+    let vertex_count = integer(number, &single(number, &fields)?)?;
+    let mut vertices = Vec::with_capacity(vertex_count);
+    for _ in 0..vertex_count {
+        let (number, fields) = lines.next("a vertex")?;
+        vertices.push(vertex(number, &fields)?);
+    }
+
+    indices
+        .into_iter()
+        .map(|(number, patch)| {
+            let mut points = Vec::with_capacity(16);
+            for index in patch {
+                match index.checked_sub(1).and_then(|i| vertices.get(i)) {
+                    Some(p) => points.push(p.clone()),
+                    None => {
+                        return Err(line_error(
+                            number,
+                            format!("vertex index {} is out of range", index),
+                        ))
+                    }
+                }
+            }
+            Ok(BezierPatch::new(points.try_into().unwrap()))
+        })
+        .collect()
+}
+
+fn bpt(lines: &mut Lines, count: usize) -> Result<Vec<BezierPatch>> {
+    let mut patches = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (number, fields) = lines.next("a patch")?;
+        let degrees = fields
+            .iter()
+            .map(|f| integer(number, f))
+            .collect::<Result<Vec<_>>>()?;
+        if degrees != [3, 3] {
+            return Err(line_error(number, "only bicubic patches are supported"));
+        }
+        let mut points = Vec::with_capacity(16);
+        for _ in 0..16 {
+            let (number, fields) = lines.next("a control point")?;
+            points.push(vertex(number, &fields)?);
+        }
+        patches.push(BezierPatch::new(points.try_into().unwrap()));
+    }
+    Ok(patches)
+}
+
+// the non-empty lines with their numbers, split into fields
+struct Lines<'a> {
+    lines: std::iter::Peekable<std::iter::Enumerate<std::str::Lines<'a>>>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Lines<'a> {
+        Lines {
+            lines: text.lines().enumerate().peekable(),
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some((_, line)) = self.lines.peek() {
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.lines.next();
+        }
+    }
+
+    fn peek(&mut self, expected: &str) -> Result<(usize, Vec<String>)> {
+        self.skip_blank();
+        match self.lines.peek() {
+            Some((number, line)) => Ok((number + 1, fields(line))),
+            None => Err(invalid_data(format!("missing {}", expected))),
+        }
+    }
+
+    fn next(&mut self, expected: &str) -> Result<(usize, Vec<String>)> {
+        let line = self.peek(expected)?;
+        self.lines.next();
+        Ok(line)
+    }
+}
+
+fn fields(line: &str) -> Vec<String> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|f| !f.is_empty())
+        .map(|f| f.to_string())
+        .collect()
+}
+
+fn single(number: usize, fields: &[String]) -> Result<String> {
+    match fields {
+        [field] => Ok(field.clone()),
+        _ => Err(line_error(
+            number,
+            format!("expected 1 number, got {}", fields.len()),
+        )),
+    }
+}
+
+fn integer(number: usize, field: &str) -> Result<usize> {
+    field
+        .parse()
+        .map_err(|_| line_error(number, format!("invalid integer '{}'", field)))
+}
+
+fn vertex(number: usize, fields: &[String]) -> Result<Tuple> {
+    if fields.len() != 3 {
+        return Err(line_error(
+            number,
+            format!("expected 3 coordinates, got {}", fields.len()),
+        ));
+    }
+    let coordinate = |field: &String| {
+        field
+            .parse::<f64>()
+            .map_err(|_| line_error(number, format!("invalid number '{}'", field)))
+    };
+    Ok(point(
+        coordinate(&fields[0])?,
+        coordinate(&fields[1])?,
+        coordinate(&fields[2])?,
+    ))
+}
+
+fn line_error(number: usize, message: impl Into<String>) -> Error {
+    invalid_data(format!("line {}: {}", number, message.into()))
+}
+
+fn invalid_data(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
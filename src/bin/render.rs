@@ -0,0 +1,183 @@
+use lab_raytracing_rs::{
+    camera::{AntiAliasing, Renderer},
+    canvas::{BitDepth, Canvas},
+    scene_file::Scene,
+};
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+const USAGE: &str = "usage: render [options] <scene.yml>...
+
+Renders every scene file. With several scene files, --output names the
+directory the images are written to.
+
+options:
+  -o, --output <path>           image file to write (png, ppm, pfm or hdr), - for a ppm on stdout
+  -r, --resolution <w>x<h>      override the resolution of the scene's camera
+  -a, --antialiasing <mode>     off, fast, dynamic or the raster size, e.g. 3
+  -t, --threads <n>             number of render threads, off renders single threaded
+  -h, --help                    print this help";
+
+#[derive(Debug, Default)]
+struct Options {
+    output: Option<PathBuf>,
+    resolution: Option<(usize, usize)>,
+    antialiasing: Option<AntiAliasing>,
+    renderer: Option<Renderer>,
+    scenes: Vec<PathBuf>,
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut failed = false;
+    for scene in options.scenes.iter() {
+        if let Err(err) = render(&options, scene) {
+            eprintln!("{}: {}", scene.display(), err);
+            failed = true;
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn render(options: &Options, scene_file: &Path) -> Result<(), Box<dyn Error>> {
+    let mut scene = Scene::load_scene_file(scene_file)?;
+    for warning in scene.warnings.iter() {
+        eprintln!("{}: warning: {}", scene_file.display(), warning);
+    }
+
+    let mut camera = scene.camera;
+    if let Some((hsize, vsize)) = options.resolution {
+        camera.set_resolution(hsize, vsize);
+    }
+    if let Some(antialiasing) = &options.antialiasing {
+        camera.antialiasing = antialiasing.clone();
+    }
+    if let Some(renderer) = &options.renderer {
+        camera.renderer = renderer.clone();
+    }
+
+    let output = options.output_for(scene_file)?;
+    eprintln!(
+        "rendering {} at {}x{} to {}",
+        scene_file.display(),
+        camera.hsize,
+        camera.vsize,
+        output.display()
+    );
+    eprintln!("bvh: {}", scene.world.build_bvh());
+    let canvas = camera.render(&scene.world);
+    write_image(&canvas, &output)?;
+
+    Ok(())
+}
+
+fn write_image(canvas: &Canvas, output: &Path) -> io::Result<()> {
+    if output == Path::new("-") {
+        let writer = &mut BufWriter::with_capacity(1024 * 128, io::stdout());
+        canvas.ppm_binary(writer, BitDepth::Eight)?;
+        return writer.flush();
+    }
+    canvas.save(output)
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("{} expects a value", name))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                "-o" | "--output" => options.output = Some(PathBuf::from(value(&arg)?)),
+                "-r" | "--resolution" => {
+                    options.resolution = Some(parse_resolution(&value(&arg)?)?)
+                }
+                "-a" | "--antialiasing" => {
+                    options.antialiasing = Some(AntiAliasing::parse(&value(&arg)?)?)
+                }
+                "-t" | "--threads" => options.renderer = Some(Renderer::parse(&value(&arg)?)?),
+                s if s.starts_with('-') && s != "-" => return Err(format!("unknown option {}", s)),
+                _ => options.scenes.push(PathBuf::from(arg)),
+            }
+        }
+
+        if options.scenes.is_empty() {
+            return Err("no scene file given".to_string());
+        }
+        if options.scenes.len() > 1 && options.output.as_deref() == Some(Path::new("-")) {
+            return Err("only a single scene can be written to stdout".to_string());
+        }
+        if options.scenes.len() > 1 {
+            let mut written = HashMap::new();
+            for scene in options.scenes.iter() {
+                if let Some(other) = written.insert(image_name(scene), scene) {
+                    return Err(format!(
+                        "{} and {} would both be written to {}",
+                        other.display(),
+                        scene.display(),
+                        image_name(scene).display()
+                    ));
+                }
+            }
+        }
+
+        // settings given as options win over the environment, invalid
+        // environment settings are only reported when they are used
+        if options.antialiasing.is_none() {
+            options.antialiasing = Some(AntiAliasing::from_env()?);
+        }
+        if options.renderer.is_none() {
+            options.renderer = Some(Renderer::from_env()?);
+        }
+        Ok(options)
+    }
+
+    // batch renders write one image per scene into the output directory
+    fn output_for(&self, scene_file: &Path) -> io::Result<PathBuf> {
+        let name = image_name(scene_file);
+        match &self.output {
+            None => Ok(name),
+            Some(output) if self.scenes.len() == 1 => Ok(output.clone()),
+            Some(directory) => {
+                fs::create_dir_all(directory)?;
+                Ok(directory.join(name))
+            }
+        }
+    }
+}
+
+fn image_name(scene_file: &Path) -> PathBuf {
+    Path::new(scene_file.file_stem().unwrap_or_default()).with_extension("png")
+}
+
+fn parse_resolution(resolution: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("invalid resolution '{}', expected e.g. 800x600", resolution);
+    let (width, height) = resolution.split_once('x').ok_or_else(invalid)?;
+    let width = width.parse::<usize>().map_err(|_| invalid())?;
+    let height = height.parse::<usize>().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width, height))
+}
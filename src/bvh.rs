@@ -0,0 +1,668 @@
+// Bounding volume hierarchies over group members, split where the surface
+// area heuristic expects the fewest tests: a ray hits a box about as often as
+// the box's surface compares to its parent's. Members are never taken apart,
+// CSG nodes, instances and subgroups stay whole, and large subgroups get a
+// hierarchy of their own, also those placed by instances or in CSG nodes. Members without finite bounds, like planes, stay
+// next to the hierarchy.
+use std::{cell::Cell, collections::HashMap, fmt, sync::Arc};
+
+use crate::{
+    csg::CSG,
+    groups::{Group, GroupMember, AABB},
+    intersections::{with_shape_hits, Hit, InstancePath, Intersection},
+    objects::Object,
+    rays::Ray,
+};
+
+// what testing a box costs compared to testing a member
+const TRAVERSAL_COST: f64 = 1.0;
+const INTERSECTION_COST: f64 = 1.0;
+const BINS: usize = 12;
+// a leaf is split even when the heuristic does not ask for it, and groups
+// with fewer members are left as they are
+const MAX_LEAF_SIZE: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BvhStats {
+    // one hierarchy for the top and one for every large group below it
+    pub trees: usize,
+    pub members: usize,
+    pub unbounded: usize,
+    pub nodes: usize,
+    pub leaves: usize,
+    pub depth: usize,
+    pub min_leaf: usize,
+    pub max_leaf: usize,
+    // the expected cost of a ray through each hierarchy, summed
+    pub cost: f64,
+}
+
+impl BvhStats {
+    fn new() -> BvhStats {
+        BvhStats {
+            trees: 0,
+            members: 0,
+            unbounded: 0,
+            nodes: 0,
+            leaves: 0,
+            depth: 0,
+            min_leaf: usize::MAX,
+            max_leaf: 0,
+            cost: 0.0,
+        }
+    }
+
+    pub fn mean_leaf(&self) -> f64 {
+        if self.leaves == 0 {
+            return 0.0;
+        }
+        self.members as f64 / self.leaves as f64
+    }
+
+    fn leaf(&mut self, size: usize, depth: usize) {
+        self.leaves += 1;
+        self.min_leaf = self.min_leaf.min(size);
+        self.max_leaf = self.max_leaf.max(size);
+        self.depth = self.depth.max(depth);
+    }
+
+    fn merge(&mut self, other: BvhStats) {
+        self.trees += other.trees;
+        self.members += other.members;
+        self.unbounded += other.unbounded;
+        self.nodes += other.nodes;
+        self.leaves += other.leaves;
+        self.depth = self.depth.max(other.depth);
+        self.min_leaf = self.min_leaf.min(other.min_leaf);
+        self.max_leaf = self.max_leaf.max(other.max_leaf);
+        self.cost += other.cost;
+    }
+}
+
+impl fmt::Display for BvhStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} members in {} trees, {} unbounded, {} nodes, depth {}, ",
+            self.members, self.trees, self.unbounded, self.nodes, self.depth
+        )?;
+        if self.leaves == 0 {
+            write!(f, "no leaves")?;
+        } else {
+            write!(
+                f,
+                "{} leaves of {} to {} members ({:.1} on average)",
+                self.leaves,
+                self.min_leaf,
+                self.max_leaf,
+                self.mean_leaf()
+            )?;
+        }
+        write!(f, ", cost {:.2}", self.cost)
+    }
+}
+
+struct Item {
+    member: GroupMember,
+    bounds: AABB,
+    centroid: [f64; 3],
+}
+
+// the members to put in place of the given ones, the unbounded ones first
+pub fn build(members: Vec<GroupMember>) -> (Vec<GroupMember>, BvhStats) {
+    build_shared(members, &mut HashMap::new())
+}
+
+// groups placed by several instances are rebuilt once and stay shared, the
+// original is kept so its address cannot be taken by another group
+type Rebuilt = HashMap<*const Group, (Arc<Group>, Arc<Group>)>;
+
+fn build_shared(members: Vec<GroupMember>, rebuilt: &mut Rebuilt) -> (Vec<GroupMember>, BvhStats) {
+    let mut stats = BvhStats::new();
+    let mut placed = Vec::new();
+    let mut items = Vec::new();
+    for member in members {
+        let member = rebuild(member, &mut stats, rebuilt);
+        match member.bounds() {
+            Some(bounds) if is_finite(&bounds) => {
+                let c = bounds.center();
+                items.push(Item {
+                    member,
+                    bounds,
+                    centroid: [c.x, c.y, c.z],
+                });
+            }
+            _ => {
+                stats.unbounded += 1;
+                placed.push(member);
+            }
+        }
+    }
+    if !items.is_empty() {
+        let root_area = surface_area(&union(&items));
+        stats.trees += 1;
+        stats.members += items.len();
+        // the group holding them is the box of the root
+        match split(items, 1, root_area, &mut stats) {
+            Node::Leaf(members) => placed.extend(members),
+            Node::Inner(children) => placed.extend(children),
+            Node::Member(member) => placed.push(member),
+        }
+    }
+    (placed, stats)
+}
+
+// large groups get their own hierarchy and keep their transform, smaller
+// ones, instances and CSG nodes only pass the rebuild on to their members
+fn rebuild(member: GroupMember, stats: &mut BvhStats, rebuilt: &mut Rebuilt) -> GroupMember {
+    match member {
+        GroupMember::SubGroup(g) => {
+            if let Some((_, done)) = rebuilt.get(&Arc::as_ptr(&g)) {
+                return GroupMember::SubGroup(done.clone());
+            }
+            let elements = g.members().to_vec();
+            let elements = if elements.len() > MAX_LEAF_SIZE {
+                let (elements, nested) = build_shared(elements, rebuilt);
+                stats.merge(nested);
+                elements
+            } else {
+                elements
+                    .into_iter()
+                    .map(|e| rebuild(e, stats, rebuilt))
+                    .collect()
+            };
+            let done = Arc::new(g.with_members(elements));
+            rebuilt.insert(Arc::as_ptr(&g), (g, done.clone()));
+            GroupMember::SubGroup(done)
+        }
+        GroupMember::Instance(i) => {
+            let member = rebuild(i.member().clone(), stats, rebuilt);
+            GroupMember::Instance(Arc::new(i.with_member(member)))
+        }
+        GroupMember::CSG(c) => {
+            let mut rebuild = |member: &GroupMember| rebuild(member.clone(), stats, rebuilt);
+            GroupMember::CSG(Arc::new(match c.as_ref() {
+                CSG::Union(l, r) => CSG::Union(rebuild(l), rebuild(r)),
+                CSG::Intersection(l, r) => CSG::Intersection(rebuild(l), rebuild(r)),
+                CSG::Difference(l, r) => CSG::Difference(rebuild(l), rebuild(r)),
+            }))
+        }
+        member => member,
+    }
+}
+
+enum Node {
+    Leaf(Vec<GroupMember>),
+    // the two children of a node
+    Inner(Vec<GroupMember>),
+    // a leaf with a single member needs no box of its own
+    Member(GroupMember),
+}
+
+fn split(mut items: Vec<Item>, depth: usize, root_area: f64, stats: &mut BvhStats) -> Node {
+    let bounds = union(&items);
+    let area = surface_area(&bounds);
+    let leaf_cost = INTERSECTION_COST * items.len() as f64;
+
+    let best = if items.len() > 1 {
+        best_split(&items, area)
+    } else {
+        None
+    };
+    let split_here = match best {
+        Some((cost, _, _)) => cost < leaf_cost || items.len() > MAX_LEAF_SIZE,
+        None => items.len() > MAX_LEAF_SIZE,
+    };
+    if !split_here {
+        stats.nodes += 1;
+        stats.leaf(items.len(), depth);
+        stats.cost += relative(area, root_area) * leaf_cost;
+        if items.len() == 1 {
+            return Node::Member(items.pop().unwrap().member);
+        }
+        return Node::Leaf(items.into_iter().map(|item| item.member).collect());
+    }
+
+    let right = match best {
+        Some((_, axis, boundary)) => {
+            let bin = binning(&items, axis).unwrap();
+            let (left, right): (Vec<Item>, Vec<Item>) = items
+                .into_iter()
+                .partition(|item| bin(item.centroid[axis]) <= boundary);
+            items = left;
+            right
+        }
+        // the centroids are all at one place, only halving the members helps
+        None => {
+            let half = items.len() / 2;
+            items.split_off(half)
+        }
+    };
+    stats.nodes += 1;
+    stats.cost += relative(area, root_area) * TRAVERSAL_COST;
+    let children = [items, right]
+        .into_iter()
+        .map(|child| match split(child, depth + 1, root_area, stats) {
+            Node::Leaf(members) | Node::Inner(members) => {
+                let mut node = Group::default();
+                for member in members {
+                    node.push_member(member);
+                }
+                GroupMember::SubGroup(Arc::new(node))
+            }
+            Node::Member(member) => member,
+        })
+        .collect();
+    Node::Inner(children)
+}
+
+// the bin of a centroid along the axis, when the centroids spread at all
+fn binning(items: &[Item], axis: usize) -> Option<impl Fn(f64) -> usize> {
+    let low = items
+        .iter()
+        .map(|i| i.centroid[axis])
+        .fold(f64::INFINITY, f64::min);
+    let high = items
+        .iter()
+        .map(|i| i.centroid[axis])
+        .fold(f64::NEG_INFINITY, f64::max);
+    if high <= low {
+        return None;
+    }
+    Some(move |c: f64| (((c - low) / (high - low) * BINS as f64) as usize).min(BINS - 1))
+}
+
+// the cost, axis and last bin on the left of the cheapest split
+fn best_split(items: &[Item], area: f64) -> Option<(f64, usize, usize)> {
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        let Some(bin_of) = binning(items, axis) else {
+            continue;
+        };
+        let mut counts = [0usize; BINS];
+        let mut boxes: [Option<AABB>; BINS] = Default::default();
+        for item in items {
+            let bin = bin_of(item.centroid[axis]);
+            counts[bin] += 1;
+            boxes[bin] = Group::outer_bounds(&boxes[bin], &Some(item.bounds.clone()));
+        }
+        // the boxes and counts left of every boundary, then right of it
+        let mut left = Vec::with_capacity(BINS - 1);
+        let (mut bounds, mut count) = (None, 0);
+        for bin in 0..BINS - 1 {
+            bounds = Group::outer_bounds(&bounds, &boxes[bin]);
+            count += counts[bin];
+            left.push((bounds.clone(), count));
+        }
+        let (mut bounds, mut count) = (None, 0);
+        for boundary in (0..BINS - 1).rev() {
+            bounds = Group::outer_bounds(&bounds, &boxes[boundary + 1]);
+            count += counts[boundary + 1];
+            let (left_bounds, left_count) = &left[boundary];
+            if *left_count == 0 || count == 0 {
+                continue;
+            }
+            let cost = TRAVERSAL_COST
+                + INTERSECTION_COST
+                    * (relative(surface_area(left_bounds.as_ref().unwrap()), area)
+                        * *left_count as f64
+                        + relative(surface_area(bounds.as_ref().unwrap()), area) * count as f64);
+            if best.as_ref().is_none_or(|(c, _, _)| cost < *c) {
+                best = Some((cost, axis, boundary));
+            }
+        }
+    }
+    best
+}
+
+fn union(items: &[Item]) -> AABB {
+    let mut bounds = items[0].bounds.clone();
+    for item in &items[1..] {
+        bounds = &bounds + &item.bounds;
+    }
+    bounds
+}
+
+fn surface_area(b: &AABB) -> f64 {
+    let (x, y, z) = (b.max.x - b.min.x, b.max.y - b.min.y, b.max.z - b.min.z);
+    2.0 * (x * y + y * z + z * x)
+}
+
+// flat boxes have no area, they are still found as often as their parent
+fn relative(area: f64, parent: f64) -> f64 {
+    if parent > 0.0 {
+        area / parent
+    } else {
+        1.0
+    }
+}
+
+// the traversal stack every query of a thread reuses, a traversal never
+// starts another one
+thread_local! {
+    static STACK: Cell<Vec<(usize, f64)>> = const { Cell::new(Vec::new()) };
+}
+
+fn is_finite(b: &AABB) -> bool {
+    [b.min.x, b.min.y, b.min.z, b.max.x, b.max.y, b.max.z]
+        .iter()
+        .all(|v| v.is_finite())
+}
+
+// The hierarchy below the world in one array, the way meshes keep theirs.
+// Groups place their members themselves, so below the world a group is only
+// a box: its members that are not groups share a leaf and its subgroups
+// become nodes of their own, paired up where a group has more than two.
+// CSG nodes and instances are tested as a whole in the leaves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearBvh {
+    nodes: Vec<LinearNode>,
+    members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LinearNode {
+    min: [f64; 3],
+    max: [f64; 3],
+    // a leaf holds count members starting at first, the first child of an
+    // inner node follows it and the second one is at first
+    first: u32,
+    count: u32,
+}
+
+enum Tree {
+    Leaf([f64; 3], [f64; 3], Vec<GroupMember>),
+    Inner([f64; 3], [f64; 3], Box<Tree>, Box<Tree>),
+}
+
+impl LinearBvh {
+    pub fn new(members: &[GroupMember]) -> LinearBvh {
+        let mut bvh = LinearBvh {
+            nodes: Vec::new(),
+            members: Vec::new(),
+        };
+        if let Some(tree) = tree(members) {
+            bvh.emit(tree);
+        }
+        bvh
+    }
+
+    fn emit(&mut self, tree: Tree) {
+        let index = self.nodes.len();
+        match tree {
+            Tree::Leaf(min, max, members) => {
+                self.nodes.push(LinearNode {
+                    min,
+                    max,
+                    first: self.members.len() as u32,
+                    count: members.len() as u32,
+                });
+                self.members.extend(members);
+            }
+            Tree::Inner(min, max, left, right) => {
+                self.nodes.push(LinearNode {
+                    min,
+                    max,
+                    first: 0,
+                    count: 0,
+                });
+                self.emit(*left);
+                self.nodes[index].first = self.nodes.len() as u32;
+                self.emit(*right);
+            }
+        }
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn members(&self) -> &[GroupMember] {
+        &self.members
+    }
+
+    // the members of every leaf whose box the ray passes inside its interval,
+    // the children of a node in the order the ray enters them. visit tells
+    // how far along the ray boxes are still of interest, a closest hit query
+    // skips those beyond the nearest hit so far and an any hit query ends the
+    // traversal by returning less than t_min.
+    pub fn traverse<'a>(&'a self, ray: &Ray, mut visit: impl FnMut(&'a [GroupMember]) -> f64) {
+        let Some(root) = self.nodes.first() else {
+            return;
+        };
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let inverse = [
+            1.0 / ray.direction.x,
+            1.0 / ray.direction.y,
+            1.0 / ray.direction.z,
+        ];
+        let mut t_max = ray.t_max;
+        let Some(t) = root.enter(&origin, &inverse, ray.t_min, t_max) else {
+            return;
+        };
+        let mut stack = STACK.take();
+        stack.push((0, t));
+        while let Some((index, t)) = stack.pop() {
+            if t > t_max {
+                continue;
+            }
+            let node = &self.nodes[index];
+            let first = node.first as usize;
+            if node.count > 0 {
+                t_max = t_max.min(visit(&self.members[first..first + node.count as usize]));
+                // nothing is left of the interval
+                if t_max < ray.t_min {
+                    break;
+                }
+                continue;
+            }
+            let near = self.nodes[index + 1].enter(&origin, &inverse, ray.t_min, t_max);
+            let far = self.nodes[first].enter(&origin, &inverse, ray.t_min, t_max);
+            match (near, far) {
+                (Some(a), Some(b)) if b < a => stack.extend([(index + 1, a), (first, b)]),
+                (Some(a), Some(b)) => stack.extend([(first, b), (index + 1, a)]),
+                (Some(a), None) => stack.push((index + 1, a)),
+                (None, Some(b)) => stack.push((first, b)),
+                (None, None) => {}
+            }
+        }
+        stack.clear();
+        STACK.set(stack);
+    }
+
+    // appends every hit inside the interval of the ray, unsorted
+    pub fn intersect<'a>(&'a self, ray: &Ray, xs: &mut Vec<Hit<'a>>) {
+        let start = xs.len();
+        self.traverse(ray, |members| {
+            for member in members {
+                member.intersect_into(ray, InstancePath::default(), xs);
+            }
+            ray.t_max
+        });
+        let mut index = 0;
+        xs.retain(|hit| {
+            index += 1;
+            index <= start || (hit.t >= ray.t_min && hit.t <= ray.t_max)
+        });
+    }
+
+    // whether any hit inside the interval is with an object that passes the
+    // test, the traversal ends at the first one. Objects that do not pass
+    // are not even intersected.
+    pub fn any_hit(&self, ray: &Ray, test: impl Fn(&Object) -> bool) -> bool {
+        let inside = |t: f64| t >= ray.t_min && t <= ray.t_max;
+        let mut found = false;
+        // groups, csg and instances need hits that know their object, the
+        // buffer is only allocated by queries that meet one
+        let mut hits = Vec::new();
+        with_shape_hits(|shape_hits| {
+            self.traverse(ray, |members| {
+                found = members.iter().any(|member| match member {
+                    GroupMember::Object(o) if !test(o) => false,
+                    GroupMember::Object(o) => {
+                        shape_hits.clear();
+                        o.intersect_nearest_into(ray, shape_hits);
+                        shape_hits.iter().any(|hit| inside(hit.t))
+                    }
+                    member => {
+                        hits.clear();
+                        member.intersect_into(ray, InstancePath::default(), &mut hits);
+                        hits.iter().any(|hit| inside(hit.t) && test(hit.object))
+                    }
+                });
+                if found {
+                    f64::NEG_INFINITY
+                } else {
+                    ray.t_max
+                }
+            })
+        });
+        found
+    }
+
+    // the hit nearest to the start of the interval, of those at the same
+    // place the first one found. Only that one becomes an intersection.
+    pub fn closest_hit(&self, ray: &Ray) -> Option<Intersection> {
+        let mut closest: Option<Hit> = None;
+        let mut t_max = ray.t_max;
+        let mut found = false;
+        let mut hits = Vec::new();
+        with_shape_hits(|shape_hits| {
+            self.traverse(ray, |members| {
+                for member in members {
+                    // meshes skip their faces behind the nearest hit so far
+                    let narrowed = ray.clone().between(ray.t_min, t_max);
+                    // once something is found t_max is where it is
+                    let mut keep = |t: f64| {
+                        let nearer = !found || t < t_max;
+                        let keep = t >= ray.t_min && t <= t_max && nearer;
+                        if keep {
+                            t_max = t;
+                            found = true;
+                        }
+                        keep
+                    };
+                    match member {
+                        // most members are objects, their hits only become
+                        // hits of the world when they are the nearest so far
+                        GroupMember::Object(o) => {
+                            shape_hits.clear();
+                            o.intersect_nearest_into(&narrowed, shape_hits);
+                            for hit in shape_hits.iter() {
+                                if keep(hit.t) {
+                                    closest = Some(hit.on(o, InstancePath::default()));
+                                }
+                            }
+                        }
+                        member => {
+                            hits.clear();
+                            member.intersect_into(ray, InstancePath::default(), &mut hits);
+                            for hit in hits.iter() {
+                                if keep(hit.t) {
+                                    closest = Some(*hit);
+                                }
+                            }
+                        }
+                    }
+                }
+                t_max
+            })
+        });
+        closest.map(|hit| hit.to_intersection())
+    }
+}
+
+impl LinearNode {
+    fn enter(&self, origin: &[f64; 3], inverse: &[f64; 3], t_min: f64, t_max: f64) -> Option<f64> {
+        enter(&self.min, &self.max, origin, inverse, t_min, t_max)
+    }
+}
+
+// where the ray enters the box inside the interval, the inverse direction
+// spares a division per axis
+pub(crate) fn enter(
+    min: &[f64; 3],
+    max: &[f64; 3],
+    origin: &[f64; 3],
+    inverse: &[f64; 3],
+    t_min: f64,
+    t_max: f64,
+) -> Option<f64> {
+    let (mut near, mut far) = (t_min, t_max);
+    for axis in 0..3 {
+        // a ray parallel to a slab is inside it all along or never
+        if inverse[axis].is_infinite() {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) * inverse[axis];
+        let t2 = (max[axis] - origin[axis]) * inverse[axis];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+    (near <= far).then_some(near)
+}
+
+fn tree(members: &[GroupMember]) -> Option<Tree> {
+    let mut leaf = Vec::new();
+    let mut children = Vec::new();
+    for member in members {
+        match member {
+            // empty groups are never hit
+            GroupMember::SubGroup(g) => {
+                if g.bounds().is_some() {
+                    children.extend(tree(g.members()));
+                }
+            }
+            member => leaf.push(member.clone()),
+        }
+    }
+    if !leaf.is_empty() {
+        let (mut min, mut max) = corners(&leaf[0]);
+        for member in &leaf[1..] {
+            let (low, high) = corners(member);
+            (min, max) = enclose((min, max), (low, high));
+        }
+        children.insert(0, Tree::Leaf(min, max, leaf));
+    }
+    pair(children)
+}
+
+fn pair(mut children: Vec<Tree>) -> Option<Tree> {
+    match children.len() {
+        0 => None,
+        1 => children.pop(),
+        n => {
+            let right = pair(children.split_off(n / 2)).unwrap();
+            let left = pair(children).unwrap();
+            let (min, max) = enclose(left.corners(), right.corners());
+            Some(Tree::Inner(min, max, Box::new(left), Box::new(right)))
+        }
+    }
+}
+
+impl Tree {
+    fn corners(&self) -> ([f64; 3], [f64; 3]) {
+        match self {
+            Tree::Leaf(min, max, _) | Tree::Inner(min, max, _, _) => (*min, *max),
+        }
+    }
+}
+
+// members without finite bounds get a box that every ray passes, the bounds
+// of transformed planes can even be turned inside out
+fn corners(member: &GroupMember) -> ([f64; 3], [f64; 3]) {
+    match member.bounds() {
+        Some(b) if is_finite(&b) => ([b.min.x, b.min.y, b.min.z], [b.max.x, b.max.y, b.max.z]),
+        _ => ([f64::NEG_INFINITY; 3], [f64::INFINITY; 3]),
+    }
+}
+
+fn enclose(a: ([f64; 3], [f64; 3]), b: ([f64; 3], [f64; 3])) -> ([f64; 3], [f64; 3]) {
+    (
+        [0, 1, 2].map(|i| a.0[i].min(b.0[i])),
+        [0, 1, 2].map(|i| a.1[i].max(b.1[i])),
+    )
+}
@@ -26,7 +26,7 @@ pub struct Camera {
     pub renderer: Renderer,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AntiAliasing {
     Off,
     Fast,
@@ -34,7 +34,7 @@ pub enum AntiAliasing {
     Dynamic,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Renderer {
     SingleThreaded,
     Multithreaded(usize),
@@ -45,32 +45,42 @@ impl Camera {
         let transform = identity_matrix();
         let transform_inverse = transform.inverse().unwrap();
 
-        let half_view = (field_of_view / 2.0).tan();
-        let aspect = hsize as f64 / vsize as f64;
-        let mut half_width = half_view * aspect;
-        let mut half_height = half_view;
-        if aspect >= 1.0 {
-            half_width = half_view;
-            half_height = half_view / aspect;
-        }
-        let pixel_size = (half_width * 2.0) / hsize as f64;
-
-        let antialiasing = AntiAliasing::from_env();
-        let renderer = Renderer::from_env();
+        // invalid settings are left to whoever asks from_env to report
+        let antialiasing = AntiAliasing::from_env().unwrap_or(AntiAliasing::Fast);
+        let renderer = Renderer::from_env().unwrap_or(Renderer::Multithreaded(num_cpus::get()));
 
-        Camera {
+        let mut camera = Camera {
             hsize,
             vsize,
             field_of_view,
             transform,
             transform_inverse,
-            pixel_size,
-            half_width,
-            half_height,
+            pixel_size: 0.0,
+            half_width: 0.0,
+            half_height: 0.0,
             ray_recursion_depth: RAY_RECURSION_DEPTH,
             antialiasing,
             renderer,
+        };
+        camera.set_resolution(hsize, vsize);
+        camera
+    }
+
+    pub fn set_resolution(&mut self, hsize: usize, vsize: usize) {
+        let half_view = (self.field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let mut half_width = half_view * aspect;
+        let mut half_height = half_view;
+        if aspect >= 1.0 {
+            half_width = half_view;
+            half_height = half_view / aspect;
         }
+
+        self.hsize = hsize;
+        self.vsize = vsize;
+        self.half_width = half_width;
+        self.half_height = half_height;
+        self.pixel_size = (half_width * 2.0) / hsize as f64;
     }
 
     pub fn set_transform(&mut self, transform: Matrix4x4) {
@@ -113,15 +123,24 @@ impl Camera {
 }
 
 impl AntiAliasing {
-    pub fn from_env() -> Self {
+    pub fn from_env() -> Result<Self, String> {
         match env::var("ANTIALIASING") {
-            Err(_) => Self::Fast,
-            Ok(antialiasing) => match antialiasing.to_lowercase().as_str() {
-                "" => Self::Fast,
-                "off" => Self::Off,
-                "fast" => Self::Fast,
-                "dynamic" => Self::Dynamic,
-                s => Self::Raster(s.parse::<usize>().unwrap()),
+            Err(_) => Ok(Self::Fast),
+            Ok(antialiasing) => {
+                Self::parse(&antialiasing).map_err(|err| format!("ANTIALIASING: {}", err))
+            }
+        }
+    }
+
+    pub fn parse(antialiasing: &str) -> Result<Self, String> {
+        match antialiasing.to_lowercase().as_str() {
+            "" => Ok(Self::Fast),
+            "off" => Ok(Self::Off),
+            "fast" => Ok(Self::Fast),
+            "dynamic" => Ok(Self::Dynamic),
+            s => match s.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Self::Raster(n)),
+                _ => Err(format!("unknown antialiasing mode '{}'", antialiasing)),
             },
         }
     }
@@ -232,13 +251,20 @@ impl AntiAliasing {
 }
 
 impl Renderer {
-    pub fn from_env() -> Self {
+    pub fn from_env() -> Result<Self, String> {
         match env::var("CORES") {
-            Err(_) => Self::Multithreaded(num_cpus::get()),
-            Ok(antialiasing) => match antialiasing.to_lowercase().as_str() {
-                "" => Self::Multithreaded(num_cpus::get()),
-                "off" => Self::SingleThreaded,
-                cores => Self::Multithreaded(cores.parse::<usize>().unwrap()),
+            Err(_) => Ok(Self::Multithreaded(num_cpus::get())),
+            Ok(cores) => Self::parse(&cores).map_err(|err| format!("CORES: {}", err)),
+        }
+    }
+
+    pub fn parse(cores: &str) -> Result<Self, String> {
+        match cores.to_lowercase().as_str() {
+            "" => Ok(Self::Multithreaded(num_cpus::get())),
+            "off" => Ok(Self::SingleThreaded),
+            s => match s.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Self::Multithreaded(n)),
+                _ => Err(format!("invalid number of threads '{}'", cores)),
             },
         }
     }
@@ -1,14 +1,29 @@
 // use crate::{colors::color, tuples::Tuple};
 // use super::colors::Color;
-use std::io::{Result, Write};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::Path,
+};
 
 use crate::tuples::{color, Tuple};
 
+pub mod compare;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+mod zlib;
+
+pub use png::{BitDepth, PngFormat};
+
 #[derive(Debug)]
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<Tuple>,
+    // only allocated once a pixel is not fully opaque
+    alpha: Option<Vec<f64>>,
 }
 
 impl Canvas {
@@ -17,6 +32,7 @@ impl Canvas {
             width,
             height,
             pixels: Vec::with_capacity(width * height),
+            alpha: None,
         };
         for _ in 0..(width * height) {
             let black = color(0.0, 0.0, 0.0);
@@ -45,6 +61,85 @@ impl Canvas {
         }
     }
 
+    pub fn alpha_at(&self, w: usize, h: usize) -> f64 {
+        match &self.alpha {
+            Some(alpha) => alpha[self.index(w, h)],
+            None => 1.0,
+        }
+    }
+
+    pub fn set_alpha(&mut self, w: usize, h: usize, a: f64) {
+        let i = self.index(w, h);
+        let size = self.width * self.height;
+        self.alpha.get_or_insert_with(|| vec![1.0; size])[i] = a;
+    }
+
+    // writes the image in the format matching the file extension
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let extension = file_extension(path);
+        let write = match extension.as_str() {
+            "ppm" => {
+                |canvas: &Canvas, writer: &mut dyn Write| canvas.ppm_binary(writer, BitDepth::Eight)
+            }
+            "png" => Canvas::png,
+            "pfm" => Canvas::pfm,
+            "hdr" => Canvas::hdr,
+            _ => return Err(unsupported_format(&extension)),
+        };
+        let writer = &mut BufWriter::with_capacity(1024 * 128, File::create(path)?);
+        write(self, writer)?;
+        writer.flush()
+    }
+
+    // reads an image in the format matching the file extension
+    pub fn load(path: &Path) -> Result<Canvas> {
+        let extension = file_extension(path);
+        let read = match extension.as_str() {
+            "ppm" | "pgm" => Canvas::from_ppm,
+            "png" => Canvas::from_png,
+            "pfm" => Canvas::from_pfm,
+            "hdr" => Canvas::from_hdr,
+            _ => return Err(unsupported_format(&extension)),
+        };
+        read(&mut BufReader::new(File::open(path)?))
+    }
+
+    pub fn pfm(&self, writer: &mut dyn Write) -> Result<()> {
+        pfm::write(self, writer)
+    }
+
+    pub fn from_pfm(reader: &mut dyn Read) -> Result<Canvas> {
+        pfm::read(reader)
+    }
+
+    pub fn hdr(&self, writer: &mut dyn Write) -> Result<()> {
+        hdr::write(self, writer)
+    }
+
+    pub fn from_hdr(reader: &mut dyn Read) -> Result<Canvas> {
+        hdr::read(reader)
+    }
+
+    pub fn png(&self, writer: &mut dyn Write) -> Result<()> {
+        png::write(self, writer, &PngFormat::default())
+    }
+
+    pub fn from_png(reader: &mut dyn Read) -> Result<Canvas> {
+        png::read(reader)
+    }
+
+    pub fn png_with(&self, writer: &mut dyn Write, format: &PngFormat) -> Result<()> {
+        png::write(self, writer, format)
+    }
+
+    pub fn ppm_binary(&self, writer: &mut dyn Write, depth: BitDepth) -> Result<()> {
+        ppm::write_binary(self, writer, depth)
+    }
+
+    pub fn from_ppm(reader: &mut dyn Read) -> Result<Canvas> {
+        ppm::read(reader)
+    }
+
     pub fn ppm(&self, writer: &mut dyn Write) -> Result<()> {
         writer.write_all(b"P3\n")?;
         writer.write_all(format!("{} {}\n", self.width, self.height).as_bytes())?;
@@ -69,6 +164,70 @@ impl Canvas {
     }
 }
 
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn unsupported_format(extension: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("unsupported image format '{}'", extension),
+    )
+}
+
+fn invalid_data(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
+
+// whitespace separated tokens of the text headers of netpbm style files
+struct HeaderReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> HeaderReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        HeaderReader { data, position: 0 }
+    }
+
+    fn token(&mut self) -> Result<&'a str> {
+        while let Some(&c) = self.data.get(self.position) {
+            if c == b'#' {
+                while self.data.get(self.position).is_some_and(|&c| c != b'\n') {
+                    self.position += 1;
+                }
+            } else if c.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        let start = self.position;
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|c| !c.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+        if start == self.position {
+            return Err(invalid_data("unexpected end of header"));
+        }
+        std::str::from_utf8(&self.data[start..self.position])
+            .map_err(|_| invalid_data("invalid header"))
+    }
+
+    fn number(&mut self) -> Result<usize> {
+        let token = self.token()?;
+        token
+            .parse()
+            .map_err(|_| invalid_data(format!("'{}' is not a number", token)))
+    }
+}
+
 fn add_color(w: &mut dyn Write, mut length: u8, c: f64) -> Result<u8> {
     let r = clamp(c * 255.0, 0, 255);
     let original_length = length;
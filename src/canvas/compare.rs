@@ -0,0 +1,159 @@
+// Metrics for comparing two renders of the same size. Differences are computed
+// on the raw channel values, SSIM on the luminance clamped to 0..1.
+use super::Canvas;
+use crate::tuples::{color, Tuple};
+
+const SSIM_WINDOW: usize = 8;
+const SSIM_STRIDE: usize = 4;
+const SSIM_C1: f64 = 0.01 * 0.01;
+const SSIM_C2: f64 = 0.03 * 0.03;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub max_difference: f64,
+    pub rmse: f64,
+    pub psnr: f64,
+    pub ssim: f64,
+}
+
+pub fn compare(a: &Canvas, b: &Canvas) -> Comparison {
+    let max_difference = channel_differences(a, b).fold(0.0, f64::max);
+    Comparison {
+        max_difference,
+        rmse: rmse(a, b),
+        psnr: psnr(a, b),
+        ssim: ssim(a, b),
+    }
+}
+
+// the absolute difference of every channel
+pub fn difference(a: &Canvas, b: &Canvas) -> Canvas {
+    check_sizes(a, b);
+    let mut canvas = Canvas::new(a.width, a.height);
+    for (i, (p, q)) in a.pixels.iter().zip(&b.pixels).enumerate() {
+        canvas.pixels[i] = color((p.x - q.x).abs(), (p.y - q.y).abs(), (p.z - q.z).abs());
+    }
+    canvas
+}
+
+pub fn rmse(a: &Canvas, b: &Canvas) -> f64 {
+    let count = (a.pixels.len() * 3).max(1) as f64;
+    let sum: f64 = channel_differences(a, b).map(|d| d * d).sum();
+    (sum / count).sqrt()
+}
+
+// peak signal to noise ratio in decibels for a peak value of 1.0, identical
+// images have an infinite ratio
+pub fn psnr(a: &Canvas, b: &Canvas) -> f64 {
+    let rmse = rmse(a, b);
+    if rmse == 0.0 {
+        return f64::INFINITY;
+    }
+    -20.0 * rmse.log10()
+}
+
+// mean structural similarity over overlapping windows, 1.0 for identical images
+pub fn ssim(a: &Canvas, b: &Canvas) -> f64 {
+    check_sizes(a, b);
+    let la: Vec<f64> = a.pixels.iter().map(luminance).collect();
+    let lb: Vec<f64> = b.pixels.iter().map(luminance).collect();
+
+    let window_width = SSIM_WINDOW.min(a.width);
+    let window_height = SSIM_WINDOW.min(a.height);
+    if window_width == 0 || window_height == 0 {
+        return 1.0;
+    }
+
+    let mut total = 0.0;
+    let mut windows = 0;
+    for y in window_starts(a.height, window_height) {
+        for x in window_starts(a.width, window_width) {
+            let indices = (y..y + window_height)
+                .flat_map(|h| (x..x + window_width).map(move |w| w + h * a.width));
+            let n = (window_width * window_height) as f64;
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for i in indices {
+                sa += la[i];
+                sb += lb[i];
+                saa += la[i] * la[i];
+                sbb += lb[i] * lb[i];
+                sab += la[i] * lb[i];
+            }
+            let (mean_a, mean_b) = (sa / n, sb / n);
+            let variance_a = saa / n - mean_a * mean_a;
+            let variance_b = sbb / n - mean_b * mean_b;
+            let covariance = sab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * covariance + SSIM_C2))
+                / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1)
+                    * (variance_a + variance_b + SSIM_C2));
+            windows += 1;
+        }
+    }
+    total / windows as f64
+}
+
+// colors the largest channel difference of every pixel from black over blue,
+// red and yellow to white, white marking the largest difference in the image
+pub fn heatmap(a: &Canvas, b: &Canvas) -> Canvas {
+    let difference = difference(a, b);
+    let errors: Vec<f64> = difference
+        .pixels
+        .iter()
+        .map(|d| d.x.max(d.y).max(d.z))
+        .collect();
+    let max = errors.iter().cloned().fold(0.0, f64::max);
+
+    let ramp = [
+        color(0.0, 0.0, 0.0),
+        color(0.0, 0.0, 1.0),
+        color(1.0, 0.0, 0.0),
+        color(1.0, 1.0, 0.0),
+        color(1.0, 1.0, 1.0),
+    ];
+    let mut canvas = Canvas::new(a.width, a.height);
+    if max == 0.0 {
+        return canvas;
+    }
+    for (i, error) in errors.iter().enumerate() {
+        let position = error / max * (ramp.len() - 1) as f64;
+        let segment = (position.floor() as usize).min(ramp.len() - 2);
+        let t = position - segment as f64;
+        canvas.pixels[i] = &ramp[segment] * (1.0 - t) + &ramp[segment + 1] * t;
+    }
+    canvas
+}
+
+fn check_sizes(a: &Canvas, b: &Canvas) {
+    assert!(
+        a.width == b.width && a.height == b.height,
+        "cannot compare a {}x{} canvas to a {}x{} canvas",
+        a.width,
+        a.height,
+        b.width,
+        b.height
+    );
+}
+
+fn channel_differences<'a>(a: &'a Canvas, b: &'a Canvas) -> impl Iterator<Item = f64> + 'a {
+    check_sizes(a, b);
+    a.pixels
+        .iter()
+        .zip(&b.pixels)
+        .flat_map(|(p, q)| [p.x - q.x, p.y - q.y, p.z - q.z])
+        .map(f64::abs)
+}
+
+fn luminance(c: &Tuple) -> f64 {
+    (0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z).clamp(0.0, 1.0)
+}
+
+// window positions covering the whole length, the last one aligned to the end
+fn window_starts(length: usize, window: usize) -> Vec<usize> {
+    let last = length - window;
+    let mut starts: Vec<usize> = (0..=last).step_by(SSIM_STRIDE).collect();
+    if starts.last() != Some(&last) {
+        starts.push(last);
+    }
+    starts
+}
@@ -0,0 +1,247 @@
+// Radiance RGBE images: three 8-bit mantissas sharing one exponent per pixel.
+// Scanlines are written with the run length encoding of newer Radiance
+// versions, which stores each channel separately.
+use std::io::{Read, Result, Write};
+
+use super::{invalid_data, Canvas};
+use crate::tuples::{color, Tuple};
+
+const MIN_RUN: usize = 4;
+const MAX_RUN: usize = 127;
+// the largest image a header may ask for, 8192 by 8192 pixels
+const MAX_PIXELS: usize = 1 << 26;
+
+pub fn write(canvas: &Canvas, writer: &mut dyn Write) -> Result<()> {
+    writer.write_all(b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n")?;
+    writer.write_all(format!("-Y {} +X {}\n", canvas.height, canvas.width).as_bytes())?;
+
+    let mut scanline = Vec::with_capacity(canvas.width * 4);
+    for h in 0..canvas.height {
+        let pixels: Vec<[u8; 4]> = (0..canvas.width).map(|w| rgbe(canvas.at(w, h))).collect();
+        scanline.clear();
+        // only these widths can be run length encoded
+        if (8..0x8000).contains(&canvas.width) {
+            scanline.extend_from_slice(&[2, 2, (canvas.width >> 8) as u8, canvas.width as u8]);
+            for channel in 0..4 {
+                let values: Vec<u8> = pixels.iter().map(|p| p[channel]).collect();
+                encode_runs(&values, &mut scanline);
+            }
+        } else {
+            scanline.extend(pixels.iter().flatten());
+        }
+        writer.write_all(&scanline)?;
+    }
+    Ok(())
+}
+
+fn encode_runs(values: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < values.len() {
+        // find the next run long enough to be worth encoding
+        let mut start = i;
+        let mut run = 0;
+        while start < values.len() {
+            run = values[start..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|&&v| v == values[start])
+                .count();
+            if run >= MIN_RUN {
+                break;
+            }
+            start += run;
+        }
+        let start = start.min(values.len());
+
+        for literals in values[i..start].chunks(128) {
+            out.push(literals.len() as u8);
+            out.extend_from_slice(literals);
+        }
+        if start < values.len() {
+            out.push(128 + run as u8);
+            out.push(values[start]);
+            i = start + run;
+        } else {
+            i = start;
+        }
+    }
+}
+
+fn rgbe(c: &Tuple) -> [u8; 4] {
+    let (r, g, b) = (c.x.max(0.0), c.y.max(0.0), c.z.max(0.0));
+    let v = r.max(g).max(b);
+    if v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // v = m * 2^e with m in [0.5, 1)
+    let mut e = v.log2().floor() as i32 + 1;
+    if v / 2f64.powi(e) >= 1.0 {
+        e += 1;
+    }
+    let scale = 256.0 / 2f64.powi(e);
+    [
+        (r * scale).min(255.0) as u8,
+        (g * scale).min(255.0) as u8,
+        (b * scale).min(255.0) as u8,
+        (e + 128).clamp(0, 255) as u8,
+    ]
+}
+
+fn from_rgbe(p: &[u8]) -> Tuple {
+    if p[3] == 0 {
+        return color(0.0, 0.0, 0.0);
+    }
+    let f = 2f64.powi(p[3] as i32 - (128 + 8));
+    color(p[0] as f64 * f, p[1] as f64 * f, p[2] as f64 * f)
+}
+
+pub fn read(reader: &mut dyn Read) -> Result<Canvas> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut lines = data.split(|&b| b == b'\n');
+    let mut position = 0;
+    let mut next_line = || {
+        let line = lines.next()?;
+        position += line.len() + 1;
+        Some(String::from_utf8_lossy(line).trim().to_string())
+    };
+
+    match next_line() {
+        Some(magic) if magic.starts_with("#?") => {}
+        _ => return Err(invalid_data("not a radiance file")),
+    }
+    loop {
+        match next_line() {
+            None => return Err(invalid_data("radiance header is not terminated")),
+            Some(line) if line.is_empty() => break,
+            Some(line) => {
+                if let Some(format) = line.strip_prefix("FORMAT=") {
+                    if format != "32-bit_rle_rgbe" {
+                        return Err(invalid_data(format!("unsupported format '{}'", format)));
+                    }
+                }
+            }
+        }
+    }
+    let resolution = next_line().unwrap_or_default();
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", height, "+X", width] => (height.parse().ok(), width.parse().ok()),
+        _ => (None, None),
+    };
+    let (height, width): (usize, usize) = match (height, width) {
+        (Some(height), Some(width)) => (height, width),
+        _ => {
+            return Err(invalid_data(format!(
+                "unsupported resolution '{}'",
+                resolution
+            )))
+        }
+    };
+
+    // the header alone must not decide how much is allocated: every
+    // scanline takes at least a pixel of the data, while runs make wide
+    // scanlines cheap so their size is capped
+    if height > (data.len() - position.min(data.len())) / 4 {
+        return Err(invalid_data("radiance file is truncated"));
+    }
+    if width
+        .checked_mul(height)
+        .is_none_or(|pixels| pixels > MAX_PIXELS)
+    {
+        return Err(invalid_data(format!(
+            "a {}x{} image is too large",
+            width, height
+        )));
+    }
+
+    let mut decoder = Decoder {
+        data: &data,
+        position,
+    };
+    let mut canvas = Canvas::new(width, height);
+    for h in 0..height {
+        let scanline = decoder.scanline(width)?;
+        for (w, pixel) in scanline.chunks(4).enumerate() {
+            canvas.set(w, h, from_rgbe(pixel));
+        }
+    }
+    Ok(canvas)
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn byte(&mut self) -> Result<u8> {
+        let byte = self
+            .data
+            .get(self.position)
+            .ok_or_else(|| invalid_data("radiance file is truncated"))?;
+        self.position += 1;
+        Ok(*byte)
+    }
+
+    fn pixel(&mut self) -> Result<[u8; 4]> {
+        Ok([self.byte()?, self.byte()?, self.byte()?, self.byte()?])
+    }
+
+    fn scanline(&mut self, width: usize) -> Result<Vec<u8>> {
+        let mut scanline = vec![0; width * 4];
+        let start = self.position;
+        let first = self.pixel()?;
+        let encoded = (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2;
+        if encoded && first[2] & 0x80 == 0 {
+            if ((first[2] as usize) << 8 | first[3] as usize) != width {
+                return Err(invalid_data("scanline width mismatch"));
+            }
+            for channel in 0..4 {
+                let mut w = 0;
+                while w < width {
+                    let count = self.byte()? as usize;
+                    let (count, run) = if count > 128 {
+                        (count - 128, true)
+                    } else {
+                        (count, false)
+                    };
+                    if count == 0 || w + count > width {
+                        return Err(invalid_data("invalid scanline run"));
+                    }
+                    let value = if run { self.byte()? } else { 0 };
+                    for _ in 0..count {
+                        scanline[w * 4 + channel] = if run { value } else { self.byte()? };
+                        w += 1;
+                    }
+                }
+            }
+            return Ok(scanline);
+        }
+
+        // flat pixels, possibly with the repeat markers of old Radiance versions
+        self.position = start;
+        let mut w = 0;
+        let mut shift = 0;
+        while w < width {
+            let pixel = self.pixel()?;
+            if pixel[0..3] == [1, 1, 1] && w > 0 {
+                let count = (pixel[3] as usize) << shift;
+                if w + count > width {
+                    return Err(invalid_data("invalid scanline run"));
+                }
+                let previous: Vec<u8> = scanline[(w - 1) * 4..w * 4].to_vec();
+                for _ in 0..count {
+                    scanline[w * 4..w * 4 + 4].copy_from_slice(&previous);
+                    w += 1;
+                }
+                shift += 8;
+            } else {
+                scanline[w * 4..w * 4 + 4].copy_from_slice(&pixel);
+                w += 1;
+                shift = 0;
+            }
+        }
+        Ok(scanline)
+    }
+}
@@ -0,0 +1,70 @@
+// Portable float maps: a short text header followed by 32-bit floats per
+// channel, rows stored from the bottom of the image to the top. A negative
+// scale in the header marks little endian data.
+use std::io::{Read, Result, Write};
+
+use super::{invalid_data, Canvas, HeaderReader};
+use crate::tuples::color;
+
+pub fn write(canvas: &Canvas, writer: &mut dyn Write) -> Result<()> {
+    writer.write_all(format!("PF\n{} {}\n-1.0\n", canvas.width, canvas.height).as_bytes())?;
+
+    let mut row = Vec::with_capacity(canvas.width * 12);
+    for h in (0..canvas.height).rev() {
+        row.clear();
+        for w in 0..canvas.width {
+            let c = canvas.at(w, h);
+            for sample in [c.x, c.y, c.z] {
+                row.extend_from_slice(&(sample as f32).to_le_bytes());
+            }
+        }
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
+
+pub fn read(reader: &mut dyn Read) -> Result<Canvas> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut header = HeaderReader::new(&data);
+    let channels = match header.token()? {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => return Err(invalid_data(format!("unknown pfm type '{}'", magic))),
+    };
+    let width = header.number()?;
+    let height = header.number()?;
+    let scale: f32 = header
+        .token()?
+        .parse()
+        .map_err(|_| invalid_data("invalid pfm scale"))?;
+    // a single whitespace character separates the header from the samples
+    let samples = data.get(header.position + 1..).unwrap_or_default();
+
+    if samples.len() < width * height * channels * 4 {
+        return Err(invalid_data("pfm file is truncated"));
+    }
+    let sample = |i: usize| {
+        let bytes = samples[i * 4..i * 4 + 4].try_into().unwrap();
+        let value = if scale < 0.0 {
+            f32::from_le_bytes(bytes)
+        } else {
+            f32::from_be_bytes(bytes)
+        };
+        value as f64
+    };
+
+    let mut canvas = Canvas::new(width, height);
+    for h in 0..height {
+        for w in 0..width {
+            let i = ((height - 1 - h) * width + w) * channels;
+            let c = match channels {
+                1 => color(sample(i), sample(i), sample(i)),
+                _ => color(sample(i), sample(i + 1), sample(i + 2)),
+            };
+            canvas.set(w, h, c);
+        }
+    }
+    Ok(canvas)
+}
@@ -0,0 +1,320 @@
+use std::io::{Read, Result, Write};
+
+use super::{invalid_data, zlib, Canvas};
+use crate::tuples::color;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PngFormat {
+    pub depth: BitDepth,
+    pub alpha: bool,
+}
+
+impl Default for PngFormat {
+    fn default() -> Self {
+        PngFormat {
+            depth: BitDepth::Eight,
+            alpha: false,
+        }
+    }
+}
+
+pub fn write(canvas: &Canvas, writer: &mut dyn Write, format: &PngFormat) -> Result<()> {
+    let (bit_depth, bytes_per_sample) = match format.depth {
+        BitDepth::Eight => (8, 1),
+        BitDepth::Sixteen => (16, 2),
+    };
+    let (color_type, channels) = if format.alpha { (6, 4) } else { (2, 3) };
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(canvas.width as u32).to_be_bytes());
+    header.extend_from_slice(&(canvas.height as u32).to_be_bytes());
+    // no interlacing, the only compression and filter methods png defines
+    header.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+
+    let bytes_per_pixel = channels * bytes_per_sample;
+    let mut scanlines = Vec::with_capacity((1 + canvas.width * bytes_per_pixel) * canvas.height);
+    let mut previous = vec![0; canvas.width * bytes_per_pixel];
+    let mut row = Vec::with_capacity(canvas.width * bytes_per_pixel);
+    for h in 0..canvas.height {
+        row.clear();
+        for w in 0..canvas.width {
+            let c = canvas.at(w, h);
+            let mut samples = vec![c.x, c.y, c.z];
+            if format.alpha {
+                samples.push(canvas.alpha_at(w, h));
+            }
+            for sample in samples {
+                match format.depth {
+                    BitDepth::Eight => row.push(quantize(sample, 255.0) as u8),
+                    BitDepth::Sixteen => {
+                        row.extend_from_slice(&quantize(sample, 65535.0).to_be_bytes())
+                    }
+                }
+            }
+        }
+        filter(&row, &previous, bytes_per_pixel, &mut scanlines);
+        std::mem::swap(&mut row, &mut previous);
+    }
+
+    writer.write_all(&SIGNATURE)?;
+    chunk(writer, b"IHDR", &header)?;
+    chunk(writer, b"IDAT", &zlib::compress(&scanlines))?;
+    chunk(writer, b"IEND", &[])
+}
+
+// reads gray, gray with alpha, truecolor, truecolor with alpha and palette
+// images of every bit depth, interlaced images are not supported
+pub fn read(reader: &mut dyn Read) -> Result<Canvas> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    if !data.starts_with(&SIGNATURE) {
+        return Err(invalid_data("not a png file"));
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+    let mut rest = &data[SIGNATURE.len()..];
+    loop {
+        if rest.len() < 12 {
+            return Err(invalid_data("png file is truncated"));
+        }
+        let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() < 12 + length {
+            return Err(invalid_data("png file is truncated"));
+        }
+        let kind = &rest[4..8];
+        let content = &rest[8..8 + length];
+        let crc = &rest[8 + length..12 + length];
+        if crc32(&[kind, content]).to_be_bytes() != crc {
+            return Err(invalid_data(format!(
+                "crc mismatch in {} chunk",
+                String::from_utf8_lossy(kind)
+            )));
+        }
+        match kind {
+            b"IHDR" => header = Some(content),
+            b"PLTE" => palette = content,
+            b"tRNS" => transparency = content,
+            b"IDAT" => compressed.extend_from_slice(content),
+            b"IEND" => break,
+            _ => {}
+        }
+        rest = &rest[12 + length..];
+    }
+
+    let header = match header {
+        Some(header) if header.len() == 13 => header,
+        _ => return Err(invalid_data("png file has no header")),
+    };
+    let width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let (bit_depth, color_type) = (header[8] as usize, header[9]);
+    if header[12] != 0 {
+        return Err(invalid_data("interlaced png files are not supported"));
+    }
+    let channels = match (color_type, bit_depth) {
+        (0, 1 | 2 | 4 | 8 | 16) => 1,
+        (3, 1 | 2 | 4 | 8) => 1,
+        (4, 8 | 16) => 2,
+        (2, 8 | 16) => 3,
+        (6, 8 | 16) => 4,
+        _ => {
+            return Err(invalid_data(format!(
+                "unsupported png color type {} with bit depth {}",
+                color_type, bit_depth
+            )))
+        }
+    };
+    if color_type == 3 && palette.is_empty() {
+        return Err(invalid_data("png file has no palette"));
+    }
+
+    let scanlines = zlib::decompress(&compressed)?;
+    let bits_per_pixel = channels * bit_depth;
+    let row_size = (width * bits_per_pixel).div_ceil(8);
+    if scanlines.len() < (row_size + 1) * height {
+        return Err(invalid_data("png image data is truncated"));
+    }
+
+    let bpp = bits_per_pixel.div_ceil(8);
+    let max = ((1u32 << bit_depth) - 1) as f64;
+    let mut canvas = Canvas::new(width, height);
+    let mut previous = vec![0; row_size];
+    let mut row = vec![0; row_size];
+    for h in 0..height {
+        let line = &scanlines[h * (row_size + 1)..(h + 1) * (row_size + 1)];
+        unfilter(line[0], &line[1..], &previous, bpp, &mut row)?;
+        // samples are packed from the most significant bit
+        let sample = |i: usize| -> u32 {
+            match bit_depth {
+                16 => (row[i * 2] as u32) << 8 | row[i * 2 + 1] as u32,
+                8 => row[i] as u32,
+                _ => {
+                    let bit = i * bit_depth;
+                    let shift = 8 - bit_depth - bit % 8;
+                    (row[bit / 8] as u32 >> shift) & ((1 << bit_depth) - 1)
+                }
+            }
+        };
+        for w in 0..width {
+            let i = w * channels;
+            let value = |i: usize| sample(i) as f64 / max;
+            let (c, alpha) = match color_type {
+                0 => {
+                    let alpha = match transparency {
+                        [high, low] if sample(i) == (*high as u32) << 8 | *low as u32 => 0.0,
+                        _ => 1.0,
+                    };
+                    (color(value(i), value(i), value(i)), alpha)
+                }
+                3 => {
+                    let index = sample(i) as usize;
+                    let entry = match palette.get(index * 3..index * 3 + 3) {
+                        Some(entry) => entry,
+                        None => return Err(invalid_data("palette index is out of range")),
+                    };
+                    let alpha = transparency.get(index).map_or(1.0, |&a| a as f64 / 255.0);
+                    let [r, g, b] = [entry[0], entry[1], entry[2]].map(|c| c as f64 / 255.0);
+                    (color(r, g, b), alpha)
+                }
+                4 => (color(value(i), value(i), value(i)), value(i + 1)),
+                2 => (color(value(i), value(i + 1), value(i + 2)), 1.0),
+                _ => (color(value(i), value(i + 1), value(i + 2)), value(i + 3)),
+            };
+            canvas.set(w, h, c);
+            if alpha < 1.0 {
+                canvas.set_alpha(w, h, alpha);
+            }
+        }
+        std::mem::swap(&mut row, &mut previous);
+    }
+    Ok(canvas)
+}
+
+fn unfilter(
+    filter_type: u8,
+    line: &[u8],
+    previous: &[u8],
+    bpp: usize,
+    row: &mut [u8],
+) -> Result<()> {
+    for i in 0..line.len() {
+        let a = if i >= bpp { row[i - bpp] } else { 0 };
+        let b = previous[i];
+        let c = if i >= bpp { previous[i - bpp] } else { 0 };
+        let predictor = match filter_type {
+            0 => 0,
+            1 => a,
+            2 => b,
+            3 => ((a as u16 + b as u16) / 2) as u8,
+            4 => paeth(a, b, c),
+            _ => return Err(invalid_data(format!("unknown png filter {}", filter_type))),
+        };
+        row[i] = line[i].wrapping_add(predictor);
+    }
+    Ok(())
+}
+
+fn quantize(c: f64, max: f64) -> u16 {
+    (c * max).round().clamp(0.0, max) as u16
+}
+
+// picks the filter with the smallest sum of absolute differences, the
+// heuristic suggested by the png specification
+fn filter(row: &[u8], previous: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    let mut best: Option<(u64, u8, Vec<u8>)> = None;
+    for filter_type in 0..5u8 {
+        let filtered: Vec<u8> = (0..row.len())
+            .map(|i| {
+                let a = if i >= bpp { row[i - bpp] } else { 0 };
+                let b = previous[i];
+                let c = if i >= bpp { previous[i - bpp] } else { 0 };
+                let predictor = match filter_type {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    _ => paeth(a, b, c),
+                };
+                row[i].wrapping_sub(predictor)
+            })
+            .collect();
+        let cost = filtered
+            .iter()
+            .map(|&v| (v as i8).unsigned_abs() as u64)
+            .sum();
+        if best
+            .as_ref()
+            .is_none_or(|(best_cost, _, _)| cost < *best_cost)
+        {
+            best = Some((cost, filter_type, filtered));
+        }
+    }
+    let (_, filter_type, filtered) = best.unwrap();
+    out.push(filter_type);
+    out.extend_from_slice(&filtered);
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn chunk(writer: &mut dyn Write, kind: &[u8; 4], data: &[u8]) -> Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(&[kind, data]);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+pub fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = 0xffffffff;
+    for part in parts {
+        for &byte in part.iter() {
+            crc = CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+        }
+    }
+    crc ^ 0xffffffff
+}
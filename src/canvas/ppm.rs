@@ -0,0 +1,88 @@
+// Binary netpbm output and a reader for the ascii and binary variants of
+// portable pixmaps and graymaps.
+use std::io::{Read, Result, Write};
+
+use super::{invalid_data, BitDepth, Canvas, HeaderReader};
+use crate::tuples::color;
+
+pub fn write_binary(canvas: &Canvas, writer: &mut dyn Write, depth: BitDepth) -> Result<()> {
+    let maxval = match depth {
+        BitDepth::Eight => 255,
+        BitDepth::Sixteen => 65535,
+    };
+    writer.write_all(format!("P6\n{} {}\n{}\n", canvas.width, canvas.height, maxval).as_bytes())?;
+
+    let mut row = Vec::with_capacity(canvas.width * 6);
+    for h in 0..canvas.height {
+        row.clear();
+        for w in 0..canvas.width {
+            let c = canvas.at(w, h);
+            for sample in [c.x, c.y, c.z] {
+                let value = (sample * maxval as f64).round().clamp(0.0, maxval as f64) as u16;
+                match depth {
+                    BitDepth::Eight => row.push(value as u8),
+                    BitDepth::Sixteen => row.extend_from_slice(&value.to_be_bytes()),
+                }
+            }
+        }
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
+
+pub fn read(reader: &mut dyn Read) -> Result<Canvas> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut header = HeaderReader::new(&data);
+    let (channels, binary) = match header.token()? {
+        "P2" => (1, false),
+        "P3" => (3, false),
+        "P5" => (1, true),
+        "P6" => (3, true),
+        magic => return Err(invalid_data(format!("unknown ppm type '{}'", magic))),
+    };
+    let width = header.number()?;
+    let height = header.number()?;
+    let maxval = header.number()?;
+    if maxval == 0 || maxval > 65535 {
+        return Err(invalid_data(format!("invalid maxval {}", maxval)));
+    }
+
+    let count = width * height * channels;
+    let samples: Vec<usize> = if binary {
+        // a single whitespace character separates the header from the samples
+        let bytes = data.get(header.position + 1..).unwrap_or_default();
+        let size = if maxval > 255 { 2 } else { 1 };
+        if bytes.len() < count * size {
+            return Err(invalid_data("ppm file is truncated"));
+        }
+        bytes
+            .chunks(size)
+            .take(count)
+            .map(|b| match b {
+                [high, low] => (*high as usize) << 8 | *low as usize,
+                _ => b[0] as usize,
+            })
+            .collect()
+    } else {
+        (0..count).map(|_| header.number()).collect::<Result<_>>()?
+    };
+    if let Some(sample) = samples.iter().find(|&&s| s > maxval) {
+        return Err(invalid_data(format!("sample {} exceeds maxval", sample)));
+    }
+
+    let mut canvas = Canvas::new(width, height);
+    let value = |i: usize| samples[i] as f64 / maxval as f64;
+    for h in 0..height {
+        for w in 0..width {
+            let i = (h * width + w) * channels;
+            let c = match channels {
+                1 => color(value(i), value(i), value(i)),
+                _ => color(value(i), value(i + 1), value(i + 2)),
+            };
+            canvas.set(w, h, c);
+        }
+    }
+    Ok(canvas)
+}
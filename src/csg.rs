@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::{
     groups::{Group, GroupMember, AABB},
-    intersections::Intersection,
+    intersections::{Hit, InstancePath, Intersection},
     materials::Material,
     matrices::Matrix4x4,
     objects::Object,
@@ -18,21 +18,55 @@ pub enum CSG {
 
 impl CSG {
     pub fn intersect(&self, ray: &Ray) -> Vec<Intersection> {
+        let mut xs = Vec::new();
+        self.intersect_into(ray, InstancePath::default(), &mut xs);
+        xs.iter().map(Hit::to_intersection).collect()
+    }
+
+    // appends the hits that are left of both children, sorted
+    pub fn intersect_into<'a>(
+        &'a self,
+        ray: &Ray,
+        instances: InstancePath<'a>,
+        xs: &mut Vec<Hit<'a>>,
+    ) {
         let (l, r) = match self {
             CSG::Union(l, r) => (l, r),
             CSG::Intersection(l, r) => (l, r),
             CSG::Difference(l, r) => (l, r),
         };
 
-        let mut leftxs = l.intersect(ray);
-        let mut rightxs = r.intersect(ray);
-        let mut xs = Vec::new();
-        xs.append(&mut leftxs);
-        xs.append(&mut rightxs);
-
-        xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
-
-        self.filter_intersections(&xs)
+        // whether a hit is on the left is known from the child that found
+        // it, the same objects may be part of both through instances
+        let start = xs.len();
+        l.intersect_into(ray, instances, xs);
+        let split = xs.len();
+        r.intersect_into(ray, instances, xs);
+        let end = xs.len();
+        let by_t = |a: &Hit, b: &Hit| a.t.partial_cmp(&b.t).unwrap();
+        xs[start..split].sort_by(by_t);
+        xs[split..end].sort_by(by_t);
+
+        // the hits of both children in order, the ones that are kept are
+        // appended before the children's are removed
+        let (mut i, mut j) = (start, split);
+        let mut inl = false;
+        let mut inr = false;
+        while i < split || j < end {
+            let lhit = j == end || (i < split && xs[i].t <= xs[j].t);
+            let hit = if lhit { xs[i] } else { xs[j] };
+            if self.intersection_allowed(lhit, inl, inr) {
+                xs.push(hit);
+            }
+            if lhit {
+                inl = !inl;
+                i += 1;
+            } else {
+                inr = !inr;
+                j += 1;
+            }
+        }
+        xs.drain(start..end);
     }
 
     pub fn intersection_allowed(&self, lhit: bool, inl: bool, inr: bool) -> bool {
@@ -1,6 +1,8 @@
 use crate::{
+    bvh::{self, BvhStats},
     csg::CSG,
-    intersections::Intersection,
+    instances::Instance,
+    intersections::{with_shape_hits, Hit, InstancePath, Intersection},
     materials::Material,
     matrices::{identity_matrix, Matrix4x4},
     objects::Object,
@@ -15,6 +17,7 @@ pub enum GroupMember {
     SubGroup(Arc<Group>),
     Object(Arc<Object>),
     CSG(Arc<CSG>),
+    Instance(Arc<Instance>),
 }
 
 impl GroupMember {
@@ -23,6 +26,44 @@ impl GroupMember {
             GroupMember::SubGroup(g) => g.intersect(ray),
             GroupMember::Object(o) => o.intersect(ray, o),
             GroupMember::CSG(c) => c.intersect(ray),
+            GroupMember::Instance(i) => Instance::intersect(i, ray),
+        }
+    }
+
+    // appends the hits unsorted, they borrow the objects from the member
+    pub fn intersect_into<'a>(
+        &'a self,
+        ray: &Ray,
+        instances: InstancePath<'a>,
+        xs: &mut Vec<Hit<'a>>,
+    ) {
+        match self {
+            GroupMember::SubGroup(g) => g.intersect_into(ray, instances, xs),
+            GroupMember::Object(o) => with_shape_hits(|hits| {
+                o.intersect_into(ray, hits);
+                xs.extend(hits.iter().map(|hit| hit.on(o, instances)));
+            }),
+            GroupMember::CSG(c) => c.intersect_into(ray, instances, xs),
+            GroupMember::Instance(i) => Instance::intersect_into(i, ray, instances, xs),
+        }
+    }
+
+    // how many instances are nested on the deepest path into the member
+    pub(crate) fn instance_depth(&self) -> usize {
+        match self {
+            GroupMember::SubGroup(g) => g
+                .members()
+                .iter()
+                .map(GroupMember::instance_depth)
+                .max()
+                .unwrap_or(0),
+            GroupMember::Object(_) => 0,
+            GroupMember::CSG(c) => match c.as_ref() {
+                CSG::Union(l, r) | CSG::Intersection(l, r) | CSG::Difference(l, r) => {
+                    l.instance_depth().max(r.instance_depth())
+                }
+            },
+            GroupMember::Instance(i) => 1 + i.member().instance_depth(),
         }
     }
 
@@ -31,6 +72,7 @@ impl GroupMember {
             GroupMember::SubGroup(g) => g.bounds().clone(),
             GroupMember::Object(o) => o.bounds().clone(),
             GroupMember::CSG(c) => c.bounds(),
+            GroupMember::Instance(i) => i.bounds().clone(),
         }
     }
 
@@ -47,6 +89,11 @@ impl GroupMember {
                 GroupMember::Object(Arc::new(o))
             }
             GroupMember::CSG(c) => GroupMember::CSG(Arc::new(c.update_transform(update))),
+            GroupMember::Instance(i) => {
+                let mut i = i.as_ref().clone();
+                i.set_transform(update * i.transform());
+                GroupMember::Instance(Arc::new(i))
+            }
         }
     }
 
@@ -67,6 +114,12 @@ impl GroupMember {
                 c.set_material(m);
                 GroupMember::CSG(Arc::new(c))
             }
+            // the shared members keep their materials
+            GroupMember::Instance(i) => {
+                let mut i = i.as_ref().clone();
+                i.set_material(m);
+                GroupMember::Instance(Arc::new(i))
+            }
         }
     }
 
@@ -77,6 +130,16 @@ impl GroupMember {
                 vec![o.clone()]
             }
             GroupMember::CSG(c) => c.objects(),
+            // the objects of an instance are not placed in this group
+            GroupMember::Instance(_) => Vec::new(),
+        }
+    }
+
+    pub fn instances(&self) -> Vec<Arc<Instance>> {
+        match self {
+            GroupMember::SubGroup(g) => g.instances(),
+            GroupMember::Instance(i) => vec![i.clone()],
+            _ => Vec::new(),
         }
     }
 
@@ -85,6 +148,7 @@ impl GroupMember {
             GroupMember::SubGroup(g) => g.includes(obj),
             GroupMember::Object(o) => o == obj,
             GroupMember::CSG(c) => c.includes(obj),
+            GroupMember::Instance(i) => i.member().includes(obj),
         }
     }
 }
@@ -157,6 +221,7 @@ impl_op_ex!(+|a: &AABB, b: &AABB| -> AABB {
 });
 
 impl Group {
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         let transform = identity_matrix();
         let transform_inverse = transform.inverse().unwrap();
@@ -178,6 +243,12 @@ impl Group {
         let mut min = point(f64::INFINITY, f64::INFINITY, f64::INFINITY);
         let mut max = point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
 
+        // instances are kept whole, their members are shared
+        for i in self.instances() {
+            group.bounds = Self::outer_bounds(&group.bounds, i.bounds());
+            group.elements.push(GroupMember::Instance(i));
+        }
+
         for e in self.objects() {
             if e.bounds().clone().unwrap().goes_to_infinity() {
                 group.add_object(e.as_ref().clone());
@@ -291,7 +362,7 @@ impl Group {
         3
     }
 
-    fn objects(&self) -> Vec<Arc<Object>> {
+    pub fn objects(&self) -> Vec<Arc<Object>> {
         let mut ls = Vec::new();
         for e in self.elements.iter() {
             ls.append(&mut e.objects());
@@ -299,6 +370,14 @@ impl Group {
         ls
     }
 
+    pub fn instances(&self) -> Vec<Arc<Instance>> {
+        let mut ls = Vec::new();
+        for e in self.elements.iter() {
+            ls.append(&mut e.instances());
+        }
+        ls
+    }
+
     pub fn add_group(&mut self, mut e: Group) {
         e.set_transform(&self.transform * e.transform());
         self.bounds = Self::outer_bounds(&self.bounds, e.bounds());
@@ -313,11 +392,57 @@ impl Group {
         self.elements.push(e)
     }
 
+    pub fn add_instance(&mut self, mut e: Instance) {
+        e.set_transform(&self.transform * e.transform());
+        self.bounds = Self::outer_bounds(&self.bounds, e.bounds());
+        let e = GroupMember::Instance(Arc::new(e));
+        self.elements.push(e)
+    }
+
+    pub fn add_csg(&mut self, e: CSG) {
+        let e = e.update_transform(&self.transform);
+        self.bounds = Self::outer_bounds(&self.bounds, &e.bounds());
+        let e = GroupMember::CSG(Arc::new(e));
+        self.elements.push(e)
+    }
+
+    // a member that is already where it belongs, the transform of the group
+    // is not applied to it
+    pub(crate) fn push_member(&mut self, e: GroupMember) {
+        self.bounds = Self::outer_bounds(&self.bounds, &e.bounds());
+        self.elements.push(e)
+    }
+
+    pub fn members(&self) -> &[GroupMember] {
+        &self.elements
+    }
+
+    // the same group with its members sorted into a bounding volume hierarchy
+    pub fn build_bvh(mut self) -> (Self, BvhStats) {
+        let (elements, stats) = bvh::build(std::mem::take(&mut self.elements));
+        (self.with_members(elements), stats)
+    }
+
+    // the same group holding other members in the same place
+    pub(crate) fn with_members(&self, elements: Vec<GroupMember>) -> Group {
+        let mut group = Group {
+            transform: self.transform.clone(),
+            transform_inverse: self.transform_inverse.clone(),
+            bounds: None,
+            elements: Vec::new(),
+        };
+        for e in elements {
+            group.push_member(e);
+        }
+        group
+    }
+
     pub fn get_object(&self, idx: usize) -> Arc<Object> {
         match self.elements.get(idx).unwrap() {
             GroupMember::Object(o) => o.clone(),
             GroupMember::SubGroup(_) => panic!("found a group"),
             GroupMember::CSG(_) => panic!("found a csg"),
+            GroupMember::Instance(_) => panic!("found an instance"),
         }
     }
 
@@ -412,6 +537,21 @@ impl Group {
         xs
     }
 
+    pub fn intersect_into<'a>(
+        &'a self,
+        ray: &Ray,
+        instances: InstancePath<'a>,
+        xs: &mut Vec<Hit<'a>>,
+    ) {
+        match &self.bounds {
+            Some(b) if b.is_intersected(ray) => {}
+            _ => return,
+        }
+        for element in self.elements.iter() {
+            element.intersect_into(ray, instances, xs);
+        }
+    }
+
     pub fn bounds(&self) -> &Option<AABB> {
         &self.bounds
     }
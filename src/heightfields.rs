@@ -0,0 +1,242 @@
+// A heightfield is a grid of heights over the unit square of the xz plane,
+// every cell between four samples is split into two triangles. Rays walk the
+// cells they cross with a 2D DDA, so only the triangles below the ray are
+// tested and nothing is tessellated into objects. Normals are interpolated
+// from vertex normals found by central differences of the heights.
+use std::sync::Arc;
+
+use crate::{
+    canvas::Canvas,
+    groups::AABB,
+    intersections::ShapeHit,
+    rays::Ray,
+    triangles::intersect_triangle,
+    tuples::{point, vector, Tuple},
+};
+
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightfield {
+    // samples along x and along z
+    columns: usize,
+    rows: usize,
+    heights: Arc<Vec<f64>>,
+    min: f64,
+    max: f64,
+}
+
+impl Heightfield {
+    // the heights are stored row by row, a row runs along x
+    pub fn new(columns: usize, rows: usize, heights: Vec<f64>) -> Result<Heightfield, String> {
+        if columns < 2 || rows < 2 {
+            return Err("a heightfield needs at least 2 by 2 samples".to_string());
+        }
+        if heights.len() != columns * rows {
+            return Err(format!(
+                "expected {} heights, got {}",
+                columns * rows,
+                heights.len()
+            ));
+        }
+        let min = heights.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        Ok(Heightfield {
+            columns,
+            rows,
+            heights: Arc::new(heights),
+            min,
+            max,
+        })
+    }
+
+    // the luminance of the pixels, the first row of the image is at z = 0
+    pub fn from_canvas(canvas: &Canvas) -> Result<Heightfield, String> {
+        let heights = canvas
+            .pixels
+            .iter()
+            .map(|c| 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z)
+            .collect();
+        Heightfield::new(canvas.width, canvas.height, heights)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn height(&self, column: usize, row: usize) -> f64 {
+        self.heights[row * self.columns + column]
+    }
+
+    fn corner(&self, column: usize, row: usize) -> Tuple {
+        point(
+            column as f64 / (self.columns - 1) as f64,
+            self.height(column, row),
+            row as f64 / (self.rows - 1) as f64,
+        )
+    }
+
+    // the corners of a triangle, two per cell with the diagonal from the
+    // first corner of the cell to the opposite one
+    fn triangle(&self, face: usize) -> [(usize, usize); 3] {
+        let cell = face / 2;
+        let (column, row) = (cell % (self.columns - 1), cell / (self.columns - 1));
+        if face.is_multiple_of(2) {
+            [(column, row), (column + 1, row), (column + 1, row + 1)]
+        } else {
+            [(column, row), (column + 1, row + 1), (column, row + 1)]
+        }
+    }
+
+    // appends the hits to xs with the face that was hit
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        if self.columns < 2 || self.rows < 2 {
+            return;
+        }
+        let (x_min, x_max) = slab(0.0, 1.0, ray.origin.x, ray.direction.x);
+        let (y_min, y_max) = slab(
+            self.min - EPSILON,
+            self.max + EPSILON,
+            ray.origin.y,
+            ray.direction.y,
+        );
+        let (z_min, z_max) = slab(0.0, 1.0, ray.origin.z, ray.direction.z);
+        let t_enter = x_min.max(y_min).max(z_min);
+        let t_exit = x_max.min(y_max).min(z_max);
+        if t_enter > t_exit {
+            return;
+        }
+
+        let cells_x = self.columns - 1;
+        let cells_z = self.rows - 1;
+        let start = ray.position(t_enter);
+        let cell = |p: f64, cells: usize| {
+            ((p * cells as f64).floor() as isize).clamp(0, cells as isize - 1)
+        };
+        let (mut column, mut row) = (cell(start.x, cells_x), cell(start.z, cells_z));
+
+        // the t where the ray leaves the current cell along an axis and the
+        // t it takes to cross a whole cell
+        let axis = |origin: f64, direction: f64, index: isize, cells: usize| {
+            let size = 1.0 / cells as f64;
+            if direction > 0.0 {
+                (
+                    ((index + 1) as f64 * size - origin) / direction,
+                    size / direction,
+                    1,
+                )
+            } else if direction < 0.0 {
+                (
+                    (index as f64 * size - origin) / direction,
+                    -size / direction,
+                    -1,
+                )
+            } else {
+                (f64::INFINITY, f64::INFINITY, 0)
+            }
+        };
+        let (mut next_x, delta_x, step_x) = axis(ray.origin.x, ray.direction.x, column, cells_x);
+        let (mut next_z, delta_z, step_z) = axis(ray.origin.z, ray.direction.z, row, cells_z);
+
+        let mut t_cell = t_enter;
+        loop {
+            let t_leave = next_x.min(next_z).min(t_exit);
+            self.intersect_cell(ray, column as usize, row as usize, t_cell, t_leave, xs);
+            if next_x < next_z {
+                if next_x > t_exit {
+                    break;
+                }
+                column += step_x;
+                if column < 0 || column >= cells_x as isize {
+                    break;
+                }
+                t_cell = next_x;
+                next_x += delta_x;
+            } else {
+                if next_z > t_exit {
+                    break;
+                }
+                row += step_z;
+                if row < 0 || row >= cells_z as isize {
+                    break;
+                }
+                t_cell = next_z;
+                next_z += delta_z;
+            }
+        }
+    }
+
+    fn intersect_cell(
+        &self,
+        ray: &Ray,
+        column: usize,
+        row: usize,
+        t_enter: f64,
+        t_leave: f64,
+        xs: &mut Vec<ShapeHit>,
+    ) {
+        // skip cells the ray passes above or below
+        let heights = [
+            self.height(column, row),
+            self.height(column + 1, row),
+            self.height(column, row + 1),
+            self.height(column + 1, row + 1),
+        ];
+        let low = heights.iter().cloned().fold(f64::INFINITY, f64::min);
+        let high = heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let y_enter = ray.origin.y + t_enter * ray.direction.y;
+        let y_leave = ray.origin.y + t_leave * ray.direction.y;
+        if y_enter.max(y_leave) < low - EPSILON || y_enter.min(y_leave) > high + EPSILON {
+            return;
+        }
+
+        let cell = row * (self.columns - 1) + column;
+        for face in [cell * 2, cell * 2 + 1] {
+            let [p1, p2, p3] = self.triangle(face).map(|(c, r)| self.corner(c, r));
+            if let Some((t, u, v)) = intersect_triangle(ray, &p1, &(&p2 - &p1), &(&p3 - &p1)) {
+                xs.push(ShapeHit { t, u, v, face });
+            }
+        }
+    }
+
+    pub fn normal_at(&self, face: usize, u: f64, v: f64) -> Tuple {
+        let [n1, n2, n3] = self.triangle(face).map(|(c, r)| self.vertex_normal(c, r));
+        (n2 * u + n3 * v + n1 * (1.0 - u - v)).normalize()
+    }
+
+    // the slopes towards the neighbours, one sided at the border
+    fn vertex_normal(&self, column: usize, row: usize) -> Tuple {
+        let left = column.saturating_sub(1);
+        let right = (column + 1).min(self.columns - 1);
+        let back = row.saturating_sub(1);
+        let front = (row + 1).min(self.rows - 1);
+        let dx = (self.height(right, row) - self.height(left, row)) * (self.columns - 1) as f64
+            / (right - left) as f64;
+        let dz = (self.height(column, front) - self.height(column, back)) * (self.rows - 1) as f64
+            / (front - back) as f64;
+        vector(-dx, 1.0, -dz).normalize()
+    }
+
+    pub fn bounds(&self) -> AABB {
+        AABB {
+            min: point(0.0, self.min, 0.0),
+            max: point(1.0, self.max, 1.0),
+        }
+    }
+}
+
+fn slab(min: f64, max: f64, origin: f64, direction: f64) -> (f64, f64) {
+    if direction.abs() < EPSILON {
+        if origin < min || origin > max {
+            return (f64::INFINITY, f64::NEG_INFINITY);
+        }
+        return (f64::NEG_INFINITY, f64::INFINITY);
+    }
+    let t1 = (min - origin) / direction;
+    let t2 = (max - origin) / direction;
+    (t1.min(t2), t1.max(t2))
+}
@@ -0,0 +1,128 @@
+// An instance places a shared group, or a single object like a mesh, with a
+// transform of its own. The members are stored once however often they are
+// placed since rays are moved into the space of the instance instead of
+// pushing the transform down into every object.
+use crate::{
+    groups::{GroupMember, AABB},
+    intersections::{Hit, InstancePath, Intersection, MAX_INSTANCE_DEPTH},
+    materials::Material,
+    matrices::{identity_matrix, Matrix4x4},
+    rays::Ray,
+    tuples::Tuple,
+};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    member: GroupMember,
+    transform: Matrix4x4,
+    transform_inverse: Matrix4x4,
+    // replaces the materials of all objects of the instance
+    pub material: Option<Material>,
+    bounds: Option<AABB>,
+}
+
+impl Instance {
+    // hits keep the instances they were found in inline, so no more than
+    // MAX_INSTANCE_DEPTH of them can be nested
+    pub fn new(member: GroupMember) -> Result<Instance, String> {
+        if member.instance_depth() >= MAX_INSTANCE_DEPTH {
+            return Err(format!(
+                "instances are nested deeper than {}",
+                MAX_INSTANCE_DEPTH
+            ));
+        }
+        let transform = identity_matrix();
+        let transform_inverse = identity_matrix();
+        let bounds = member.bounds();
+        Ok(Instance {
+            member,
+            transform,
+            transform_inverse,
+            material: None,
+            bounds,
+        })
+    }
+
+    pub fn member(&self) -> &GroupMember {
+        &self.member
+    }
+
+    // the same instance placing another member
+    pub(crate) fn with_member(&self, member: GroupMember) -> Instance {
+        Instance {
+            bounds: member.bounds().map(|b| b * &self.transform),
+            member,
+            transform: self.transform.clone(),
+            transform_inverse: self.transform_inverse.clone(),
+            material: self.material.clone(),
+        }
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix4x4) {
+        self.transform_inverse = transform.inverse().unwrap();
+        self.bounds = self.member.bounds().map(|b| b * &transform);
+        self.transform = transform;
+    }
+
+    pub fn transform(&self) -> &Matrix4x4 {
+        &self.transform
+    }
+
+    pub fn transform_inverse(&self) -> &Matrix4x4 {
+        &self.transform_inverse
+    }
+
+    pub fn set_material(&mut self, material: &Material) {
+        self.material = Some(material.clone());
+    }
+
+    pub fn bounds(&self) -> &Option<AABB> {
+        &self.bounds
+    }
+
+    // the hits remember the instances they were found in, innermost first
+    pub fn intersect(instance: &Arc<Instance>, world_ray: &Ray) -> Vec<Intersection> {
+        let mut hits = Vec::new();
+        Instance::intersect_into(instance, world_ray, InstancePath::default(), &mut hits);
+        let mut xs: Vec<Intersection> = hits.iter().map(Hit::to_intersection).collect();
+        xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        xs
+    }
+
+    pub fn intersect_into<'a>(
+        instance: &'a Arc<Instance>,
+        world_ray: &Ray,
+        instances: InstancePath<'a>,
+        xs: &mut Vec<Hit<'a>>,
+    ) {
+        if let Some(bounds) = &instance.bounds {
+            if !bounds.is_intersected(world_ray) {
+                return;
+            }
+        }
+        let local_ray = world_ray.transform(&instance.transform_inverse);
+        instance
+            .member
+            .intersect_into(&local_ray, instances.inside(instance), xs);
+    }
+}
+
+// the point in the space the members of the innermost instance live in
+pub fn instance_point(instances: &[Arc<Instance>], world_point: &Tuple) -> Tuple {
+    instances
+        .iter()
+        .rev()
+        .fold(world_point.clone(), |p, instance| {
+            &instance.transform_inverse * p
+        })
+}
+
+// a normal from the space of the innermost instance to world space
+pub fn instance_normal(instances: &[Arc<Instance>], normal: Tuple) -> Tuple {
+    instances.iter().fold(normal, |n, instance| {
+        let mut n = instance.transform_inverse.transpose() * n;
+        n.w = 0.0;
+        n.normalize()
+    })
+}
@@ -1,11 +1,14 @@
 use crate::{
-    lights::lighting,
-    objects::Object,
+    instances::{instance_normal, instance_point, Instance},
+    lights::phong,
+    materials::Material,
+    objects::{Object, Shape},
+    patterns::pattern_at_shape,
     rays::Ray,
     tuples::{color, dot, reflect, Tuple},
     world::World,
 };
-use std::sync::Arc;
+use std::{cell::RefCell, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct Intersection {
@@ -13,18 +16,163 @@ pub struct Intersection {
     pub object: Arc<Object>,
     pub u: f64,
     pub v: f64,
+    // the face of a mesh that was hit
+    pub face: usize,
+    // the instances the object was reached through, innermost first
+    pub instances: Vec<Arc<Instance>>,
+}
+
+// an intersection with a shape before it is tied to an object, what the
+// intersection routines write into the buffers they are given
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeHit {
+    pub t: f64,
+    pub u: f64,
+    pub v: f64,
+    pub face: usize,
+}
+
+impl ShapeHit {
+    pub fn at(t: f64) -> ShapeHit {
+        ShapeHit {
+            t,
+            u: 0.0,
+            v: 0.0,
+            face: 0,
+        }
+    }
+
+    pub fn with_object(&self, object: &Arc<Object>) -> Intersection {
+        Intersection {
+            t: self.t,
+            object: object.clone(),
+            u: self.u,
+            v: self.v,
+            face: self.face,
+            instances: Vec::new(),
+        }
+    }
+
+    pub fn on<'a>(&self, object: &'a Arc<Object>, instances: InstancePath<'a>) -> Hit<'a> {
+        Hit {
+            t: self.t,
+            u: self.u,
+            v: self.v,
+            face: self.face,
+            object,
+            instances,
+        }
+    }
+}
+
+// an intersection that borrows the object it hit and the instances it was
+// reached through, what queries collect before the hits they keep become
+// intersections
+#[derive(Debug, Clone, Copy)]
+pub struct Hit<'a> {
+    pub t: f64,
+    pub u: f64,
+    pub v: f64,
+    pub face: usize,
+    pub object: &'a Arc<Object>,
+    pub instances: InstancePath<'a>,
+}
+
+impl Hit<'_> {
+    pub fn to_intersection(&self) -> Intersection {
+        Intersection {
+            t: self.t,
+            object: self.object.clone(),
+            u: self.u,
+            v: self.v,
+            face: self.face,
+            instances: self.instances.to_vec(),
+        }
+    }
+}
+
+pub const MAX_INSTANCE_DEPTH: usize = 8;
+
+// the instances around a hit, outermost first, kept inline so that hits
+// need no allocation of their own
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstancePath<'a> {
+    len: usize,
+    instances: [Option<&'a Arc<Instance>>; MAX_INSTANCE_DEPTH],
+}
+
+impl<'a> InstancePath<'a> {
+    // Instance::new rejects deeper nesting
+    pub fn inside(&self, instance: &'a Arc<Instance>) -> InstancePath<'a> {
+        debug_assert!(self.len < MAX_INSTANCE_DEPTH);
+        let mut path = *self;
+        path.instances[path.len] = Some(instance);
+        path.len += 1;
+        path
+    }
+
+    // innermost first like the instances of an intersection
+    pub fn to_vec(&self) -> Vec<Arc<Instance>> {
+        self.instances[..self.len]
+            .iter()
+            .rev()
+            .map(|instance| Arc::clone(instance.unwrap()))
+            .collect()
+    }
+}
+
+thread_local! {
+    // a query started while another one holds a buffer takes the next one
+    static SHAPE_HIT_BUFFERS: RefCell<Vec<Vec<ShapeHit>>> = const { RefCell::new(Vec::new()) };
+}
+
+// lends an empty buffer for the hits of shapes that the thread keeps for
+// the next query
+pub fn with_shape_hits<R>(f: impl FnOnce(&mut Vec<ShapeHit>) -> R) -> R {
+    let mut hits = SHAPE_HIT_BUFFERS
+        .with_borrow_mut(Vec::pop)
+        .unwrap_or_default();
+    let result = f(&mut hits);
+    hits.clear();
+    SHAPE_HIT_BUFFERS.with_borrow_mut(|buffers| buffers.push(hits));
+    result
+}
+
+impl Intersection {
+    // an object placed by two instances are two different surfaces
+    fn same_surface(&self, object: &Arc<Object>, instances: &[Arc<Instance>]) -> bool {
+        Arc::ptr_eq(&self.object, object)
+            && self.instances.len() == instances.len()
+            && self
+                .instances
+                .iter()
+                .zip(instances)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
 }
 
 impl PartialEq for Intersection {
     fn eq(&self, other: &Self) -> bool {
-        self.t == other.t && Arc::ptr_eq(&self.object, &other.object)
+        self.t == other.t && other.same_surface(&self.object, &self.instances)
     }
 }
 
+// the material of the outermost instance that overrides it
+fn material_of<'a>(object: &'a Object, instances: &'a [Arc<Instance>]) -> &'a Material {
+    instances
+        .iter()
+        .rev()
+        .find_map(|i| i.material.as_ref())
+        .unwrap_or(&object.material)
+}
+
+// hits closer than this to the start of a ray are where the ray started from
+pub const HIT_EPSILON: f64 = 0.0001;
+
 pub fn hit(xs: &[Intersection]) -> Option<&Intersection> {
     let mut r = None;
     for current in xs.iter() {
-        if current.t < 0.0001 {
+        if current.t < HIT_EPSILON {
             continue;
         }
         r = match r {
@@ -52,6 +200,15 @@ pub struct IntersectionPrecomputations {
     pub inside: bool,
     pub n1: f64,
     pub n2: f64,
+    pub u: f64,
+    pub v: f64,
+    pub instances: Vec<Arc<Instance>>,
+}
+
+impl IntersectionPrecomputations {
+    pub fn material(&self) -> &Material {
+        material_of(&self.object, &self.instances)
+    }
 }
 
 pub fn prepare_computations(
@@ -59,7 +216,7 @@ pub fn prepare_computations(
     ray: &Ray,
     xs: &[Intersection],
 ) -> IntersectionPrecomputations {
-    let mut containers: Vec<Arc<Object>> = Vec::with_capacity(xs.len());
+    let mut containers: Vec<&Intersection> = Vec::with_capacity(xs.len());
     let mut n1 = 0.0;
     let mut n2 = 0.0;
     for i in xs.iter() {
@@ -67,16 +224,20 @@ pub fn prepare_computations(
             if containers.is_empty() {
                 n1 = 1.0;
             } else {
-                n1 = containers.last().unwrap().material.refractive_index;
+                let x = containers.last().unwrap();
+                n1 = material_of(&x.object, &x.instances).refractive_index;
             }
         }
 
-        match containers.iter().position(|x| Arc::ptr_eq(x, &i.object)) {
+        match containers
+            .iter()
+            .position(|x| x.same_surface(&i.object, &i.instances))
+        {
             Some(index) => {
                 containers.remove(index);
             }
             None => {
-                containers.push(i.object.clone());
+                containers.push(i);
             }
         }
 
@@ -84,7 +245,8 @@ pub fn prepare_computations(
             if containers.is_empty() {
                 n2 = 1.0;
             } else {
-                n2 = containers.last().unwrap().material.refractive_index
+                let x = containers.last().unwrap();
+                n2 = material_of(&x.object, &x.instances).refractive_index;
             }
         }
     }
@@ -93,13 +255,22 @@ pub fn prepare_computations(
     let object = intersection.object.clone();
     let point = ray.position(t);
     let eyev = -&ray.direction;
-    let mut normalv = object.normal_at(&point, intersection);
+    let instances = intersection.instances.clone();
+    let mut normalv = instance_normal(
+        &instances,
+        object.normal_at(&instance_point(&instances, &point), intersection),
+    );
     let mut inside = false;
     if dot(&normalv, &eyev) < 0.0 {
         inside = true;
         normalv = -normalv;
     }
     let reflectv = reflect(&ray.direction, &normalv);
+    // texture coordinates of meshes depend on the face that was hit
+    let (u, v) = match &object.shape {
+        Shape::Mesh(mesh) => mesh.texture_uv(intersection.face, intersection.u, intersection.v),
+        _ => (intersection.u, intersection.v),
+    };
 
     IntersectionPrecomputations {
         t,
@@ -111,16 +282,24 @@ pub fn prepare_computations(
         inside,
         n1,
         n2,
+        u,
+        v,
+        instances,
     }
 }
 
 pub fn color_at(world: &World, ray: &Ray, remaining: usize) -> Tuple {
-    let intersections = world.insersect(ray);
-    let hit = hit(&intersections);
-    match hit {
+    match world.closest_hit(ray) {
         None => color(0.0, 0.0, 0.0),
         Some(intersection) => {
-            let precomputations = prepare_computations(intersection, ray, &intersections);
+            // only refraction needs every surface along the ray for the
+            // refractive indices, an opaque hit is seen from the outside
+            let precomputations =
+                if material_of(&intersection.object, &intersection.instances).transparency > 0.0 {
+                    prepare_computations(&intersection, ray, &world.insersect(ray))
+                } else {
+                    prepare_computations(&intersection, ray, std::slice::from_ref(&intersection))
+                };
             shade_hit(world, &precomputations, remaining)
         }
     }
@@ -128,9 +307,20 @@ pub fn color_at(world: &World, ray: &Ray, remaining: usize) -> Tuple {
 
 pub fn shade_hit(world: &World, comps: &IntersectionPrecomputations, remaining: usize) -> Tuple {
     let in_shadow = world.is_shadowed(comps.point.clone());
-    let surface = lighting(
-        &comps.object.material,
-        &comps.object,
+    let material = comps.material();
+    // patterns are placed in the space of the object inside its instances
+    let material_color = match &material.pattern {
+        None => material.color.clone(),
+        Some(pattern) => pattern_at_shape(
+            pattern,
+            &comps.object,
+            &instance_point(&comps.instances, &comps.point),
+            &(comps.u, comps.v),
+        ),
+    };
+    let surface = phong(
+        material,
+        &material_color,
         world.light.as_ref().unwrap(),
         &comps.point,
         &comps.eyev,
@@ -140,7 +330,6 @@ pub fn shade_hit(world: &World, comps: &IntersectionPrecomputations, remaining:
     let reflected = reflected_color(world, comps, remaining);
     let refracted = refracted_color(world, comps, remaining);
 
-    let material = &comps.object.material;
     if material.reflective > 0.0 && material.transparency > 0.0 {
         let reflectance = schlick(comps);
         return surface + reflected * reflectance + refracted * (1.0 - reflectance);
@@ -154,7 +343,7 @@ pub fn reflected_color(
     comps: &IntersectionPrecomputations,
     remaining: usize,
 ) -> Tuple {
-    if comps.object.material.reflective == 0.0 {
+    if comps.material().reflective == 0.0 {
         return color(0.0, 0.0, 0.0);
     }
     if remaining == 0 {
@@ -162,7 +351,7 @@ pub fn reflected_color(
     }
     let reflect_ray = Ray::new(comps.point.clone(), comps.reflectv.clone());
     let color = color_at(world, &reflect_ray, remaining - 1);
-    color * comps.object.material.reflective
+    color * comps.material().reflective
 }
 
 pub fn refracted_color(
@@ -170,7 +359,7 @@ pub fn refracted_color(
     comps: &IntersectionPrecomputations,
     remaining: usize,
 ) -> Tuple {
-    if comps.object.material.transparency == 0.0 {
+    if comps.material().transparency == 0.0 {
         return color(0.0, 0.0, 0.0);
     }
     if remaining == 0 {
@@ -197,7 +386,7 @@ pub fn refracted_color(
     let refract_ray = Ray::new(comps.point.clone(), direction);
     // Find the color of the refracted ray, making sure to multiply
     // by the transparency value to account for any opacity
-    color_at(world, &refract_ray, remaining - 1) * comps.object.material.transparency
+    color_at(world, &refract_ray, remaining - 1) * comps.material().transparency
 }
 
 pub fn schlick(comps: &IntersectionPrecomputations) -> f64 {
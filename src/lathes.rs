@@ -0,0 +1,245 @@
+// A lathe revolves a profile of (radius, y) points around the y axis. Every
+// segment of the profile sweeps a piece of a cone, a cylinder or a ring in a
+// plane, so a ray meets it where a quadratic vanishes. Splines are sampled
+// into short segments whose normals follow the curve instead of the segment.
+use crate::{
+    groups::AABB,
+    intersections::ShapeHit,
+    quartic::solve_quadratic,
+    rays::Ray,
+    tuples::{point, vector, Tuple},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    start: (f64, f64),
+    end: (f64, f64),
+    // normals of the profile at both ends as (radial, y)
+    start_normal: (f64, f64),
+    end_normal: (f64, f64),
+    // the texture v at both ends, the length of the profile up to there
+    start_v: f64,
+    end_v: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lathe {
+    segments: Vec<Segment>,
+}
+
+impl Lathe {
+    // each segment is flat, normals do not change along it
+    pub fn polyline(profile: &[(f64, f64)]) -> Lathe {
+        let normals = profile
+            .windows(2)
+            .map(|w| {
+                let n = segment_normal(w[0], w[1]);
+                (n, n)
+            })
+            .collect();
+        Lathe::new(profile, normals)
+    }
+
+    // a catmull-rom spline through the profile points, sampled with steps
+    // segments between two of them
+    pub fn spline(profile: &[(f64, f64)], steps: usize) -> Lathe {
+        let steps = steps.max(1);
+        let mut points = Vec::new();
+        let mut tangents = Vec::new();
+        for i in 0..profile.len().saturating_sub(1) {
+            let p0 = profile[i.saturating_sub(1)];
+            let p1 = profile[i];
+            let p2 = profile[i + 1];
+            let p3 = profile[(i + 2).min(profile.len() - 1)];
+            let last = if i + 2 == profile.len() {
+                steps
+            } else {
+                steps - 1
+            };
+            for step in 0..=last {
+                let s = step as f64 / steps as f64;
+                let (p, t) = catmull_rom(p0, p1, p2, p3, s);
+                points.push(p);
+                tangents.push(t);
+            }
+        }
+        let normals = tangents
+            .windows(2)
+            .zip(points.windows(2))
+            .map(|(t, p)| {
+                // fall back to the segment where the curve stops
+                let fallback = segment_normal(p[0], p[1]);
+                (
+                    profile_normal(t[0]).unwrap_or(fallback),
+                    profile_normal(t[1]).unwrap_or(fallback),
+                )
+            })
+            .collect();
+        Lathe::new(&points, normals)
+    }
+
+    fn new(profile: &[(f64, f64)], normals: Vec<((f64, f64), (f64, f64))>) -> Lathe {
+        let length: f64 = profile.windows(2).map(|w| distance(w[0], w[1])).sum();
+        let mut travelled = 0.0;
+        let segments = profile
+            .windows(2)
+            .zip(normals)
+            .map(|(w, (start_normal, end_normal))| {
+                let start_v = travelled;
+                travelled += distance(w[0], w[1]);
+                Segment {
+                    start: w[0],
+                    end: w[1],
+                    start_normal,
+                    end_normal,
+                    start_v: fraction(start_v, length),
+                    end_v: fraction(travelled, length),
+                }
+            })
+            .collect();
+        Lathe { segments }
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    // the face of a hit is its segment and u how far along the segment it is
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        let hit = |t: f64, segment: usize, s: f64| ShapeHit {
+            t,
+            u: s,
+            v: self.texture_v(segment, s),
+            face: segment,
+        };
+        let last = self.segments.len().saturating_sub(1);
+        for (index, segment) in self.segments.iter().enumerate() {
+            let (r0, y0) = segment.start;
+            let (r1, y1) = segment.end;
+            // a shared end point only belongs to the next segment
+            let along = |s: f64| (0.0..1.0).contains(&s) || (index == last && s == 1.0);
+            if y0 == y1 {
+                // a ring in the plane at y0
+                if ray.direction.y == 0.0 || r0 == r1 {
+                    continue;
+                }
+                let t = (y0 - ray.origin.y) / ray.direction.y;
+                let x = ray.origin.x + t * ray.direction.x;
+                let z = ray.origin.z + t * ray.direction.z;
+                let s = ((x * x + z * z).sqrt() - r0) / (r1 - r0);
+                if along(s) {
+                    xs.push(hit(t, index, s));
+                }
+                continue;
+            }
+            // the radius changes linearly with y, r = a + b t along the ray
+            let k = (r1 - r0) / (y1 - y0);
+            let a = r0 + (ray.origin.y - y0) * k;
+            let b = ray.direction.y * k;
+            let roots = solve_quadratic(
+                ray.direction.x.powi(2) + ray.direction.z.powi(2) - b * b,
+                2.0 * (ray.origin.x * ray.direction.x + ray.origin.z * ray.direction.z - a * b),
+                ray.origin.x.powi(2) + ray.origin.z.powi(2) - a * a,
+            );
+            for t in roots {
+                let s = (ray.origin.y + t * ray.direction.y - y0) / (y1 - y0);
+                if along(s) {
+                    xs.push(hit(t, index, s));
+                }
+            }
+        }
+    }
+
+    pub fn normal_at(&self, local_point: &Tuple, segment: usize, s: f64) -> Tuple {
+        let segment = &self.segments[segment];
+        let nr = segment.start_normal.0 * (1.0 - s) + segment.end_normal.0 * s;
+        let ny = segment.start_normal.1 * (1.0 - s) + segment.end_normal.1 * s;
+        let dist = (local_point.x.powi(2) + local_point.z.powi(2)).sqrt();
+        if dist < f64::EPSILON {
+            return vector(0.0, ny.signum(), 0.0);
+        }
+        vector(nr * local_point.x / dist, ny, nr * local_point.z / dist).normalize()
+    }
+
+    // the texture v runs along the profile from its first point, u around the
+    // y axis comes from the point
+    pub fn texture_v(&self, segment: usize, s: f64) -> f64 {
+        let segment = &self.segments[segment];
+        segment.start_v + (segment.end_v - segment.start_v) * s
+    }
+
+    pub fn bounds(&self) -> AABB {
+        let mut radius: f64 = 0.0;
+        let mut min_y = f64::INFINITY;
+        let mut max_y = f64::NEG_INFINITY;
+        for segment in self.segments.iter() {
+            for (r, y) in [segment.start, segment.end] {
+                radius = radius.max(r.abs());
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
+        }
+        if self.segments.is_empty() {
+            (min_y, max_y) = (0.0, 0.0);
+        }
+        AABB {
+            min: point(-radius, min_y, -radius),
+            max: point(radius, max_y, radius),
+        }
+    }
+}
+
+// the profile normal to the right of the direction from start to end, it
+// points away from the axis when the profile goes up
+fn segment_normal(start: (f64, f64), end: (f64, f64)) -> (f64, f64) {
+    profile_normal((end.0 - start.0, end.1 - start.1)).unwrap_or((1.0, 0.0))
+}
+
+fn profile_normal(tangent: (f64, f64)) -> Option<(f64, f64)> {
+    let length = (tangent.0 * tangent.0 + tangent.1 * tangent.1).sqrt();
+    if length < f64::EPSILON {
+        return None;
+    }
+    Some((tangent.1 / length, -tangent.0 / length))
+}
+
+// the point and the tangent at s between p1 and p2
+fn catmull_rom(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    p3: (f64, f64),
+    s: f64,
+) -> ((f64, f64), (f64, f64)) {
+    let curve = |a: f64, b: f64, c: f64, d: f64| {
+        let value = 0.5
+            * (2.0 * b
+                + (c - a) * s
+                + (2.0 * a - 5.0 * b + 4.0 * c - d) * s * s
+                + (3.0 * b - a - 3.0 * c + d) * s * s * s);
+        let slope = 0.5
+            * ((c - a)
+                + 2.0 * (2.0 * a - 5.0 * b + 4.0 * c - d) * s
+                + 3.0 * (3.0 * b - a - 3.0 * c + d) * s * s);
+        (value, slope)
+    };
+    let (r, dr) = curve(p0.0, p1.0, p2.0, p3.0);
+    let (y, dy) = curve(p0.1, p1.1, p2.1, p3.1);
+    ((r, y), (dr, dy))
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+fn fraction(part: f64, whole: f64) -> f64 {
+    if whole == 0.0 {
+        0.0
+    } else {
+        part / whole
+    }
+}
@@ -1,16 +1,29 @@
 #[macro_use]
+pub mod bezier_file;
+pub mod bvh;
 pub mod camera;
 pub mod canvas;
 pub mod csg;
 pub mod groups;
+pub mod heightfields;
+pub mod instances;
 pub mod intersections;
+pub mod lathes;
 pub mod lights;
 pub mod materials;
 pub mod matrices;
+pub mod meshes;
+pub mod mtl_file;
 pub mod obj_file;
 pub mod objects;
+pub mod patches;
 pub mod patterns;
+pub mod ply_file;
+pub mod quartic;
 pub mod rays;
+pub mod scene_file;
+pub mod sdf;
+pub mod stl_file;
 pub mod transformations;
 pub mod triangles;
 pub mod tuples;
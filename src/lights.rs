@@ -22,6 +22,7 @@ impl Pointlight {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn lighting(
     material: &Material,
     object: &Arc<Object>,
@@ -30,14 +31,35 @@ pub fn lighting(
     eyev: &Tuple,
     normalv: &Tuple,
     in_shadow: bool,
+    hit_uv: &(f64, f64),
 ) -> Tuple {
     let material_color = match &material.pattern {
         None => material.color.clone(),
-        Some(pattern) => pattern_at_shape(pattern, object, point),
+        Some(pattern) => pattern_at_shape(pattern, object, point, hit_uv),
     };
+    phong(
+        material,
+        &material_color,
+        light,
+        point,
+        eyev,
+        normalv,
+        in_shadow,
+    )
+}
 
+// the phong reflection model for a color already looked up on the surface
+pub fn phong(
+    material: &Material,
+    material_color: &Tuple,
+    light: &Pointlight,
+    point: &Tuple,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    in_shadow: bool,
+) -> Tuple {
     let black = color(0.0, 0.0, 0.0);
-    let effective_color = &material_color * &light.intensity;
+    let effective_color = material_color * &light.intensity;
     let lightv = (&light.position - point).normalize();
     let ambient = &effective_color * material.ambient;
 
@@ -23,6 +23,7 @@ pub struct Material {
 }
 
 impl Material {
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         Self {
             color: color(1.0, 1.0, 1.0),
@@ -119,7 +119,7 @@ impl Matrix3x3 {
     }
 
     pub fn cofactor(&self, w: usize, h: usize) -> f64 {
-        match (w + h) % 2 == 0 {
+        match (w + h).is_multiple_of(2) {
             true => self.submatrix(w, h).determinant(),
             false => -self.submatrix(w, h).determinant(),
         }
@@ -205,7 +205,7 @@ impl Matrix4x4 {
     }
 
     pub fn cofactor(&self, w: usize, h: usize) -> f64 {
-        match (w + h) % 2 == 0 {
+        match (w + h).is_multiple_of(2) {
             true => self.submatrix(w, h).determinant(),
             false => -self.submatrix(w, h).determinant(),
         }
@@ -0,0 +1,318 @@
+// Triangle meshes keep vertices, normals and texture coordinates in buffers
+// that are shared between meshes and their clones, a face only holds indices
+// into them. A bounding volume hierarchy over the faces keeps the triangle
+// tests to the faces near the ray.
+use std::{cmp::Ordering, ops::Range, sync::Arc};
+
+use crate::{
+    bvh::enter,
+    groups::AABB,
+    intersections::ShapeHit,
+    rays::Ray,
+    triangles::{intersect_triangle, texture_uv},
+    tuples::{cross, point, Tuple},
+};
+
+const LEAF_SIZE: usize = 4;
+// the median splits halve the faces at every level and faces are indexed by
+// u32, so no leaf is deeper than this
+const MAX_DEPTH: usize = u32::BITS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub vertices: [u32; 3],
+    pub normals: Option<[u32; 3]>,
+    pub uvs: Option<[u32; 3]>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    min: [f64; 3],
+    max: [f64; 3],
+    // a leaf holds count faces starting at first, the first child of an inner
+    // node follows it and the second one is at first
+    first: u32,
+    count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    pub vertices: Arc<Vec<Tuple>>,
+    pub normals: Arc<Vec<Tuple>>,
+    pub uvs: Arc<Vec<(f64, f64)>>,
+    faces: Arc<Vec<Face>>,
+    nodes: Arc<Vec<Node>>,
+}
+
+struct Item {
+    face: Face,
+    bounds: AABB,
+    centroid: Tuple,
+}
+
+impl Mesh {
+    // the indices of the faces have to be valid for the buffers
+    pub fn new(
+        vertices: Arc<Vec<Tuple>>,
+        normals: Arc<Vec<Tuple>>,
+        uvs: Arc<Vec<(f64, f64)>>,
+        faces: Vec<Face>,
+    ) -> Mesh {
+        let mut items: Vec<Item> = faces
+            .into_iter()
+            .map(|face| {
+                let [p1, p2, p3] = face.vertices.map(|i| &vertices[i as usize]);
+                let bounds = AABB {
+                    min: point(
+                        p1.x.min(p2.x).min(p3.x),
+                        p1.y.min(p2.y).min(p3.y),
+                        p1.z.min(p2.z).min(p3.z),
+                    ),
+                    max: point(
+                        p1.x.max(p2.x).max(p3.x),
+                        p1.y.max(p2.y).max(p3.y),
+                        p1.z.max(p2.z).max(p3.z),
+                    ),
+                };
+                let centroid = bounds.center();
+                Item {
+                    face,
+                    bounds,
+                    centroid,
+                }
+            })
+            .collect();
+        assert!(
+            items.len() <= u32::MAX as usize,
+            "too many faces for a mesh"
+        );
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            build(&mut nodes, &mut items, 0, 0);
+        }
+        Mesh {
+            vertices,
+            normals,
+            uvs,
+            faces: Arc::new(items.into_iter().map(|item| item.face).collect()),
+            nodes: Arc::new(nodes),
+        }
+    }
+
+    // the faces in the order of the hierarchy
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    pub fn corners(&self, face: usize) -> [&Tuple; 3] {
+        self.faces[face]
+            .vertices
+            .map(|i| &self.vertices[i as usize])
+    }
+
+    // the faces of every leaf whose box the ray passes between t_min and
+    // t_max, the children of a node in the order the ray enters them. visit
+    // tells how far along the ray faces are still of interest.
+    fn traverse(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        mut t_max: f64,
+        mut visit: impl FnMut(Range<usize>) -> f64,
+    ) {
+        let Some(root) = self.nodes.first() else {
+            return;
+        };
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let inverse = [
+            1.0 / ray.direction.x,
+            1.0 / ray.direction.y,
+            1.0 / ray.direction.z,
+        ];
+        let enters =
+            |node: &Node, t_max: f64| enter(&node.min, &node.max, &origin, &inverse, t_min, t_max);
+        let Some(t) = enters(root, t_max) else {
+            return;
+        };
+        // a pending sibling for every level above the node on top
+        let mut stack = [(0u32, 0.0); MAX_DEPTH + 1];
+        stack[0] = (0, t);
+        let mut depth = 1;
+        while depth > 0 {
+            depth -= 1;
+            let (index, t) = stack[depth];
+            if t > t_max {
+                continue;
+            }
+            let index = index as usize;
+            let node = &self.nodes[index];
+            let first = node.first as usize;
+            if node.count > 0 {
+                t_max = t_max.min(visit(first..first + node.count as usize));
+                continue;
+            }
+            let near = enters(&self.nodes[index + 1], t_max).map(|t| (index as u32 + 1, t));
+            let far = enters(&self.nodes[first], t_max).map(|t| (node.first, t));
+            let (near, far) = match (near, far) {
+                (Some(a), Some(b)) if b.1 < a.1 => (Some(b), Some(a)),
+                pair => pair,
+            };
+            for entry in [far, near].into_iter().flatten() {
+                stack[depth] = entry;
+                depth += 1;
+            }
+        }
+    }
+
+    fn intersect_face(&self, ray: &Ray, face: usize) -> Option<ShapeHit> {
+        let [p1, p2, p3] = self.corners(face);
+        intersect_triangle(ray, p1, &(p2 - p1), &(p3 - p1)).map(|(t, u, v)| ShapeHit {
+            t,
+            u,
+            v,
+            face,
+        })
+    }
+
+    // appends every hit along the whole line with the face that was hit
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        self.traverse(ray, f64::NEG_INFINITY, f64::INFINITY, |faces| {
+            xs.extend(faces.filter_map(|face| self.intersect_face(ray, face)));
+            f64::INFINITY
+        });
+    }
+
+    // appends hits inside the interval of the ray, each one nearer than those
+    // before, so the last one is the nearest and faces behind it are skipped
+    pub fn intersect_nearest_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        let mut t_max = ray.t_max;
+        self.traverse(ray, ray.t_min, ray.t_max, |faces| {
+            for face in faces {
+                if let Some(hit) = self.intersect_face(ray, face) {
+                    if hit.t >= ray.t_min && hit.t <= t_max {
+                        t_max = hit.t;
+                        xs.push(hit);
+                    }
+                }
+            }
+            t_max
+        });
+    }
+
+    // interpolated from the vertex normals when the face has them
+    pub fn normal_at(&self, face: usize, u: f64, v: f64) -> Tuple {
+        match self.faces[face].normals {
+            Some([n1, n2, n3]) => {
+                let [n1, n2, n3] = [n1, n2, n3].map(|i| &self.normals[i as usize]);
+                n2 * u + n3 * v + n1 * (1.0 - u - v)
+            }
+            None => {
+                let [p1, p2, p3] = self.corners(face);
+                cross(&(p3 - p1), &(p2 - p1)).normalize()
+            }
+        }
+    }
+
+    pub fn texture_uv(&self, face: usize, u: f64, v: f64) -> (f64, f64) {
+        let uvs = self.faces[face]
+            .uvs
+            .map(|corners| corners.map(|i| self.uvs[i as usize]));
+        texture_uv(&uvs, &(u, v))
+    }
+
+    pub fn bounds(&self) -> AABB {
+        match self.nodes.first() {
+            Some(root) => AABB {
+                min: point(root.min[0], root.min[1], root.min[2]),
+                max: point(root.max[0], root.max[1], root.max[2]),
+            },
+            None => AABB {
+                min: point(0.0, 0.0, 0.0),
+                max: point(0.0, 0.0, 0.0),
+            },
+        }
+    }
+}
+
+// splits the items at the median of their centroids along the axis where the
+// centroids spread the most
+fn build(nodes: &mut Vec<Node>, items: &mut [Item], first: usize, depth: usize) {
+    debug_assert!(depth < MAX_DEPTH);
+    let mut bounds = items[0].bounds.clone();
+    let mut centroids = AABB {
+        min: items[0].centroid.clone(),
+        max: items[0].centroid.clone(),
+    };
+    for item in items.iter() {
+        bounds = union(&bounds, &item.bounds);
+        centroids = union(
+            &centroids,
+            &AABB {
+                min: item.centroid.clone(),
+                max: item.centroid.clone(),
+            },
+        );
+    }
+    let index = nodes.len();
+    nodes.push(Node {
+        min: [bounds.min.x, bounds.min.y, bounds.min.z],
+        max: [bounds.max.x, bounds.max.y, bounds.max.z],
+        first: first as u32,
+        count: items.len() as u32,
+    });
+    if items.len() <= LEAF_SIZE {
+        return;
+    }
+
+    let extent = &centroids.max - &centroids.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    let middle = items.len() / 2;
+    items.select_nth_unstable_by(middle, |a, b| {
+        coordinate(&a.centroid, axis)
+            .partial_cmp(&coordinate(&b.centroid, axis))
+            .unwrap_or(Ordering::Equal)
+    });
+    let (left, right) = items.split_at_mut(middle);
+    build(nodes, left, first, depth + 1);
+    let second = nodes.len();
+    build(nodes, right, first + middle, depth + 1);
+    nodes[index].first = second as u32;
+    nodes[index].count = 0;
+}
+
+fn coordinate(p: &Tuple, axis: usize) -> f64 {
+    match axis {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+fn union(a: &AABB, b: &AABB) -> AABB {
+    AABB {
+        min: point(
+            a.min.x.min(b.min.x),
+            a.min.y.min(b.min.y),
+            a.min.z.min(b.min.z),
+        ),
+        max: point(
+            a.max.x.max(b.max.x),
+            a.max.y.max(b.max.y),
+            a.max.z.max(b.max.z),
+        ),
+    }
+}
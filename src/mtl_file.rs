@@ -0,0 +1,161 @@
+// Wavefront material libraries. The Phong terms of the format are colors
+// while materials::Material uses scalars, so the average of Ka and Ks scales
+// the default ambient and specular terms and Kd becomes the color.
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    canvas::Canvas,
+    materials::Material,
+    obj_file::ObjError,
+    patterns::{image_pattern, Filter, Pattern, Wrap},
+    tuples::{color, Tuple},
+};
+
+struct Entry {
+    name: String,
+    material: Material,
+    illum: Option<usize>,
+    specular: f64,
+}
+
+impl Entry {
+    fn finish(mut self) -> (String, Material) {
+        match self.illum {
+            // color on, ambient off: the diffuse color without any shading
+            Some(0) => {
+                self.material.ambient = 1.0;
+                self.material.diffuse = 0.0;
+                self.material.specular = 0.0;
+            }
+            Some(1) => self.material.specular = 0.0,
+            // the illumination models with reflections turned on
+            Some(3..=8) => self.material.reflective = self.specular,
+            _ => {}
+        }
+        (self.name, self.material)
+    }
+}
+
+// map_Kd images are loaded relative to directory
+pub fn parse_mtl_file(
+    content: &str,
+    directory: &Path,
+) -> Result<HashMap<String, Material>, ObjError> {
+    let mut materials = HashMap::new();
+    let mut textures: HashMap<String, Pattern> = HashMap::new();
+    let mut entry: Option<Entry> = None;
+
+    for (number, line) in content.lines().enumerate() {
+        let number = number + 1;
+        let mut fields = line.split_whitespace();
+        let keyword = match fields.next() {
+            None => continue,
+            Some(k) if k.starts_with('#') => continue,
+            Some(k) => k,
+        };
+        let fields: Vec<&str> = fields.collect();
+
+        if keyword == "newmtl" {
+            let name = fields
+                .first()
+                .ok_or_else(|| ObjError::new(number, "missing material name"))?;
+            if let Some(previous) = entry.take() {
+                let (name, material) = previous.finish();
+                materials.insert(name, material);
+            }
+            entry = Some(Entry {
+                name: name.to_string(),
+                material: Material::default(),
+                illum: None,
+                specular: 1.0,
+            });
+            continue;
+        }
+
+        let current = match entry.as_mut() {
+            Some(current) => current,
+            None => {
+                return Err(ObjError::new(
+                    number,
+                    format!("'{}' before the first newmtl", keyword),
+                ))
+            }
+        };
+        let material = &mut current.material;
+        match keyword {
+            "Kd" => material.color = rgb(&fields, number)?,
+            "Ka" => material.ambient = Material::default().ambient * average(&fields, number)?,
+            "Ks" => {
+                current.specular = average(&fields, number)?;
+                material.specular = Material::default().specular * current.specular;
+            }
+            "Ns" => material.shininess = scalar(&fields, number)?,
+            "d" => material.transparency = 1.0 - scalar(&fields, number)?,
+            "Tr" => material.transparency = scalar(&fields, number)?,
+            "Ni" => material.refractive_index = scalar(&fields, number)?,
+            "illum" => {
+                let illum = fields.first().and_then(|f| f.parse().ok());
+                if illum.is_none() {
+                    return Err(ObjError::new(number, "invalid illumination model"));
+                }
+                current.illum = illum;
+            }
+            "map_Kd" => {
+                // options come before the file name
+                let file = fields
+                    .last()
+                    .ok_or_else(|| ObjError::new(number, "missing texture file"))?;
+                if !textures.contains_key(*file) {
+                    let path = directory.join(file);
+                    let canvas = Canvas::load(&path).map_err(|err| {
+                        ObjError::new(
+                            number,
+                            format!("could not read {}: {}", path.display(), err),
+                        )
+                    })?;
+                    let pattern = image_pattern(canvas, Filter::Bilinear, Wrap::Repeat);
+                    textures.insert(file.to_string(), pattern);
+                }
+                material.pattern = Some(Box::new(textures[*file].clone()));
+            }
+            // emission, bump maps and other extensions have no equivalent
+            _ => {}
+        }
+    }
+    if let Some(last) = entry {
+        let (name, material) = last.finish();
+        materials.insert(name, material);
+    }
+    Ok(materials)
+}
+
+fn numbers(fields: &[&str], line: usize) -> Result<Vec<f64>, ObjError> {
+    fields
+        .iter()
+        .map(|f| {
+            f.parse()
+                .map_err(|_| ObjError::new(line, format!("invalid number '{}'", f)))
+        })
+        .collect()
+}
+
+fn scalar(fields: &[&str], line: usize) -> Result<f64, ObjError> {
+    match numbers(fields, line)?[..] {
+        [value] => Ok(value),
+        _ => Err(ObjError::new(line, "expected a single number")),
+    }
+}
+
+// a single value stands for a gray
+fn rgb(fields: &[&str], line: usize) -> Result<Tuple, ObjError> {
+    match numbers(fields, line)?[..] {
+        [value] => Ok(color(value, value, value)),
+        [r, g, b] => Ok(color(r, g, b)),
+        _ => Err(ObjError::new(line, "expected a color")),
+    }
+}
+
+fn average(fields: &[&str], line: usize) -> Result<f64, ObjError> {
+    let c = rgb(fields, line)?;
+    Ok((c.x + c.y + c.z) / 3.0)
+}
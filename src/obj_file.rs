@@ -1,10 +1,13 @@
-use std::collections::HashMap;
-
-use regex::Regex;
+use std::{collections::HashMap, fmt, fs, path::Path, sync::Arc};
 
 use crate::{
     groups::Group,
-    objects::{smooth_triangle, triangle},
+    materials::Material,
+    matrices::identity_matrix,
+    meshes::{Face, Mesh},
+    mtl_file::parse_mtl_file,
+    objects::{Object, Shape},
+    triangles::{SmoothTriangle, Triangle},
     tuples::{point, Tuple},
 };
 
@@ -13,8 +16,59 @@ pub struct Parser {
     pub ignored_lines: usize,
     pub vertices: Vec<Tuple>,
     pub normals: Vec<Tuple>,
+    pub texture_vertices: Vec<(f64, f64)>,
     current_group: String,
     pub groups: HashMap<String, Group>,
+    pub material_libraries: Vec<String>,
+    pub materials: HashMap<String, Material>,
+    // problems that did not stop the parser, e.g. missing material libraries
+    pub warnings: Vec<ObjError>,
+    current_material: Material,
+    current_material_name: String,
+    // faces of the meshes by group and material, None when faces become
+    // single triangles
+    meshes: Option<Vec<MeshFaces>>,
+}
+
+#[derive(Debug)]
+struct MeshFaces {
+    group: String,
+    material_name: String,
+    material: Material,
+    faces: Vec<Face>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ObjError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        ObjError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}", self.message);
+        }
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+// one corner of a face, indices are already resolved to positions in the lists
+struct FaceVertex {
+    vertex: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
 }
 
 impl Default for Parser {
@@ -28,6 +82,7 @@ impl Parser {
         let ignored_lines = 0;
         let vertices = Vec::default();
         let normals = Vec::default();
+        let texture_vertices = Vec::default();
         let current_group = "default_group".to_string();
         let mut groups = HashMap::default();
         groups.insert(current_group.clone(), Group::default());
@@ -35,182 +90,271 @@ impl Parser {
             ignored_lines,
             vertices,
             normals,
+            texture_vertices,
             current_group,
             groups,
+            material_libraries: Vec::default(),
+            materials: HashMap::default(),
+            warnings: Vec::default(),
+            current_material: Material::default(),
+            current_material_name: String::default(),
+            meshes: None,
         }
     }
 
-    pub fn parse_obj_file(content: &str) -> Parser {
-        let mut p = Parser::new();
+    // material libraries are only recorded, faces use the default material
+    pub fn parse_obj_file(content: &str) -> Result<Parser, ObjError> {
+        Self::parse(content, None, false)
+    }
 
-        let re_vertex = Regex::new(r"^v +(?P<x>[-0-9.]+) (?P<y>[-0-9.]+) (?P<z>[-0-9.]+)$")
-            .expect("vertex regex is invalid");
-        let re_normal = Regex::new(r"^vn +(?P<x>[-0-9.]+) (?P<y>[-0-9.]+) (?P<z>[-0-9.]+)$")
-            .expect("vertex regex is invalid");
-        let re_face = Regex::new(r"^f(( [0-9]+){3,})$").expect("face regex is invalid");
-        let re_smooth_face_2 =
-            Regex::new(r"^f(( +[0-9]*/[0-9]*){3,})$").expect("face regex is invalid");
-        let re_smooth_face_3 =
-            Regex::new(r"^f(( +[0-9]*/[0-9]*/[0-9]*){3,})$").expect("face regex is invalid");
-        let re_group = Regex::new(r"^g +(?P<name>\w+)$").expect("group regex is invalid");
-
-        for line in content.lines() {
-            let line = line.trim();
-
-            // vertex
-            if let Some(cap) = re_vertex.captures(line) {
-                let x = cap
-                    .name("x")
-                    .expect("x not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("x is not a usize");
-                let y = cap
-                    .name("y")
-                    .expect("y not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("y is not a usize");
-                let z = cap
-                    .name("z")
-                    .expect("z not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("z is not a usize");
-                p.vertices.push(point(x, y, z));
-                continue;
-            }
+    // the faces of a group that share a material become a single mesh, which
+    // needs a fraction of the memory of the triangle objects
+    pub fn parse_obj_file_as_meshes(content: &str) -> Result<Parser, ObjError> {
+        Self::parse(content, None, true)
+    }
 
-            // vertex normal
-            if let Some(cap) = re_normal.captures(line) {
-                let x = cap
-                    .name("x")
-                    .expect("x not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("x is not a usize");
-                let y = cap
-                    .name("y")
-                    .expect("y not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("y is not a usize");
-                let z = cap
-                    .name("z")
-                    .expect("z not found")
-                    .as_str()
-                    .parse::<f64>()
-                    .expect("z is not a usize");
-                p.normals.push(point(x, y, z));
-                continue;
-            }
+    // reads the file and the material libraries it refers to, which are
+    // looked up next to it
+    pub fn load_obj_file(path: &Path) -> Result<Parser, ObjError> {
+        Self::load(path, false)
+    }
+
+    pub fn load_obj_file_as_meshes(path: &Path) -> Result<Parser, ObjError> {
+        Self::load(path, true)
+    }
+
+    fn load(path: &Path, meshes: bool) -> Result<Parser, ObjError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            ObjError::new(0, format!("could not read {}: {}", path.display(), err))
+        })?;
+        let directory = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse(&content, Some(directory), meshes)
+    }
+
+    fn parse(content: &str, directory: Option<&Path>, meshes: bool) -> Result<Parser, ObjError> {
+        let mut p = Parser::new();
+        if meshes {
+            p.meshes = Some(Vec::new());
+        }
 
-            // face
-            if let Some(cap) = re_face.captures(line) {
-                let indices: Vec<usize> = cap[1]
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.parse::<usize>().expect("offset not a usize"))
-                    .collect();
-                for i in 1..(indices.len() - 1) {
-                    let idx_1 = indices[0];
-                    let idx_2 = indices[i];
-                    let idx_3 = indices[i + 1];
-                    let p1 = p.vertices[idx_1 - 1].clone();
-                    let p2 = p.vertices[idx_2 - 1].clone();
-                    let p3 = p.vertices[idx_3 - 1].clone();
+        for (number, line) in content.lines().enumerate() {
+            let number = number + 1;
+            let mut fields = line.split_whitespace();
+            let keyword = fields.next();
+            let fields: Vec<&str> = fields.collect();
+
+            match keyword {
+                Some("v") => p.vertices.push(tuple(&fields, number)?),
+                Some("vn") => p.normals.push(tuple(&fields, number)?),
+                Some("vt") => {
+                    if fields.is_empty() || fields.len() > 3 {
+                        return Err(ObjError::new(number, "expected 1 to 3 texture coordinates"));
+                    }
+                    let u = float(fields[0], number)?;
+                    let v = match fields.get(1) {
+                        Some(v) => float(v, number)?,
+                        None => 0.0,
+                    };
+                    p.texture_vertices.push((u, v));
+                }
+                Some("f") => p.face(&fields, number)?,
+                // objects are kept as groups of their own
+                Some("g") | Some("o") => {
+                    let name = fields
+                        .first()
+                        .ok_or_else(|| ObjError::new(number, "missing group name"))?;
+                    p.current_group = name.to_string();
                     p.groups
-                        .get_mut(&p.current_group)
-                        .expect("group is missing")
-                        .add_object(triangle(p1, p2, p3));
+                        .entry(name.to_string())
+                        .or_insert_with(Group::default);
                 }
-                continue;
+                Some("mtllib") => {
+                    for library in fields {
+                        p.material_libraries.push(library.to_string());
+                        if let Some(directory) = directory {
+                            p.load_materials(&directory.join(library), directory, number)?;
+                        }
+                    }
+                }
+                // unknown materials fall back to the default one
+                Some("usemtl") => {
+                    let name = fields.first().unwrap_or(&"");
+                    p.current_material = match p.materials.get(*name) {
+                        Some(material) => material.clone(),
+                        None => Material::default(),
+                    };
+                    p.current_material_name = name.to_string();
+                }
+                _ => p.ignored_lines += 1,
             }
+        }
 
-            // face + skipped vt
-            if let Some(cap) = re_smooth_face_2.captures(line) {
-                let indices: Vec<Vec<Option<usize>>> = cap[1]
-                    .trim()
-                    .split_whitespace()
-                    .map(|s: &str| {
-                        s.split('/')
-                            .map(|s| match s.parse::<usize>() {
-                                Ok(v) => Some(v),
-                                Err(_) => None,
-                            })
-                            .collect()
-                    })
-                    .collect();
-                for i in 1..(indices.len() - 1) {
-                    let vectex_1 = indices[0][0].expect("vectex_1 is missing");
-                    let vectex_2 = indices[i][0].expect("vectex_2 is missing");
-                    let vectex_3 = indices[i + 1][0].expect("vectex_3 is missing");
-                    let p1 = p.vertices[vectex_1 - 1].clone();
-                    let p2 = p.vertices[vectex_2 - 1].clone();
-                    let p3 = p.vertices[vectex_3 - 1].clone();
-                    let obj = triangle(p1, p2, p3);
-                    p.groups
-                        .get_mut(&p.current_group)
-                        .expect("group is missing")
-                        .add_object(obj);
-                }
-                continue;
+        p.add_meshes();
+        Ok(p)
+    }
+
+    // the meshes share the buffers of the whole file
+    fn add_meshes(&mut self) {
+        let meshes = match self.meshes.take() {
+            Some(meshes) => meshes,
+            None => return,
+        };
+        let vertices = Arc::new(self.vertices.clone());
+        let normals = Arc::new(self.normals.clone());
+        let uvs = Arc::new(self.texture_vertices.clone());
+        for entry in meshes {
+            let mesh = Mesh::new(vertices.clone(), normals.clone(), uvs.clone(), entry.faces);
+            let object = Object::new(Shape::Mesh(mesh), identity_matrix(), entry.material);
+            self.groups
+                .entry(entry.group)
+                .or_insert_with(Group::default)
+                .add_object(object);
+        }
+    }
+
+    // models are often shared without their libraries, so a missing one only
+    // leaves its materials undefined
+    fn load_materials(
+        &mut self,
+        path: &Path,
+        directory: &Path,
+        line: usize,
+    ) -> Result<(), ObjError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                let message = format!("skipped material library {}: {}", path.display(), err);
+                self.warnings.push(ObjError::new(line, message));
+                return Ok(());
             }
+        };
+        let materials = parse_mtl_file(&content, directory).map_err(|err| {
+            ObjError::new(err.line, format!("{}: {}", path.display(), err.message))
+        })?;
+        self.materials.extend(materials);
+        Ok(())
+    }
 
-            // face + skipped vt + vn
-            if let Some(cap) = re_smooth_face_3.captures(line) {
-                let indices: Vec<Vec<Option<usize>>> = cap[1]
-                    .trim()
-                    .split_whitespace()
-                    .map(|s: &str| {
-                        s.split('/')
-                            .map(|s| match s.parse::<usize>() {
-                                Ok(v) => Some(v),
-                                Err(_) => None,
-                            })
-                            .collect()
-                    })
-                    .collect();
-                for i in 1..(indices.len() - 1) {
-                    let vectex_1 = indices[0][0].expect("vectex_1 is missing");
-                    let vectex_2 = indices[i][0].expect("vectex_2 is missing");
-                    let vectex_3 = indices[i + 1][0].expect("vectex_3 is missing");
-                    let normal_1 = indices[0][2].expect("normal_1 is missing");
-                    let normal_2 = indices[i][2].expect("normal_2 is missing");
-                    let normal_3 = indices[i + 1][2].expect("normal_3 is missing");
-                    let p1 = p.vertices[vectex_1 - 1].clone();
-                    let p2 = p.vertices[vectex_2 - 1].clone();
-                    let p3 = p.vertices[vectex_3 - 1].clone();
-                    let n1 = p.normals[normal_1 - 1].clone();
-                    let n2 = p.normals[normal_2 - 1].clone();
-                    let n3 = p.normals[normal_3 - 1].clone();
-                    let obj = smooth_triangle(p1, p2, p3, n1, n2, n3);
-                    // let obj = triangle(p1, p2, p3);
-                    p.groups
-                        .get_mut(&p.current_group)
-                        .expect("group is missing")
-                        .add_object(obj);
+    fn face(&mut self, fields: &[&str], line: usize) -> Result<(), ObjError> {
+        if fields.len() < 3 {
+            return Err(ObjError::new(line, "a face needs at least 3 vertices"));
+        }
+        let corners = fields
+            .iter()
+            .map(|field| self.face_vertex(field, line))
+            .collect::<Result<Vec<FaceVertex>, ObjError>>()?;
+        if self.meshes.is_some() {
+            self.mesh_face(&corners);
+            return Ok(());
+        }
+
+        let group = self
+            .groups
+            .get_mut(&self.current_group)
+            .expect("group is missing");
+        // fan triangulation around the first vertex
+        for i in 1..(corners.len() - 1) {
+            let (c1, c2, c3) = (&corners[0], &corners[i], &corners[i + 1]);
+            let p1 = self.vertices[c1.vertex].clone();
+            let p2 = self.vertices[c2.vertex].clone();
+            let p3 = self.vertices[c3.vertex].clone();
+            let uvs = match (c1.texture, c2.texture, c3.texture) {
+                (Some(t1), Some(t2), Some(t3)) => Some([
+                    self.texture_vertices[t1],
+                    self.texture_vertices[t2],
+                    self.texture_vertices[t3],
+                ]),
+                _ => None,
+            };
+            let shape = match (c1.normal, c2.normal, c3.normal) {
+                (Some(n1), Some(n2), Some(n3)) => {
+                    let n1 = self.normals[n1].clone();
+                    let n2 = self.normals[n2].clone();
+                    let n3 = self.normals[n3].clone();
+                    let mut t = SmoothTriangle::new(p1, p2, p3, n1, n2, n3);
+                    t.uvs = uvs;
+                    Shape::SmoothTriangle(t)
                 }
-                continue;
-            }
+                _ => {
+                    let mut t = Triangle::new(p1, p2, p3);
+                    t.uvs = uvs;
+                    Shape::Triangle(t)
+                }
+            };
+            let material = self.current_material.clone();
+            group.add_object(Object::new(shape, identity_matrix(), material));
+        }
+        Ok(())
+    }
 
-            // group
-            if let Some(cap) = re_group.captures(line) {
-                let name = cap
-                    .name("name")
-                    .expect("group name is missing")
-                    .as_str()
-                    .to_string();
-                p.groups.insert(name.to_string(), Group::default()); // .expect("group defined twice");
-                p.current_group = name;
-                continue;
+    fn mesh_face(&mut self, corners: &[FaceVertex]) {
+        let meshes = self.meshes.as_mut().expect("meshes are missing");
+        let position = meshes.iter().position(|m| {
+            m.group == self.current_group && m.material_name == self.current_material_name
+        });
+        let entry = match position {
+            Some(position) => &mut meshes[position],
+            None => {
+                meshes.push(MeshFaces {
+                    group: self.current_group.clone(),
+                    material_name: self.current_material_name.clone(),
+                    material: self.current_material.clone(),
+                    faces: Vec::new(),
+                });
+                meshes.last_mut().unwrap()
             }
-
-            // ignore
-            p.ignored_lines += 1
+        };
+        // fan triangulation around the first vertex
+        for i in 1..(corners.len() - 1) {
+            let (c1, c2, c3) = (&corners[0], &corners[i], &corners[i + 1]);
+            let normals = match (c1.normal, c2.normal, c3.normal) {
+                (Some(n1), Some(n2), Some(n3)) => Some([n1 as u32, n2 as u32, n3 as u32]),
+                _ => None,
+            };
+            let uvs = match (c1.texture, c2.texture, c3.texture) {
+                (Some(t1), Some(t2), Some(t3)) => Some([t1 as u32, t2 as u32, t3 as u32]),
+                _ => None,
+            };
+            entry.faces.push(Face {
+                vertices: [c1.vertex as u32, c2.vertex as u32, c3.vertex as u32],
+                normals,
+                uvs,
+            });
         }
+    }
 
-        p
+    // vertex, vertex/texture, vertex//normal or vertex/texture/normal
+    fn face_vertex(&self, field: &str, line: usize) -> Result<FaceVertex, ObjError> {
+        let mut parts = field.split('/');
+        let vertex = parts.next().unwrap_or_default();
+        let vertex = resolve_index(vertex, self.vertices.len(), "vertex", line)?;
+        // files without texture vertices sometimes still carry texture
+        // indices, which are then only checked for being numbers
+        let texture = match parts.next().filter(|t| !t.is_empty()) {
+            Some(texture) if !self.texture_vertices.is_empty() => Some(resolve_index(
+                texture,
+                self.texture_vertices.len(),
+                "texture",
+                line,
+            )?),
+            Some(texture) => integer(texture, line).map(|_| None)?,
+            None => None,
+        };
+        let normal = match parts.next().filter(|n| !n.is_empty()) {
+            Some(normal) => Some(resolve_index(normal, self.normals.len(), "normal", line)?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(ObjError::new(
+                line,
+                format!("invalid face vertex '{}'", field),
+            ));
+        }
+        Ok(FaceVertex {
+            vertex,
+            texture,
+            normal,
+        })
     }
 
     pub fn to_group(&self) -> Group {
@@ -222,3 +366,43 @@ impl Parser {
         g
     }
 }
+
+// indices count from 1, negative ones count back from the last element read so far
+fn resolve_index(field: &str, count: usize, kind: &str, line: usize) -> Result<usize, ObjError> {
+    let index = integer(field, line)?;
+    let resolved = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => count as i64 + i,
+        _ => -1,
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::new(
+            line,
+            format!("{} index {} is out of range", kind, index),
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+fn integer(field: &str, line: usize) -> Result<i64, ObjError> {
+    field
+        .parse()
+        .map_err(|_| ObjError::new(line, format!("invalid index '{}'", field)))
+}
+
+fn float(field: &str, line: usize) -> Result<f64, ObjError> {
+    field
+        .parse()
+        .map_err(|_| ObjError::new(line, format!("invalid number '{}'", field)))
+}
+
+// x, y and z with an optional fourth weight that is not used
+fn tuple(fields: &[&str], line: usize) -> Result<Tuple, ObjError> {
+    if fields.len() != 3 && fields.len() != 4 {
+        return Err(ObjError::new(line, "expected 3 coordinates"));
+    }
+    let x = float(fields[0], line)?;
+    let y = float(fields[1], line)?;
+    let z = float(fields[2], line)?;
+    Ok(point(x, y, z))
+}
@@ -1,11 +1,17 @@
 use crate::{
     groups::AABB,
-    intersections::Intersection,
+    heightfields::Heightfield,
+    intersections::{Intersection, ShapeHit},
+    lathes::Lathe,
     materials::{Material, REFRACTIVE_INDEX_GLASS},
     matrices::{identity_matrix, Matrix4x4},
+    meshes::Mesh,
+    patterns::{cube_map, cylindrical_map, planar_map, spherical_map, toroidal_map},
+    quartic::solve_quartic,
     rays::Ray,
-    triangles::{SmoothTriangle, Triangle},
-    tuples::{color, cross, dot, point, vector, Tuple},
+    sdf::Sdf,
+    triangles::{intersect_triangle, texture_uv, SmoothTriangle, Triangle},
+    tuples::{color, dot, point, vector, Tuple},
 };
 use std::sync::{Arc, RwLock};
 
@@ -95,6 +101,62 @@ pub fn default_cone() -> Object {
     Object::new(shape, transform, material)
 }
 
+// finite pieces of the xz plane: a disk with radius 1, the square from -1 to 1
+// and a ring between inner and radius 1
+pub fn default_disk() -> Object {
+    let shape = Shape::Disk;
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+pub fn default_rectangle() -> Object {
+    let shape = Shape::Rectangle;
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+pub fn annulus(inner: f64) -> Object {
+    let shape = Shape::Annulus(inner);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+// a torus around the y axis, major is the radius of the ring and minor the
+// radius of the tube
+pub fn torus(major: f64, minor: f64) -> Object {
+    let shape = Shape::Torus(major, minor);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+// a profile revolved around the y axis
+pub fn lathe(lathe: Lathe) -> Object {
+    let shape = Shape::Lathe(lathe);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+// heights over the unit square of the xz plane
+pub fn heightfield(heightfield: Heightfield) -> Object {
+    let shape = Shape::Heightfield(heightfield);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
+// the surface where a signed distance function vanishes
+pub fn sdf(sdf: Sdf) -> Object {
+    let shape = Shape::Sdf(sdf);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
 pub fn triangle(p1: Tuple, p2: Tuple, p3: Tuple) -> Object {
     let shape = Shape::Triangle(Triangle::new(p1, p2, p3));
     let transform = identity_matrix();
@@ -102,6 +164,13 @@ pub fn triangle(p1: Tuple, p2: Tuple, p3: Tuple) -> Object {
     Object::new(shape, transform, material)
 }
 
+pub fn mesh(mesh: Mesh) -> Object {
+    let shape = Shape::Mesh(mesh);
+    let transform = identity_matrix();
+    let material = Material::default();
+    Object::new(shape, transform, material)
+}
+
 pub fn smooth_triangle(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Object {
     let shape = Shape::SmoothTriangle(SmoothTriangle::new(p1, p2, p3, n1, n2, n3));
     let transform = identity_matrix();
@@ -156,6 +225,16 @@ impl Object {
         self.intersect_local(&local_ray, obj)
     }
 
+    pub fn intersect_into(&self, world_ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        let local_ray = world_ray.transform(&self.transform_inverse);
+        self.shape.intersect_into(&local_ray, xs)
+    }
+
+    pub fn intersect_nearest_into(&self, world_ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        let local_ray = world_ray.transform(&self.transform_inverse);
+        self.shape.intersect_nearest_into(&local_ray, xs)
+    }
+
     pub fn normal_at(&self, world_point: &Tuple, hit: &Intersection) -> Tuple {
         let local_point = &self.transform_inverse * world_point;
         let local_normal = self.shape.normal_at(&local_point, hit);
@@ -189,11 +268,19 @@ impl Object {
 pub enum Shape {
     Sphere,
     Plane,
+    Disk,
+    Rectangle,
+    Annulus(f64),
     Cube,
     Cylinder(f64, f64, bool),
     Cone(f64, f64, bool),
+    Torus(f64, f64),
+    Lathe(Lathe),
+    Heightfield(Heightfield),
+    Sdf(Sdf),
     Triangle(Triangle),
     SmoothTriangle(SmoothTriangle),
+    Mesh(Mesh),
     Testshape,
 }
 
@@ -203,19 +290,43 @@ thread_local! {
 
 impl Shape {
     pub fn intersect(&self, ray: &Ray, obj: &Arc<Object>) -> Vec<Intersection> {
+        let mut xs = Vec::new();
+        self.intersect_into(ray, &mut xs);
+        xs.iter().map(|hit| hit.with_object(obj)).collect()
+    }
+
+    // appends hits that include the nearest one inside the interval of the
+    // ray, only meshes are large enough to skip what lies behind it
+    pub fn intersect_nearest_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        match self {
+            Shape::Mesh(m) => m.intersect_nearest_into(ray, xs),
+            shape => shape.intersect_into(ray, xs),
+        }
+    }
+
+    // appends the hits to xs, a buffer that is reused keeps the hot path free
+    // of allocations
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
         match self {
             Shape::Plane => {
-                if ray.direction.y.abs() < f64::EPSILON {
-                    return vec![];
+                if let Some(t) = intersect_xz_plane(ray) {
+                    xs.push(ShapeHit::at(t));
+                }
+            }
+            Shape::Disk | Shape::Rectangle | Shape::Annulus(_) => {
+                let Some(t) = intersect_xz_plane(ray) else {
+                    return;
+                };
+                let x = ray.origin.x + t * ray.direction.x;
+                let z = ray.origin.z + t * ray.direction.z;
+                let inside = match self {
+                    Shape::Rectangle => x.abs() <= 1.0 && z.abs() <= 1.0,
+                    Shape::Annulus(inner) => (inner.powi(2)..=1.0).contains(&(x * x + z * z)),
+                    _ => x * x + z * z <= 1.0,
+                };
+                if inside {
+                    xs.push(ShapeHit::at(t));
                 }
-
-                let t = -ray.origin.y / ray.direction.y;
-                vec![Intersection {
-                    t,
-                    object: obj.clone(),
-                    u: 0.0,
-                    v: 0.0,
-                }]
             }
             Shape::Sphere => {
                 let sphere_to_ray = &ray.origin - point(0.0, 0.0, 0.0); // Sphere is at 0, 0, 0
@@ -227,25 +338,13 @@ impl Shape {
                 let discriminant = (b * b) - 4.0 * a * c;
 
                 if discriminant < 0.0 {
-                    return vec![];
+                    return;
                 }
 
                 let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
                 let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
-                vec![
-                    Intersection {
-                        t: t1,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    },
-                    Intersection {
-                        t: t2,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    },
-                ]
+                xs.push(ShapeHit::at(t1));
+                xs.push(ShapeHit::at(t2));
             }
             Shape::Cube => {
                 let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x);
@@ -255,7 +354,7 @@ impl Shape {
                 let tmax = if xtmax < ytmax { xtmax } else { ytmax };
 
                 if tmin > tmax {
-                    return vec![];
+                    return;
                 }
 
                 let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z);
@@ -264,39 +363,25 @@ impl Shape {
                 let tmax = if ztmax < tmax { ztmax } else { tmax };
 
                 if tmin > tmax {
-                    return vec![];
+                    return;
                 }
 
-                vec![
-                    Intersection {
-                        t: tmin,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    },
-                    Intersection {
-                        t: tmax,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    },
-                ]
+                xs.push(ShapeHit::at(tmin));
+                xs.push(ShapeHit::at(tmax));
             }
             Shape::Cylinder(min, max, closed) => {
-                let mut xs = Vec::with_capacity(2);
-
                 let a = ray.direction.x.powi(2) + ray.direction.z.powi(2);
                 // ray is parallel to the y axis
                 if a.abs() < f64::EPSILON {
-                    intersect_caps_cylinder(min, max, closed, ray, &mut xs, obj);
-                    return xs;
+                    intersect_caps_cylinder(min, max, closed, ray, xs);
+                    return;
                 }
                 let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
                 let c = ray.origin.x.powi(2) + ray.origin.z.powi(2) - 1.0;
                 let disc = b.powi(2) - 4.0 * a * c;
                 // ray does not intersect the cylinder
                 if disc < 0.0 {
-                    return vec![];
+                    return;
                 }
 
                 let mut t0 = (-b - disc.sqrt()) / (2.0 * a);
@@ -307,30 +392,16 @@ impl Shape {
 
                 let y0 = ray.origin.y + t0 * ray.direction.y;
                 if min < &y0 && &y0 < max {
-                    xs.push(Intersection {
-                        t: t0,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    });
+                    xs.push(ShapeHit::at(t0));
                 }
                 let y1 = ray.origin.y + t1 * ray.direction.y;
                 if min < &y1 && &y1 < max {
-                    xs.push(Intersection {
-                        t: t1,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    });
+                    xs.push(ShapeHit::at(t1));
                 }
 
-                intersect_caps_cylinder(min, max, closed, ray, &mut xs, obj);
-
-                xs
+                intersect_caps_cylinder(min, max, closed, ray, xs);
             }
             Shape::Cone(min, max, closed) => {
-                let mut xs = Vec::with_capacity(4);
-
                 let a = ray.direction.x.powi(2) - ray.direction.y.powi(2) + ray.direction.z.powi(2);
                 let b = 2.0 * ray.origin.x * ray.direction.x - 2.0 * ray.origin.y * ray.direction.y
                     + 2.0 * ray.origin.z * ray.direction.z;
@@ -338,12 +409,7 @@ impl Shape {
 
                 if a.abs() < f64::EPSILON && b.abs() > f64::EPSILON {
                     let t = -c / (2.0 * b);
-                    xs.push(Intersection {
-                        t,
-                        object: obj.clone(),
-                        u: 0.0,
-                        v: 0.0,
-                    });
+                    xs.push(ShapeHit::at(t));
                 }
 
                 if a.abs() > f64::EPSILON {
@@ -358,97 +424,43 @@ impl Shape {
 
                         let y0 = ray.origin.y + t0 * ray.direction.y;
                         if min < &y0 && &y0 < max {
-                            xs.push(Intersection {
-                                t: t0,
-                                object: obj.clone(),
-                                u: 0.0,
-                                v: 0.0,
-                            });
+                            xs.push(ShapeHit::at(t0));
                         }
                         let y1 = ray.origin.y + t1 * ray.direction.y;
                         if min < &y1 && &y1 < max {
-                            xs.push(Intersection {
-                                t: t1,
-                                object: obj.clone(),
-                                u: 0.0,
-                                v: 0.0,
-                            });
+                            xs.push(ShapeHit::at(t1));
                         }
                     }
                 }
 
-                intersect_caps_cone(min, max, closed, ray, &mut xs, obj);
-
-                xs
+                intersect_caps_cone(min, max, closed, ray, xs);
             }
             Shape::Triangle(t) => {
-                let dir_cross_e2 = cross(&ray.direction, &t.e2);
-                let det = dot(&t.e1, &dir_cross_e2);
-
-                if det.abs() < f64::EPSILON {
-                    return vec![];
-                }
-
-                let f = 1.0 / det;
-                let p1_to_origin = &ray.origin - &t.p1;
-                let u = f * dot(&p1_to_origin, &dir_cross_e2);
-                if !(0.0..=1.0).contains(&u) {
-                    return vec![];
+                if let Some((t, u, v)) = intersect_triangle(ray, &t.p1, &t.e1, &t.e2) {
+                    xs.push(ShapeHit { t, u, v, face: 0 });
                 }
-
-                let origin_cross_e1 = cross(&p1_to_origin, &t.e1);
-                let v = f * dot(&ray.direction, &origin_cross_e1);
-                if v < 0.0 || (u + v) > 1.0 {
-                    return vec![];
-                }
-
-                let t = f * dot(&t.e2, &origin_cross_e1);
-                vec![Intersection {
-                    t,
-                    object: obj.clone(),
-                    u,
-                    v,
-                }]
             }
             Shape::SmoothTriangle(t) => {
-                let dir_cross_e2 = cross(&ray.direction, &t.e2);
-                let det = dot(&t.e1, &dir_cross_e2);
-
-                if det.abs() < f64::EPSILON {
-                    return vec![];
-                }
-
-                let f = 1.0 / det;
-                let p1_to_origin = &ray.origin - &t.p1;
-                let u = f * dot(&p1_to_origin, &dir_cross_e2);
-                if !(0.0..=1.0).contains(&u) {
-                    return vec![];
+                if let Some((t, u, v)) = intersect_triangle(ray, &t.p1, &t.e1, &t.e2) {
+                    xs.push(ShapeHit { t, u, v, face: 0 });
                 }
-
-                let origin_cross_e1 = cross(&p1_to_origin, &t.e1);
-                let v = f * dot(&ray.direction, &origin_cross_e1);
-                if v < 0.0 || (u + v) > 1.0 {
-                    return vec![];
-                }
-
-                let t = f * dot(&t.e2, &origin_cross_e1);
-                vec![Intersection {
-                    t,
-                    object: obj.clone(),
-                    u,
-                    v,
-                }]
             }
+            Shape::Mesh(m) => m.intersect_into(ray, xs),
+            Shape::Torus(major, minor) => intersect_torus(*major, *minor, ray, xs),
+            Shape::Lathe(l) => l.intersect_into(ray, xs),
+            Shape::Heightfield(h) => h.intersect_into(ray, xs),
+            Shape::Sdf(s) => s.intersect_into(ray, xs),
             Shape::Testshape => {
                 SAVED_RAY.with(|c| *c.write().unwrap() = Arc::new(ray.clone()));
-                vec![]
             }
         }
     }
 
     pub fn normal_at(&self, local_point: &Tuple, hit: &Intersection) -> Tuple {
         match self {
-            Shape::Plane => vector(0.0, 1.0, 0.0),
+            Shape::Plane | Shape::Disk | Shape::Rectangle | Shape::Annulus(_) => {
+                vector(0.0, 1.0, 0.0)
+            }
             Shape::Sphere => local_point - point(0.0, 0.0, 0.0),
             Shape::Cube => {
                 let xabs = local_point.x.abs();
@@ -486,20 +498,70 @@ impl Shape {
                 }
                 vector(local_point.x, y, local_point.z)
             }
+            Shape::Torus(major, _minor) => {
+                // away from the center of the tube cross section at the point
+                let dist = (local_point.x.powi(2) + local_point.z.powi(2)).sqrt();
+                if dist < f64::EPSILON {
+                    return vector(0.0, local_point.y.signum(), 0.0);
+                }
+                let scale = major / dist;
+                vector(
+                    local_point.x - local_point.x * scale,
+                    local_point.y,
+                    local_point.z - local_point.z * scale,
+                )
+            }
             Shape::Triangle(tri) => tri.normal.clone(),
             Shape::SmoothTriangle(tri) => {
                 &tri.n2 * hit.u + &tri.n3 * hit.v + &tri.n1 * (1.0 - hit.u - hit.v)
             }
+            Shape::Mesh(m) => m.normal_at(hit.face, hit.u, hit.v),
+            Shape::Lathe(l) => l.normal_at(local_point, hit.face, hit.u),
+            Shape::Heightfield(h) => h.normal_at(hit.face, hit.u, hit.v),
+            Shape::Sdf(s) => s.normal_at(local_point),
             Shape::Testshape => local_point - point(0.0, 0.0, 0.0),
         }
     }
 
+    // texture coordinates, triangles interpolate theirs with the barycentric
+    // coordinates of the hit
+    pub fn uv_at(&self, local_point: &Tuple, hit_uv: &(f64, f64)) -> (f64, f64) {
+        match self {
+            Shape::Sphere | Shape::Sdf(_) => spherical_map(local_point),
+            Shape::Plane | Shape::Testshape => planar_map(local_point),
+            // the whole shape covers the texture once
+            Shape::Disk | Shape::Rectangle => {
+                ((local_point.x + 1.0) / 2.0, (local_point.z + 1.0) / 2.0)
+            }
+            Shape::Annulus(inner) => {
+                let (u, _) = cylindrical_map(local_point);
+                let dist = (local_point.x.powi(2) + local_point.z.powi(2)).sqrt();
+                (u, (dist - inner) / (1.0 - inner))
+            }
+            Shape::Cube => cube_map(local_point),
+            Shape::Cylinder(..) | Shape::Cone(..) => cylindrical_map(local_point),
+            Shape::Torus(major, _minor) => toroidal_map(local_point, *major),
+            Shape::Triangle(t) => texture_uv(&t.uvs, hit_uv),
+            Shape::SmoothTriangle(t) => texture_uv(&t.uvs, hit_uv),
+            // prepare_computations already looked up the face that was hit
+            Shape::Mesh(_) => *hit_uv,
+            // v along the profile is found when intersecting
+            Shape::Lathe(_) => (cylindrical_map(local_point).0, hit_uv.1),
+            // the first row of the heights is at the top of the texture
+            Shape::Heightfield(_) => (local_point.x, 1.0 - local_point.z),
+        }
+    }
+
     pub fn bounds(&self) -> AABB {
         match self {
             Shape::Plane => AABB {
                 min: point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
                 max: point(f64::INFINITY, 0.0, f64::INFINITY),
             },
+            Shape::Disk | Shape::Rectangle | Shape::Annulus(_) => AABB {
+                min: point(-1.0, 0.0, -1.0),
+                max: point(1.0, 0.0, 1.0),
+            },
             Shape::Sphere => AABB {
                 min: point(-1.0, -1.0, -1.0),
                 max: point(1.0, 1.0, 1.0),
@@ -512,10 +574,20 @@ impl Shape {
                 min: point(-1.0, *minimum, -1.0),
                 max: point(1.0, *maximum, 1.0),
             },
-            Shape::Cone(minimum, maximum, _closed) => AABB {
-                min: point(*minimum, *minimum, *minimum),
-                max: point(*maximum, *maximum, *maximum),
-            },
+            Shape::Cone(minimum, maximum, _closed) => {
+                let radius = minimum.abs().max(maximum.abs());
+                AABB {
+                    min: point(-radius, *minimum, -radius),
+                    max: point(radius, *maximum, radius),
+                }
+            }
+            Shape::Torus(major, minor) => {
+                let radius = major + minor;
+                AABB {
+                    min: point(-radius, -minor, -radius),
+                    max: point(radius, *minor, radius),
+                }
+            }
             Shape::Triangle(t) => {
                 let min_x = min(t.p1.x, t.p2.x, t.p3.x);
                 let min_y = min(t.p1.y, t.p2.y, t.p3.y);
@@ -538,6 +610,10 @@ impl Shape {
                 let max = point(max_x, max_y, max_z);
                 AABB { min, max }
             }
+            Shape::Mesh(m) => m.bounds(),
+            Shape::Lathe(l) => l.bounds(),
+            Shape::Heightfield(h) => h.bounds(),
+            Shape::Sdf(s) => s.bounds(),
             Shape::Testshape => AABB {
                 min: point(-1.0, -1.0, -1.0),
                 max: point(1.0, 1.0, 1.0),
@@ -546,6 +622,48 @@ impl Shape {
     }
 }
 
+// where the ray crosses y = 0, rays parallel to the plane miss it
+fn intersect_xz_plane(ray: &Ray) -> Option<f64> {
+    if ray.direction.y.abs() < f64::EPSILON {
+        return None;
+    }
+    Some(-ray.origin.y / ray.direction.y)
+}
+
+// the t of the hits along the ray, inserting the ray into
+// (x² + y² + z² + R² - r²)² = 4R²(x² + z²) gives a quartic in t. The ray is
+// restarted where it enters the bounding sphere and its direction normalized
+// so the coefficients stay in a range where the solver is accurate even for
+// distant origins.
+fn intersect_torus(major: f64, minor: f64, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+    let length = ray.direction.magnitude();
+    let direction = &ray.direction / length;
+    let radius = major + minor;
+    let to_center = &ray.origin - point(0.0, 0.0, 0.0);
+    let b = dot(&direction, &to_center);
+    let c = dot(&to_center, &to_center) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return;
+    }
+    let start = -b - discriminant.sqrt();
+    let origin = &ray.origin + &direction * start;
+
+    let (o, d) = (&origin - point(0.0, 0.0, 0.0), direction);
+    let sum_r = major * major + minor * minor;
+    let e = dot(&o, &o) - sum_r;
+    let f = dot(&o, &d);
+    let four_major = 4.0 * major * major;
+    let roots = solve_quartic(
+        1.0,
+        4.0 * f,
+        2.0 * e + 4.0 * f * f + four_major * d.y * d.y,
+        4.0 * f * e + 2.0 * four_major * o.y * d.y,
+        e * e - four_major * (minor * minor - o.y * o.y),
+    );
+    xs.extend(roots.iter().map(|s| ShapeHit::at((start + s) / length)));
+}
+
 fn min(a: f64, b: f64, c: f64) -> f64 {
     if a < b && a < c {
         return a;
@@ -610,8 +728,7 @@ fn intersect_caps_cylinder(
     maximum: &f64,
     closed: &bool,
     ray: &Ray,
-    xs: &mut Vec<Intersection>,
-    obj: &Arc<Object>,
+    xs: &mut Vec<ShapeHit>,
 ) {
     // caps only matter if the cylinder is closed, and might possibly be
     // intersected by the ray.
@@ -623,24 +740,14 @@ fn intersect_caps_cylinder(
     // the ray with the plane at y=cyl.minimum
     let t = (minimum - ray.origin.y) / ray.direction.y;
     if check_cap(ray, t, 1.0) {
-        xs.push(Intersection {
-            t,
-            object: obj.clone(),
-            u: 0.0,
-            v: 0.0,
-        });
+        xs.push(ShapeHit::at(t));
     }
 
     // check for an intersection with the upper end cap by intersecting
     // the ray with the plane at y=cyl.maximum
     let t = (maximum - ray.origin.y) / ray.direction.y;
     if check_cap(ray, t, 1.0) {
-        xs.push(Intersection {
-            t,
-            object: obj.clone(),
-            u: 0.0,
-            v: 0.0,
-        });
+        xs.push(ShapeHit::at(t));
     }
 }
 
@@ -649,8 +756,7 @@ fn intersect_caps_cone(
     maximum: &f64,
     closed: &bool,
     ray: &Ray,
-    xs: &mut Vec<Intersection>,
-    obj: &Arc<Object>,
+    xs: &mut Vec<ShapeHit>,
 ) {
     // caps only matter if the cylinder is closed, and might possibly be
     // intersected by the ray.
@@ -662,23 +768,13 @@ fn intersect_caps_cone(
     // the ray with the plane at y=cyl.minimum
     let t = (minimum - ray.origin.y) / ray.direction.y;
     if check_cap(ray, t, *minimum) {
-        xs.push(Intersection {
-            t,
-            object: obj.clone(),
-            u: 0.0,
-            v: 0.0,
-        });
+        xs.push(ShapeHit::at(t));
     }
 
     // check for an intersection with the upper end cap by intersecting
     // the ray with the plane at y=cyl.maximum
     let t = (maximum - ray.origin.y) / ray.direction.y;
     if check_cap(ray, t, *maximum) {
-        xs.push(Intersection {
-            t,
-            object: obj.clone(),
-            u: 0.0,
-            v: 0.0,
-        });
+        xs.push(ShapeHit::at(t));
     }
 }
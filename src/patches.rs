@@ -0,0 +1,295 @@
+// Bicubic Bézier patches, the way the Utah teapot is defined. A patch is
+// tessellated into a mesh with a grid fine enough that no triangle strays
+// further than the tolerance from the surface. Every edge of a patch is split
+// by its own curve only, so patches sharing an edge split it the same way and
+// the inner grid is stitched to the edges without cracks. Vertex normals and
+// texture coordinates come from the patch itself.
+use std::sync::Arc;
+
+use crate::{
+    groups::AABB,
+    meshes::{Face, Mesh},
+    tuples::{cross, point, vector, Tuple},
+};
+
+// more segments than this along a side add nothing but memory
+const MAX_SEGMENTS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BezierPatch {
+    // four rows of four points, u runs along a row and v across the rows
+    pub points: [Tuple; 16],
+}
+
+impl BezierPatch {
+    pub fn new(points: [Tuple; 16]) -> BezierPatch {
+        BezierPatch { points }
+    }
+
+    fn control(&self, column: usize, row: usize) -> &Tuple {
+        &self.points[row * 4 + column]
+    }
+
+    fn combine(&self, weights_u: [f64; 4], weights_v: [f64; 4]) -> (f64, f64, f64) {
+        let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+        for (row, wv) in weights_v.iter().enumerate() {
+            for (column, wu) in weights_u.iter().enumerate() {
+                let p = self.control(column, row);
+                x += p.x * wu * wv;
+                y += p.y * wu * wv;
+                z += p.z * wu * wv;
+            }
+        }
+        (x, y, z)
+    }
+
+    pub fn point_at(&self, u: f64, v: f64) -> Tuple {
+        let (x, y, z) = self.combine(bernstein(u), bernstein(v));
+        point(x, y, z)
+    }
+
+    // the derivatives of the surface along u and along v
+    pub fn tangents_at(&self, u: f64, v: f64) -> (Tuple, Tuple) {
+        let (x, y, z) = self.combine(bernstein_derivative(u), bernstein(v));
+        let du = vector(x, y, z);
+        let (x, y, z) = self.combine(bernstein(u), bernstein_derivative(v));
+        (du, vector(x, y, z))
+    }
+
+    // the cross product of the tangents. Where a side of the patch collapses
+    // into a point, like the top of the teapot lid, one tangent vanishes and
+    // the way it starts to grow takes its place.
+    pub fn normal_at(&self, u: f64, v: f64) -> Tuple {
+        let (du, dv) = self.tangents_at(u, v);
+        let n = cross(&du, &dv);
+        if n.magnitude() > 1e-12 {
+            return n.normalize();
+        }
+        let (x, y, z) = self.combine(bernstein_derivative(u), bernstein_derivative(v));
+        let duv = vector(x, y, z);
+        let n = if du.magnitude() < dv.magnitude() {
+            // along v the patch leaves the collapsed side, u = 1 - u mirrors it
+            let sign = if v < 0.5 { 1.0 } else { -1.0 };
+            cross(&duv, &dv) * sign
+        } else {
+            let sign = if u < 0.5 { 1.0 } else { -1.0 };
+            cross(&du, &duv) * sign
+        };
+        if n.magnitude() > 1e-12 {
+            n.normalize()
+        } else {
+            vector(0.0, 0.0, 0.0)
+        }
+    }
+
+    // a patch stays inside the hull of its control points
+    pub fn bounds(&self) -> AABB {
+        let fold = |f: fn(f64, f64) -> f64, start: f64, axis: fn(&Tuple) -> f64| {
+            self.points.iter().map(axis).fold(start, f)
+        };
+        AABB {
+            min: point(
+                fold(f64::min, f64::INFINITY, |p| p.x),
+                fold(f64::min, f64::INFINITY, |p| p.y),
+                fold(f64::min, f64::INFINITY, |p| p.z),
+            ),
+            max: point(
+                fold(f64::max, f64::NEG_INFINITY, |p| p.x),
+                fold(f64::max, f64::NEG_INFINITY, |p| p.y),
+                fold(f64::max, f64::NEG_INFINITY, |p| p.z),
+            ),
+        }
+    }
+
+    // how many segments the curves through the rows (along u) or columns
+    // (along v) need
+    fn segments(&self, along_u: bool, tolerance: f64) -> usize {
+        (0..4)
+            .map(|i| {
+                let curve = [0, 1, 2, 3].map(|j| match along_u {
+                    true => self.control(j, i).clone(),
+                    false => self.control(i, j).clone(),
+                });
+                curve_segments(&curve, tolerance)
+            })
+            .max()
+            .unwrap()
+    }
+
+    // the four sides: v = 0 and v = 1 along u, u = 0 and u = 1 along v
+    fn side_segments(&self, tolerance: f64) -> [usize; 4] {
+        let side = |indices: [(usize, usize); 4]| {
+            curve_segments(&indices.map(|(c, r)| self.control(c, r).clone()), tolerance)
+        };
+        [
+            side([(0, 0), (1, 0), (2, 0), (3, 0)]),
+            side([(0, 3), (1, 3), (2, 3), (3, 3)]),
+            side([(0, 0), (0, 1), (0, 2), (0, 3)]),
+            side([(3, 0), (3, 1), (3, 2), (3, 3)]),
+        ]
+    }
+}
+
+// all patches in one mesh, no triangle is further than the tolerance from the
+// surface
+pub fn tessellate(patches: &[BezierPatch], tolerance: f64) -> Mesh {
+    let mut builder = Builder::default();
+    for patch in patches {
+        builder.patch(patch, tolerance);
+    }
+    Mesh::new(
+        Arc::new(builder.vertices),
+        Arc::new(builder.normals),
+        Arc::new(builder.uvs),
+        builder.faces,
+    )
+}
+
+#[derive(Default)]
+struct Builder {
+    vertices: Vec<Tuple>,
+    normals: Vec<Tuple>,
+    uvs: Vec<(f64, f64)>,
+    faces: Vec<Face>,
+}
+
+impl Builder {
+    fn vertex(&mut self, patch: &BezierPatch, u: f64, v: f64) -> u32 {
+        let index = self.vertices.len() as u32;
+        self.vertices.push(patch.point_at(u, v));
+        self.normals.push(patch.normal_at(u, v));
+        self.uvs.push((u, v));
+        index
+    }
+
+    fn triangle(&mut self, corners: [u32; 3]) {
+        let [p1, p2, p3] = corners.map(|i| &self.vertices[i as usize]);
+        // collapsed sides of a patch leave triangles without an area
+        if cross(&(p2 - p1), &(p3 - p1)).magnitude() == 0.0 {
+            return;
+        }
+        self.faces.push(Face {
+            vertices: corners,
+            normals: Some(corners),
+            uvs: Some(corners),
+        });
+    }
+
+    // an inner grid of columns by rows cells, of which only the points away
+    // from the sides are used, and the sides split on their own
+    fn patch(&mut self, patch: &BezierPatch, tolerance: f64) {
+        let columns = patch.segments(true, tolerance).max(2);
+        let rows = patch.segments(false, tolerance).max(2);
+        let [bottom, top, left, right] = patch.side_segments(tolerance);
+
+        let mut inner = Vec::with_capacity((columns - 1) * (rows - 1));
+        for row in 1..rows {
+            for column in 1..columns {
+                let u = column as f64 / columns as f64;
+                let v = row as f64 / rows as f64;
+                inner.push(self.vertex(patch, u, v));
+            }
+        }
+        let at = |column: usize, row: usize| inner[(row - 1) * (columns - 1) + column - 1];
+        for row in 1..rows - 1 {
+            for column in 1..columns - 1 {
+                let (a, b) = (at(column, row), at(column + 1, row));
+                let (c, d) = (at(column, row + 1), at(column + 1, row + 1));
+                self.triangle([a, b, d]);
+                self.triangle([a, d, c]);
+            }
+        }
+
+        // every side is stitched to the ring of inner points next to it
+        let side = |count: usize, f: &dyn Fn(f64) -> (f64, f64)| {
+            (0..=count)
+                .map(|i| {
+                    let s = i as f64 / count as f64;
+                    (s, f(s))
+                })
+                .collect::<Vec<_>>()
+        };
+        let outer = [
+            side(bottom, &|s| (s, 0.0)),
+            side(top, &|s| (s, 1.0)),
+            side(left, &|s| (0.0, s)),
+            side(right, &|s| (1.0, s)),
+        ];
+        let ring = [
+            (1..columns).map(|c| at(c, 1)).collect::<Vec<_>>(),
+            (1..columns).map(|c| at(c, rows - 1)).collect(),
+            (1..rows).map(|r| at(1, r)).collect(),
+            (1..rows).map(|r| at(columns - 1, r)).collect(),
+        ];
+        let ring_params = [columns, columns, rows, rows];
+        for ((outer, ring), cells) in outer.into_iter().zip(ring).zip(ring_params) {
+            let outer: Vec<(f64, u32)> = outer
+                .into_iter()
+                .map(|(s, (u, v))| (s, self.vertex(patch, u, v)))
+                .collect();
+            let ring: Vec<(f64, u32)> = ring
+                .into_iter()
+                .enumerate()
+                .map(|(i, index)| ((i + 1) as f64 / cells as f64, index))
+                .collect();
+            self.stitch(&outer, &ring);
+        }
+    }
+
+    // triangles between two rows of points ordered by their parameter, always
+    // advancing on the row whose next point comes first
+    fn stitch(&mut self, a: &[(f64, u32)], b: &[(f64, u32)]) {
+        let (mut i, mut j) = (0, 0);
+        while i + 1 < a.len() || j + 1 < b.len() {
+            let advance_a = match (a.get(i + 1), b.get(j + 1)) {
+                (Some(next_a), Some(next_b)) => next_a.0 <= next_b.0,
+                (Some(_), None) => true,
+                _ => false,
+            };
+            if advance_a {
+                self.triangle([a[i].1, a[i + 1].1, b[j].1]);
+                i += 1;
+            } else {
+                self.triangle([a[i].1, b[j + 1].1, b[j].1]);
+                j += 1;
+            }
+        }
+    }
+}
+
+// a cubic through these control points drawn with n straight segments is at
+// most 3/4 of the largest second difference over n² away from the curve,
+// half the tolerance goes to each direction of the patch
+fn curve_segments(curve: &[Tuple; 4], tolerance: f64) -> usize {
+    let second = |i: usize| {
+        let p = &curve[i];
+        let q = &curve[i + 1];
+        let r = &curve[i + 2];
+        vector(
+            p.x - 2.0 * q.x + r.x,
+            p.y - 2.0 * q.y + r.y,
+            p.z - 2.0 * q.z + r.z,
+        )
+        .magnitude()
+    };
+    let m = second(0).max(second(1));
+    if tolerance <= 0.0 {
+        return MAX_SEGMENTS;
+    }
+    ((1.5 * m / tolerance).sqrt().ceil() as usize).clamp(1, MAX_SEGMENTS)
+}
+
+fn bernstein(t: f64) -> [f64; 4] {
+    let s = 1.0 - t;
+    [s * s * s, 3.0 * t * s * s, 3.0 * t * t * s, t * t * t]
+}
+
+fn bernstein_derivative(t: f64) -> [f64; 4] {
+    let s = 1.0 - t;
+    [
+        -3.0 * s * s,
+        3.0 * s * s - 6.0 * t * s,
+        6.0 * t * s - 3.0 * t * t,
+        3.0 * t * t,
+    ]
+}
@@ -1,16 +1,19 @@
 use crate::{
+    canvas::Canvas,
     matrices::{identity_matrix, Matrix4x4},
     objects::Object,
-    tuples::{point, Tuple},
+    tuples::{point, vector, Tuple},
 };
 use noise::{NoiseFn, Perlin, Seedable};
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Pattern {
     transform: Matrix4x4,
     transform_inverse: Matrix4x4,
     pub renderer: Renderer,
+    // None keeps the uv coordinates of the shape or the enclosing pattern
+    pub mapping: Option<UvMapping>,
 }
 
 #[derive(Debug, Clone)]
@@ -22,10 +25,53 @@ pub enum Renderer {
     RadialGradient(Box<Pattern>, Box<Pattern>),
     Blended(Box<Pattern>, Box<Pattern>),
     Perturbed(f64, Box<Perlin>, Box<Perlin>, Box<Perlin>, Box<Pattern>),
+    Image(Arc<Texture>),
+    UvCheckers(f64, f64, Box<Pattern>, Box<Pattern>),
+    // main, upper left, upper right, bottom left and bottom right
+    AlignCheck(Box<[Pattern; 5]>),
+    // left, front, right, back, up and down
+    CubeMap(Box<[Pattern; 6]>),
     Solid(Tuple),
     Test(),
 }
 
+#[derive(Debug)]
+pub struct Texture {
+    pub canvas: Canvas,
+    pub filter: Filter,
+    pub wrap: Wrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    Spherical,
+    Planar,
+    Cylindrical,
+    Cube,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CubeFace {
+    Left,
+    Front,
+    Right,
+    Back,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    Clamp,
+}
+
 impl PartialEq for Renderer {
     fn eq(&self, other: &Renderer) -> bool {
         match (self, other) {
@@ -47,6 +93,12 @@ impl PartialEq for Renderer {
                     && z1.seed() == z2.seed()
                     && pattern1 == pattern2
             }
+            (Renderer::Image(a), Renderer::Image(b)) => Arc::ptr_eq(a, b),
+            (Renderer::UvCheckers(w1, h1, a1, b1), Renderer::UvCheckers(w2, h2, a2, b2)) => {
+                w1 == w2 && h1 == h2 && a1 == a2 && b1 == b2
+            }
+            (Renderer::AlignCheck(a), Renderer::AlignCheck(b)) => a == b,
+            (Renderer::CubeMap(a), Renderer::CubeMap(b)) => a == b,
             (Renderer::Solid(a), Renderer::Solid(b)) => a == b,
             (Renderer::Test(), Renderer::Test()) => true,
             (_, _) => false,
@@ -61,11 +113,25 @@ impl Pattern {
             transform,
             transform_inverse,
             renderer,
+            mapping: None,
         }
     }
 
     pub fn color_at(&self, p: &Tuple) -> Tuple {
-        self.renderer.color_at(p)
+        self.color_at_uv(p, &(0.0, 0.0))
+    }
+
+    // uv are the texture coordinates of the shape at the point, replaced by
+    // the pattern's own mapping of the point when it has one
+    pub fn color_at_uv(&self, p: &Tuple, uv: &(f64, f64)) -> Tuple {
+        match self.mapping {
+            Some(mapping) => self.renderer.color_at(p, &mapping.uv_at(p)),
+            None => self.renderer.color_at(p, uv),
+        }
+    }
+
+    pub fn set_mapping(&mut self, mapping: UvMapping) {
+        self.mapping = Some(mapping);
     }
 
     pub fn set_transform(&mut self, transform: Matrix4x4) {
@@ -79,45 +145,46 @@ impl Pattern {
 }
 
 impl Renderer {
-    fn color_at(&self, p: &Tuple) -> Tuple {
+    fn color_at(&self, p: &Tuple, uv: &(f64, f64)) -> Tuple {
         match self {
             Renderer::Stripes(a, b) => {
                 if p.x.floor() % 2.0 == 0.0 {
-                    return a.color_at(&(p * &a.transform_inverse));
+                    return a.color_at_uv(&(p * &a.transform_inverse), uv);
                 }
-                b.color_at(&(p * &b.transform_inverse))
+                b.color_at_uv(&(p * &b.transform_inverse), uv)
             }
             Renderer::Gradient(a, b) => {
-                let a = &a.color_at(&(p * &a.transform_inverse));
-                let b = &b.color_at(&(p * &b.transform_inverse));
+                let a = &a.color_at_uv(&(p * &a.transform_inverse), uv);
+                let b = &b.color_at_uv(&(p * &b.transform_inverse), uv);
                 let distance = b - a;
                 let fraction = p.x - p.x.floor();
                 a + distance * fraction
             }
             Renderer::Ring(a, b) => {
                 if (p.x * p.x + p.z * p.z).sqrt().floor() % 2.0 == 0.0 {
-                    return a.color_at(&(p * &a.transform_inverse));
+                    return a.color_at_uv(&(p * &a.transform_inverse), uv);
                 }
-                b.color_at(&(p * &b.transform_inverse))
+                b.color_at_uv(&(p * &b.transform_inverse), uv)
             }
             Renderer::Checkers(a, b) => {
                 let px = (p.x + (1024.0 * f64::EPSILON)).floor();
                 let py = (p.y + (1024.0 * f64::EPSILON)).floor();
                 let pz = (p.z + (1024.0 * f64::EPSILON)).floor();
                 if ((px + py + pz) % 2.0).abs() < f64::EPSILON {
-                    return a.color_at(&(p * &a.transform_inverse));
+                    return a.color_at_uv(&(p * &a.transform_inverse), uv);
                 }
-                b.color_at(&(p * &b.transform_inverse))
+                b.color_at_uv(&(p * &b.transform_inverse), uv)
             }
             Renderer::RadialGradient(a, b) => {
-                let a = &a.color_at(&(p * &a.transform_inverse));
-                let b = &b.color_at(&(p * &b.transform_inverse));
+                let a = &a.color_at_uv(&(p * &a.transform_inverse), uv);
+                let b = &b.color_at_uv(&(p * &b.transform_inverse), uv);
                 let distance = b - a;
                 let fraction = (p - point(0.0, 0.0, 0.0)).magnitude() % 1.0;
                 a + distance * fraction
             }
             Renderer::Blended(a, b) => {
-                (a.color_at(&(p * &a.transform_inverse)) + b.color_at(&(p * &b.transform_inverse)))
+                (a.color_at_uv(&(p * &a.transform_inverse), uv)
+                    + b.color_at_uv(&(p * &b.transform_inverse), uv))
                     / 2.0
             }
             Renderer::Perturbed(scale, x, y, z, pattern) => {
@@ -125,7 +192,36 @@ impl Renderer {
                 let x = p.x + x.get(point_3d) * scale;
                 let y = p.y + y.get(point_3d) * scale;
                 let z = p.z + z.get(point_3d) * scale;
-                pattern.color_at(&(point(x, y, z) * &pattern.transform_inverse))
+                pattern.color_at_uv(&(point(x, y, z) * &pattern.transform_inverse), uv)
+            }
+            Renderer::Image(texture) => texture.sample(uv),
+            Renderer::UvCheckers(width, height, a, b) => {
+                let u = (uv.0 * width).floor();
+                let v = (uv.1 * height).floor();
+                if (u + v).rem_euclid(2.0) == 0.0 {
+                    return a.color_at_uv(&(p * &a.transform_inverse), uv);
+                }
+                b.color_at_uv(&(p * &b.transform_inverse), uv)
+            }
+            Renderer::AlignCheck(patterns) => {
+                let [main, upper_left, upper_right, bottom_left, bottom_right] = &**patterns;
+                let (u, v) = *uv;
+                let pattern = if v > 0.8 && u < 0.2 {
+                    upper_left
+                } else if v > 0.8 && u > 0.8 {
+                    upper_right
+                } else if v < 0.2 && u < 0.2 {
+                    bottom_left
+                } else if v < 0.2 && u > 0.8 {
+                    bottom_right
+                } else {
+                    main
+                };
+                pattern.color_at_uv(&(p * &pattern.transform_inverse), uv)
+            }
+            Renderer::CubeMap(faces) => {
+                let face = &faces[cube_face(p) as usize];
+                face.color_at_uv(&(p * &face.transform_inverse), &cube_map(p))
             }
             Renderer::Solid(a) => a.clone(),
             Renderer::Test() => p.clone(),
@@ -138,6 +234,7 @@ pub fn solid_pattern(color: Tuple) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Solid(color),
+        mapping: None,
     }
 }
 
@@ -146,6 +243,7 @@ pub fn stripe_pattern(a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Stripes(a, b),
+        mapping: None,
     }
 }
 
@@ -154,6 +252,7 @@ pub fn gradient_pattern(a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Gradient(a, b),
+        mapping: None,
     }
 }
 
@@ -162,6 +261,7 @@ pub fn ring_pattern(a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Ring(a, b),
+        mapping: None,
     }
 }
 
@@ -170,6 +270,7 @@ pub fn checkers_pattern(a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Checkers(a, b),
+        mapping: None,
     }
 }
 
@@ -178,19 +279,166 @@ pub fn radial_gradient_pattern(a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::RadialGradient(a, b),
+        mapping: None,
+    }
+}
+
+pub fn image_pattern(canvas: Canvas, filter: Filter, wrap: Wrap) -> Pattern {
+    let texture = Texture {
+        canvas,
+        filter,
+        wrap,
+    };
+    Pattern {
+        transform: identity_matrix(),
+        transform_inverse: identity_matrix().inverse().unwrap(),
+        renderer: Renderer::Image(Arc::new(texture)),
+        mapping: None,
     }
 }
 
+pub fn uv_checkers_pattern(width: f64, height: f64, a: Box<Pattern>, b: Box<Pattern>) -> Pattern {
+    Pattern::new(identity_matrix(), Renderer::UvCheckers(width, height, a, b))
+}
+
+pub fn align_check_pattern(
+    main: Pattern,
+    upper_left: Pattern,
+    upper_right: Pattern,
+    bottom_left: Pattern,
+    bottom_right: Pattern,
+) -> Pattern {
+    let patterns = [main, upper_left, upper_right, bottom_left, bottom_right];
+    Pattern::new(identity_matrix(), Renderer::AlignCheck(Box::new(patterns)))
+}
+
+// faces are given in the order left, front, right, back, up and down
+pub fn cube_map_pattern(faces: [Pattern; 6]) -> Pattern {
+    Pattern::new(identity_matrix(), Renderer::CubeMap(Box::new(faces)))
+}
+
 pub fn test_pattern() -> Pattern {
     Pattern {
         transform: identity_matrix(),
         transform_inverse: identity_matrix().inverse().unwrap(),
         renderer: Renderer::Test(),
+        mapping: None,
     }
 }
 
-pub fn pattern_at_shape(pattern: &Pattern, object: &Arc<Object>, world_point: &Tuple) -> Tuple {
+// hit_uv are the u and v of the intersection, the barycentric coordinates on triangles
+pub fn pattern_at_shape(
+    pattern: &Pattern,
+    object: &Arc<Object>,
+    world_point: &Tuple,
+    hit_uv: &(f64, f64),
+) -> Tuple {
     let object_point = object.transform_inverse() * world_point;
+    let uv = object.shape.uv_at(&object_point, hit_uv);
     let pattern_point = &pattern.transform_inverse * object_point;
-    pattern.color_at(&pattern_point)
+    pattern.color_at_uv(&pattern_point, &uv)
+}
+
+impl Texture {
+    // v runs from the bottom to the top of the image
+    pub fn sample(&self, uv: &(f64, f64)) -> Tuple {
+        let (width, height) = (self.canvas.width as f64, self.canvas.height as f64);
+        let x = uv.0 * width;
+        let y = (1.0 - uv.1) * height;
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor(), y.floor()),
+            Filter::Bilinear => {
+                // texel centers sit at half coordinates
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let top = self.texel(x0, y0) * (1.0 - tx) + self.texel(x0 + 1.0, y0) * tx;
+                let bottom =
+                    self.texel(x0, y0 + 1.0) * (1.0 - tx) + self.texel(x0 + 1.0, y0 + 1.0) * tx;
+                top * (1.0 - ty) + bottom * ty
+            }
+        }
+    }
+
+    fn texel(&self, x: f64, y: f64) -> Tuple {
+        let (width, height) = (self.canvas.width as f64, self.canvas.height as f64);
+        let (x, y) = match self.wrap {
+            // rem_euclid rounds tiny negative values up to the size itself
+            Wrap::Repeat => (
+                x.rem_euclid(width).min(width - 1.0),
+                y.rem_euclid(height).min(height - 1.0),
+            ),
+            Wrap::Clamp => (x.clamp(0.0, width - 1.0), y.clamp(0.0, height - 1.0)),
+        };
+        self.canvas.at(x as usize, y as usize).clone()
+    }
+}
+
+pub fn spherical_map(p: &Tuple) -> (f64, f64) {
+    let theta = p.x.atan2(p.z);
+    let radius = vector(p.x, p.y, p.z).magnitude();
+    let phi = (p.y / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+    (1.0 - (raw_u + 0.5), 1.0 - phi / PI)
+}
+
+pub fn planar_map(p: &Tuple) -> (f64, f64) {
+    (p.x.rem_euclid(1.0), p.z.rem_euclid(1.0))
+}
+
+pub fn cylindrical_map(p: &Tuple) -> (f64, f64) {
+    let theta = p.x.atan2(p.z);
+    let raw_u = theta / (2.0 * PI);
+    (1.0 - (raw_u + 0.5), p.y.rem_euclid(1.0))
+}
+
+// u runs around the y axis like on a cylinder, v around the tube of a torus
+// with the given major radius
+pub fn toroidal_map(p: &Tuple, major: f64) -> (f64, f64) {
+    let (u, _) = cylindrical_map(p);
+    let dist = (p.x * p.x + p.z * p.z).sqrt();
+    let phi = p.y.atan2(dist - major);
+    (u, phi / (2.0 * PI) + 0.5)
+}
+
+impl UvMapping {
+    pub fn uv_at(&self, p: &Tuple) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(p),
+            UvMapping::Planar => planar_map(p),
+            UvMapping::Cylindrical => cylindrical_map(p),
+            UvMapping::Cube => cube_map(p),
+        }
+    }
+}
+
+// the face of the unit cube the point lies on, decided by its largest coordinate
+pub fn cube_face(p: &Tuple) -> CubeFace {
+    let coordinate = p.x.abs().max(p.y.abs()).max(p.z.abs());
+    if coordinate == p.x {
+        CubeFace::Right
+    } else if coordinate == -p.x {
+        CubeFace::Left
+    } else if coordinate == p.y {
+        CubeFace::Up
+    } else if coordinate == -p.y {
+        CubeFace::Down
+    } else if coordinate == p.z {
+        CubeFace::Front
+    } else {
+        CubeFace::Back
+    }
+}
+
+// every face of the cube shows the whole texture
+pub fn cube_map(p: &Tuple) -> (f64, f64) {
+    let (u, v) = match cube_face(p) {
+        CubeFace::Right => (1.0 - p.z, p.y + 1.0),
+        CubeFace::Left => (p.z + 1.0, p.y + 1.0),
+        CubeFace::Up => (p.x + 1.0, 1.0 - p.z),
+        CubeFace::Down => (p.x + 1.0, p.z + 1.0),
+        CubeFace::Front => (p.x + 1.0, p.y + 1.0),
+        CubeFace::Back => (1.0 - p.x, p.y + 1.0),
+    };
+    (u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
 }
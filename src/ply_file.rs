@@ -0,0 +1,353 @@
+// Stanford polygon files: a text header declares elements and their
+// properties, followed by the elements either as text or as binary little
+// endian values. Vertices may carry normals and colors, a face is a list of
+// vertex indices.
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind, Read, Result},
+    path::Path,
+};
+
+use crate::{
+    groups::Group,
+    materials::Material,
+    matrices::identity_matrix,
+    objects::{Object, Shape},
+    triangles::{SmoothTriangle, Triangle},
+    tuples::{color, point, vector, Tuple},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Type {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+#[derive(Debug)]
+enum Property {
+    Scalar(String, Type),
+    List(String, Type, Type),
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+#[derive(Debug)]
+struct Vertex {
+    position: Tuple,
+    normal: Option<Tuple>,
+    color: Option<Tuple>,
+}
+
+pub fn load_ply_file(path: &Path) -> Result<Group> {
+    parse_ply_file(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn parse_ply_file(reader: &mut dyn Read) -> Result<Group> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let (elements, binary, position) = header(&data)?;
+    let mut body = Body {
+        data: &data,
+        position,
+        binary,
+    };
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+    for element in &elements {
+        for _ in 0..element.count {
+            match element.name.as_str() {
+                "vertex" => vertices.push(vertex(element, &mut body)?),
+                "face" => faces.push(face(element, &mut body)?),
+                // edges, materials and other elements are skipped
+                _ => {
+                    for property in &element.properties {
+                        body.property(property)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut group = Group::default();
+    for (number, indices) in faces.iter().enumerate() {
+        if indices.len() < 3 {
+            return Err(invalid_data(format!(
+                "face {} has less than 3 vertices",
+                number + 1
+            )));
+        }
+        if let Some(index) = indices.iter().find(|&&i| i >= vertices.len()) {
+            return Err(invalid_data(format!(
+                "vertex index {} is out of range",
+                index
+            )));
+        }
+        // fan triangulation around the first vertex
+        for i in 1..(indices.len() - 1) {
+            let corners = [
+                &vertices[indices[0]],
+                &vertices[indices[i]],
+                &vertices[indices[i + 1]],
+            ];
+            group.add_object(triangle(corners));
+        }
+    }
+    Ok(group)
+}
+
+// a triangle is smooth when all corners have normals, vertex colors are
+// averaged since materials have a single color
+fn triangle(corners: [&Vertex; 3]) -> Object {
+    let [a, b, c] = corners;
+    let (p1, p2, p3) = (a.position.clone(), b.position.clone(), c.position.clone());
+    let shape = match (&a.normal, &b.normal, &c.normal) {
+        (Some(n1), Some(n2), Some(n3)) => Shape::SmoothTriangle(SmoothTriangle::new(
+            p1,
+            p2,
+            p3,
+            n1.clone(),
+            n2.clone(),
+            n3.clone(),
+        )),
+        _ => Shape::Triangle(Triangle::new(p1, p2, p3)),
+    };
+    let mut material = Material::default();
+    if let (Some(c1), Some(c2), Some(c3)) = (&a.color, &b.color, &c.color) {
+        material.color = (c1 + c2 + c3) / 3.0;
+    }
+    Object::new(shape, identity_matrix(), material)
+}
+
+fn vertex(element: &Element, body: &mut Body) -> Result<Vertex> {
+    let (mut position, mut normal, mut rgb) = ([0.0; 3], [None; 3], [None; 3]);
+    for property in &element.properties {
+        let value = body.property(property)?;
+        if let (Property::Scalar(name, kind), Some(value)) = (property, value) {
+            match name.as_str() {
+                "x" => position[0] = value,
+                "y" => position[1] = value,
+                "z" => position[2] = value,
+                "nx" => normal[0] = Some(value),
+                "ny" => normal[1] = Some(value),
+                "nz" => normal[2] = Some(value),
+                // integer channels span the whole range of their type
+                "red" | "green" | "blue" => {
+                    let channel = match name.as_str() {
+                        "red" => 0,
+                        "green" => 1,
+                        _ => 2,
+                    };
+                    rgb[channel] = Some(value / kind.max_channel());
+                }
+                _ => {}
+            }
+        }
+    }
+    let normal = match normal {
+        [Some(x), Some(y), Some(z)] => Some(vector(x, y, z)),
+        _ => None,
+    };
+    let color = match rgb {
+        [Some(r), Some(g), Some(b)] => Some(color(r, g, b)),
+        _ => None,
+    };
+    Ok(Vertex {
+        position: point(position[0], position[1], position[2]),
+        normal,
+        color,
+    })
+}
+
+fn face(element: &Element, body: &mut Body) -> Result<Vec<usize>> {
+    let mut indices = None;
+    for property in &element.properties {
+        match property {
+            Property::List(name, count, item)
+                if name == "vertex_indices" || name == "vertex_index" =>
+            {
+                let count = body.value(*count)? as usize;
+                let list = (0..count)
+                    .map(|_| body.value(*item))
+                    .collect::<Result<Vec<f64>>>()?;
+                if list.iter().any(|&i| i < 0.0) {
+                    return Err(invalid_data("negative vertex index"));
+                }
+                indices = Some(list.iter().map(|&i| i as usize).collect());
+            }
+            _ => {
+                body.property(property)?;
+            }
+        }
+    }
+    indices.ok_or_else(|| invalid_data("face without vertex indices"))
+}
+
+// the elements, whether the body is binary and where the body starts
+fn header(data: &[u8]) -> Result<(Vec<Element>, bool, usize)> {
+    let mut position = 0;
+    let mut next_line = || {
+        let rest = data.get(position..).filter(|r| !r.is_empty())?;
+        let length = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        position += length + 1;
+        Some(String::from_utf8_lossy(&rest[..length]).trim().to_string())
+    };
+
+    if next_line().as_deref() != Some("ply") {
+        return Err(invalid_data("not a ply file"));
+    }
+    let mut binary = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        let line = next_line().ok_or_else(|| invalid_data("ply header is not terminated"))?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            ["end_header"] => break,
+            ["format", "ascii", _] => binary = Some(false),
+            ["format", "binary_little_endian", _] => binary = Some(true),
+            ["format", format, _] => {
+                return Err(invalid_data(format!("unsupported format '{}'", format)))
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid_data(format!("invalid element count '{}'", count)))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::List(name.to_string(), kind(count)?, kind(item)?);
+                last_element(&mut elements)?.properties.push(property);
+            }
+            ["property", item, name] => {
+                let property = Property::Scalar(name.to_string(), kind(item)?);
+                last_element(&mut elements)?.properties.push(property);
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(invalid_data(format!("invalid header line '{}'", line))),
+        }
+    }
+    let binary = binary.ok_or_else(|| invalid_data("ply format is missing"))?;
+    Ok((elements, binary, position))
+}
+
+fn last_element(elements: &mut [Element]) -> Result<&mut Element> {
+    elements
+        .last_mut()
+        .ok_or_else(|| invalid_data("property before the first element"))
+}
+
+fn kind(name: &str) -> Result<Type> {
+    match name {
+        "char" | "int8" => Ok(Type::Int8),
+        "uchar" | "uint8" => Ok(Type::UInt8),
+        "short" | "int16" => Ok(Type::Int16),
+        "ushort" | "uint16" => Ok(Type::UInt16),
+        "int" | "int32" => Ok(Type::Int32),
+        "uint" | "uint32" => Ok(Type::UInt32),
+        "float" | "float32" => Ok(Type::Float32),
+        "double" | "float64" => Ok(Type::Float64),
+        _ => Err(invalid_data(format!("unknown property type '{}'", name))),
+    }
+}
+
+impl Type {
+    fn size(&self) -> usize {
+        match self {
+            Type::Int8 | Type::UInt8 => 1,
+            Type::Int16 | Type::UInt16 => 2,
+            Type::Int32 | Type::UInt32 | Type::Float32 => 4,
+            Type::Float64 => 8,
+        }
+    }
+
+    fn max_channel(&self) -> f64 {
+        match self {
+            Type::Int8 => i8::MAX as f64,
+            Type::UInt8 => u8::MAX as f64,
+            Type::Int16 => i16::MAX as f64,
+            Type::UInt16 => u16::MAX as f64,
+            Type::Int32 => i32::MAX as f64,
+            Type::UInt32 => u32::MAX as f64,
+            Type::Float32 | Type::Float64 => 1.0,
+        }
+    }
+}
+
+struct Body<'a> {
+    data: &'a [u8],
+    position: usize,
+    binary: bool,
+}
+
+impl Body<'_> {
+    // the value of a scalar property, lists are read and dropped
+    fn property(&mut self, property: &Property) -> Result<Option<f64>> {
+        match property {
+            Property::Scalar(_, kind) => Ok(Some(self.value(*kind)?)),
+            Property::List(_, count, item) => {
+                let count = self.value(*count)? as usize;
+                for _ in 0..count {
+                    self.value(*item)?;
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn value(&mut self, kind: Type) -> Result<f64> {
+        if !self.binary {
+            return self.token();
+        }
+        let size = kind.size();
+        let bytes = self
+            .data
+            .get(self.position..self.position + size)
+            .ok_or_else(|| invalid_data("ply file is truncated"))?;
+        self.position += size;
+        let value = match kind {
+            Type::Int8 => bytes[0] as i8 as f64,
+            Type::UInt8 => bytes[0] as f64,
+            Type::Int16 => i16::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Type::UInt16 => u16::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Type::Int32 => i32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Type::UInt32 => u32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Type::Float32 => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Type::Float64 => f64::from_le_bytes(bytes.try_into().unwrap()),
+        };
+        Ok(value)
+    }
+
+    fn token(&mut self) -> Result<f64> {
+        let rest = &self.data[self.position.min(self.data.len())..];
+        let start = rest
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .ok_or_else(|| invalid_data("ply file is truncated"))?;
+        let length = rest[start..]
+            .iter()
+            .position(|b| b.is_ascii_whitespace())
+            .unwrap_or(rest.len() - start);
+        self.position += start + length;
+        let token = String::from_utf8_lossy(&rest[start..start + length]);
+        token
+            .parse()
+            .map_err(|_| invalid_data(format!("invalid number '{}'", token)))
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
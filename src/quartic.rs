@@ -0,0 +1,198 @@
+// Real roots of polynomials up to degree four, used by shapes whose surface
+// is a quartic in the ray parameter. The closed forms follow Ferrari and
+// Cardano on the depressed polynomials, every root is then polished with a
+// few Newton steps on the original polynomial since the closed forms lose
+// digits when roots are close together.
+use std::{f64::consts::PI, ops::Deref};
+
+const EPSILON: f64 = 1e-9;
+const NEWTON_STEPS: usize = 4;
+
+// at most four roots in ascending order, kept inline so solving for them
+// needs no allocation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Roots {
+    len: usize,
+    values: [f64; 4],
+}
+
+impl Roots {
+    fn new(roots: &[f64]) -> Roots {
+        let mut values = [0.0; 4];
+        values[..roots.len()].copy_from_slice(roots);
+        Roots {
+            len: roots.len(),
+            values,
+        }
+    }
+
+    fn push(&mut self, root: f64) {
+        self.values[self.len] = root;
+        self.len += 1;
+    }
+
+    // polished on the original polynomial and sorted
+    fn polished(mut self, coefficients: &[f64], shift: f64) -> Roots {
+        for root in self.values[..self.len].iter_mut() {
+            *root = polish(coefficients, *root - shift);
+        }
+        self.sorted()
+    }
+
+    fn sorted(mut self) -> Roots {
+        self.values[..self.len].sort_by(|a, b| a.partial_cmp(b).unwrap());
+        self
+    }
+}
+
+impl Deref for Roots {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.values[..self.len]
+    }
+}
+
+impl IntoIterator for Roots {
+    type Item = f64;
+    type IntoIter = std::iter::Take<std::array::IntoIter<f64, 4>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter().take(self.len)
+    }
+}
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+// a x² + b x + c = 0, avoids the cancellation of the textbook formula
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Roots {
+    if is_zero(a) {
+        if is_zero(b) {
+            return Roots::new(&[]);
+        }
+        return Roots::new(&[-c / b]);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Roots::new(&[]);
+    }
+    if discriminant == 0.0 {
+        return Roots::new(&[-b / (2.0 * a)]);
+    }
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        // b and c are zero
+        return Roots::new(&[0.0]);
+    }
+    Roots::new(&[q / a, c / q]).sorted()
+}
+
+// a x³ + b x² + c x + d = 0
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Roots {
+    if is_zero(a) {
+        return solve_quadratic(b, c, d);
+    }
+    // x = y - b/3 gives y³ + p y + q = 0
+    let (b, c, d) = (b / a, c / a, d / a);
+    let sq_b = b * b;
+    let p = (-sq_b / 3.0 + c) / 3.0;
+    let q = (2.0 / 27.0 * b * sq_b - b * c / 3.0 + d) / 2.0;
+    let cb_p = p * p * p;
+    let discriminant = q * q + cb_p;
+
+    let roots = if is_zero(discriminant) {
+        if is_zero(q) {
+            Roots::new(&[0.0])
+        } else {
+            let u = (-q).cbrt();
+            Roots::new(&[2.0 * u, -u])
+        }
+    } else if discriminant < 0.0 {
+        // three real roots
+        let phi = (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        Roots::new(&[
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ])
+    } else {
+        let sqrt_d = discriminant.sqrt();
+        Roots::new(&[(sqrt_d - q).cbrt() - (sqrt_d + q).cbrt()])
+    };
+    roots.polished(&[1.0, b, c, d], b / 3.0)
+}
+
+// a x⁴ + b x³ + c x² + d x + e = 0
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Roots {
+    if is_zero(a) {
+        return solve_cubic(b, c, d, e);
+    }
+    // x = y - b/4 gives y⁴ + p y² + q y + r = 0
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let sq_b = b * b;
+    let p = -3.0 / 8.0 * sq_b + c;
+    let q = sq_b * b / 8.0 - b * c / 2.0 + d;
+    let r = -3.0 / 256.0 * sq_b * sq_b + sq_b * c / 16.0 - b * d / 4.0 + e;
+
+    let roots = if is_zero(r) {
+        // y (y³ + p y + q) = 0
+        let mut roots = solve_cubic(1.0, 0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        // any real root of the resolvent cubic splits the quartic into two
+        // quadratics
+        let resolvent = solve_cubic(1.0, -p / 2.0, -r, r * p / 2.0 - q * q / 8.0);
+        let z = match resolvent.last() {
+            Some(&z) => z,
+            None => return Roots::new(&[]),
+        };
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = match u {
+            u if is_zero(u) => 0.0,
+            u if u > 0.0 => u.sqrt(),
+            _ => return Roots::new(&[]),
+        };
+        let v = match v {
+            v if is_zero(v) => 0.0,
+            v if v > 0.0 => v.sqrt(),
+            _ => return Roots::new(&[]),
+        };
+        let v = if q < 0.0 { -v } else { v };
+        let mut roots = solve_quadratic(1.0, v, z - u);
+        for root in solve_quadratic(1.0, -v, z + u) {
+            roots.push(root);
+        }
+        roots
+    };
+    roots.polished(&[1.0, b, c, d, e], b / 4.0)
+}
+
+// Newton steps on the polynomial with the coefficients from the highest
+// degree down, a step that does not improve the residual is dropped
+fn polish(coefficients: &[f64], root: f64) -> f64 {
+    let evaluate = |x: f64| {
+        coefficients
+            .iter()
+            .fold((0.0, 0.0), |(value, derivative), c| {
+                (value * x + c, derivative * x + value)
+            })
+    };
+    let mut root = root;
+    for _ in 0..NEWTON_STEPS {
+        let (value, derivative) = evaluate(root);
+        if value == 0.0 || derivative == 0.0 {
+            break;
+        }
+        let next = root - value / derivative;
+        if evaluate(next).0.abs() >= value.abs() {
+            break;
+        }
+        root = next;
+    }
+    root
+}
@@ -4,21 +4,39 @@ use crate::{matrices::Matrix4x4, tuples::Tuple};
 pub struct Ray {
     pub origin: Tuple,
     pub direction: Tuple,
+    // the part of the ray a closest hit is searched in, a new ray is the
+    // whole line since the refractive indices depend on what lies behind it
+    pub t_min: f64,
+    pub t_max: f64,
 }
 
 impl Ray {
     pub fn new(origin: Tuple, direction: Tuple) -> Ray {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            t_min: f64::NEG_INFINITY,
+            t_max: f64::INFINITY,
+        }
+    }
+
+    pub fn between(mut self, t_min: f64, t_max: f64) -> Ray {
+        self.t_min = t_min;
+        self.t_max = t_max;
+        self
     }
 
     pub fn position(&self, t: f64) -> Tuple {
         &self.origin + &self.direction * t
     }
 
+    // the direction is not normalized, so t stays the same along the ray
     pub fn transform(&self, transformation: &Matrix4x4) -> Self {
         Ray {
             origin: &self.origin * transformation,
             direction: &self.direction * transformation,
+            t_min: self.t_min,
+            t_max: self.t_max,
         }
     }
 }
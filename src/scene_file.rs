@@ -0,0 +1,1051 @@
+use crate::{
+    bezier_file::load_bezier_file,
+    camera::Camera,
+    canvas::Canvas,
+    csg::CSG,
+    groups::{Group, GroupMember},
+    heightfields::Heightfield,
+    lathes::Lathe,
+    lights::Pointlight,
+    materials::Material,
+    matrices::{identity_matrix, Matrix4x4},
+    obj_file::Parser,
+    objects::{Object, Shape},
+    patches::tessellate,
+    patterns::{
+        align_check_pattern, checkers_pattern, cube_map_pattern, gradient_pattern, image_pattern,
+        radial_gradient_pattern, ring_pattern, solid_pattern, stripe_pattern, uv_checkers_pattern,
+        Filter, Pattern, Renderer, UvMapping, Wrap,
+    },
+    ply_file::load_ply_file,
+    sdf::Sdf,
+    stl_file::load_stl_file,
+    transformations::{
+        rotation_x, rotation_y, rotation_z, scaling, shearing, translation, view_transform,
+    },
+    tuples::{color, point, vector, Tuple},
+    world::World,
+};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+mod yaml;
+
+use yaml::{Node, Value};
+
+// Scene files follow the YAML dialect of The Ray Tracer Challenge: a list of
+// `add` (camera, light, shapes), `define` and `extend` items.
+#[derive(Debug)]
+pub struct Scene {
+    pub world: World,
+    pub camera: Camera,
+    // problems in files the scene refers to that did not stop the loader
+    pub warnings: Vec<SceneError>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneError {
+    // 0 when the error is not tied to a line, e.g. a missing camera
+    pub line: usize,
+    pub message: String,
+}
+
+impl SceneError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        SceneError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}", self.message);
+        }
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    pub fn load_scene_file(path: &Path) -> Result<Scene, SceneError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            SceneError::new(0, format!("could not read {}: {}", path.display(), err))
+        })?;
+        let directory = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse_scene_file(&content, directory)
+    }
+
+    // relative paths, e.g. of obj files, are resolved against `directory`
+    pub fn parse_scene_file(content: &str, directory: &Path) -> Result<Scene, SceneError> {
+        let root = yaml::parse(content)?;
+        let items = match &root.value {
+            Value::Sequence(items) => items,
+            Value::Null => return Err(SceneError::new(0, "scene file is empty")),
+            _ => return Err(SceneError::new(root.line, "expected a list of scene items")),
+        };
+
+        let mut builder = Builder {
+            directory: directory.to_path_buf(),
+            defines: HashMap::new(),
+            camera: None,
+            world: World::default(),
+            warnings: Vec::new(),
+        };
+        for item in items {
+            builder.item(item)?;
+        }
+
+        let camera = builder
+            .camera
+            .ok_or_else(|| SceneError::new(0, "scene does not add a camera"))?;
+        Ok(Scene {
+            world: builder.world,
+            camera,
+            warnings: builder.warnings,
+        })
+    }
+}
+
+enum Item {
+    Object(Box<Object>),
+    Group(Box<Group>),
+    Csg(CSG),
+}
+
+impl Item {
+    fn member(self) -> GroupMember {
+        match self {
+            Item::Object(o) => GroupMember::Object(Arc::new(*o)),
+            Item::Group(g) => GroupMember::SubGroup(Arc::new(*g)),
+            Item::Csg(c) => GroupMember::CSG(Arc::new(c)),
+        }
+    }
+}
+
+struct Builder {
+    directory: PathBuf,
+    defines: HashMap<String, Node>,
+    camera: Option<Camera>,
+    world: World,
+    warnings: Vec<SceneError>,
+}
+
+impl Builder {
+    fn item(&mut self, node: &Node) -> Result<(), SceneError> {
+        let entries = mapping(node)?;
+        if let Some(name) = get(entries, "define") {
+            return self.define(node, name);
+        }
+        let kind = match get(entries, "add") {
+            Some(kind) => string(kind)?,
+            None => return Err(SceneError::new(node.line, "expected 'add' or 'define'")),
+        };
+
+        match kind {
+            "camera" => {
+                if self.camera.is_some() {
+                    return Err(SceneError::new(node.line, "camera added twice"));
+                }
+                self.camera = Some(self.camera(node)?);
+            }
+            "light" => {
+                if self.world.light.is_some() {
+                    return Err(SceneError::new(node.line, "only one light is supported"));
+                }
+                self.world.light = Some(self.light(node)?);
+            }
+            _ => match self.shape(node, None)? {
+                Item::Object(o) => self.world.add_object(*o),
+                Item::Group(g) => self.world.add_group(*g),
+                Item::Csg(c) => self.world.add_csg(c),
+            },
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, node: &Node, name: &Node) -> Result<(), SceneError> {
+        let entries = mapping(node)?;
+        check_keys(node, &["define", "extend", "value"])?;
+        let name = string(name)?.to_string();
+        let value = get(entries, "value")
+            .ok_or_else(|| SceneError::new(node.line, format!("define '{}' has no value", name)))?;
+
+        let value = match get(entries, "extend") {
+            None => value.clone(),
+            Some(base) => {
+                let base = self.lookup(base)?;
+                merge(base, value)?
+            }
+        };
+        self.defines.insert(name, value);
+        Ok(())
+    }
+
+    fn lookup(&self, name: &Node) -> Result<&Node, SceneError> {
+        let key = string(name)?;
+        self.defines
+            .get(key)
+            .ok_or_else(|| SceneError::new(name.line, format!("'{}' is not defined", key)))
+    }
+
+    fn camera(&self, node: &Node) -> Result<Camera, SceneError> {
+        check_keys(
+            node,
+            &[
+                "add",
+                "width",
+                "height",
+                "field-of-view",
+                "from",
+                "to",
+                "up",
+            ],
+        )?;
+        let width = usize_value(required(node, "width")?)?;
+        let height = usize_value(required(node, "height")?)?;
+        let field_of_view = float(required(node, "field-of-view")?)?;
+        let from = triple(required(node, "from")?, point)?;
+        let to = triple(required(node, "to")?, point)?;
+        let up = triple(required(node, "up")?, vector)?;
+
+        let mut camera = Camera::new(width, height, field_of_view);
+        camera.set_transform(view_transform(&from, &to, &up));
+        Ok(camera)
+    }
+
+    fn light(&self, node: &Node) -> Result<Pointlight, SceneError> {
+        check_keys(node, &["add", "at", "intensity"])?;
+        let position = triple(required(node, "at")?, point)?;
+        let intensity = triple(required(node, "intensity")?, color)?;
+        Ok(Pointlight::new(position, intensity))
+    }
+
+    fn shape(&mut self, node: &Node, inherited: Option<&Material>) -> Result<Item, SceneError> {
+        let node = self.resolve_shape(node)?;
+        let kind = string(required(&node, "add")?)?;
+
+        let material = match get(mapping(&node)?, "material") {
+            Some(m) => Some(self.material(m)?),
+            None => inherited.cloned(),
+        };
+        let transform = match get(mapping(&node)?, "transform") {
+            Some(t) => self.transform(t)?,
+            None => identity_matrix(),
+        };
+
+        let item = match kind {
+            "sphere" | "plane" | "cube" | "disk" | "rectangle" => {
+                check_keys(&node, &["add", "material", "transform", "shadow"])?;
+                let shape = match kind {
+                    "sphere" => Shape::Sphere,
+                    "plane" => Shape::Plane,
+                    "disk" => Shape::Disk,
+                    "rectangle" => Shape::Rectangle,
+                    _ => Shape::Cube,
+                };
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "cylinder" | "cone" => {
+                check_keys(
+                    &node,
+                    &[
+                        "add",
+                        "material",
+                        "transform",
+                        "shadow",
+                        "min",
+                        "max",
+                        "closed",
+                    ],
+                )?;
+                let entries = mapping(&node)?;
+                let min = match get(entries, "min") {
+                    Some(v) => float(v)?,
+                    None => f64::NEG_INFINITY,
+                };
+                let max = match get(entries, "max") {
+                    Some(v) => float(v)?,
+                    None => f64::INFINITY,
+                };
+                let closed = match get(entries, "closed") {
+                    Some(v) => boolean(v)?,
+                    None => false,
+                };
+                let shape = match kind {
+                    "cylinder" => Shape::Cylinder(min, max, closed),
+                    _ => Shape::Cone(min, max, closed),
+                };
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "annulus" => {
+                check_keys(&node, &["add", "material", "transform", "shadow", "inner"])?;
+                let inner = match get(mapping(&node)?, "inner") {
+                    Some(v) => match float(v)? {
+                        inner if (0.0..1.0).contains(&inner) => inner,
+                        _ => {
+                            return Err(SceneError::new(
+                                v.line,
+                                "inner radius must be at least 0 and less than 1",
+                            ))
+                        }
+                    },
+                    None => 0.5,
+                };
+                let shape = Shape::Annulus(inner);
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "torus" => {
+                check_keys(
+                    &node,
+                    &["add", "material", "transform", "shadow", "major", "minor"],
+                )?;
+                let entries = mapping(&node)?;
+                let major = match get(entries, "major") {
+                    Some(v) => match float(v)? {
+                        major if major > 0.0 => major,
+                        _ => return Err(SceneError::new(v.line, "major radius must be positive")),
+                    },
+                    None => 1.0,
+                };
+                // a tube wider than the ring would pass through its own center
+                let (minor, line) = match get(entries, "minor") {
+                    Some(v) => (float(v)?, v.line),
+                    None => (0.25, node.line),
+                };
+                if minor <= 0.0 || minor > major {
+                    return Err(SceneError::new(
+                        line,
+                        "minor radius must be positive and at most the major radius",
+                    ));
+                }
+                let shape = Shape::Torus(major, minor);
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "lathe" => {
+                check_keys(
+                    &node,
+                    &[
+                        "add",
+                        "material",
+                        "transform",
+                        "shadow",
+                        "profile",
+                        "spline",
+                        "steps",
+                    ],
+                )?;
+                let entries = mapping(&node)?;
+                let profile = profile(required(&node, "profile")?)?;
+                let spline = match get(entries, "spline") {
+                    Some(v) => boolean(v)?,
+                    None => false,
+                };
+                let lathe = match spline {
+                    true => {
+                        let steps = match get(entries, "steps") {
+                            Some(v) => usize_value(v)?,
+                            None => 8,
+                        };
+                        Lathe::spline(&profile, steps)
+                    }
+                    false => Lathe::polyline(&profile),
+                };
+                let shape = Shape::Lathe(lathe);
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "heightfield" => {
+                check_keys(&node, &["add", "material", "transform", "shadow", "file"])?;
+                let file = required(&node, "file")?;
+                let path = self.directory.join(string(file)?);
+                let canvas = Canvas::load(&path).map_err(|err| {
+                    SceneError::new(
+                        file.line,
+                        format!("could not read {}: {}", path.display(), err),
+                    )
+                })?;
+                let heightfield = Heightfield::from_canvas(&canvas)
+                    .map_err(|err| SceneError::new(file.line, err))?;
+                let shape = Shape::Heightfield(heightfield);
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "sdf" => {
+                check_keys(
+                    &node,
+                    &["add", "material", "transform", "shadow", "distance"],
+                )?;
+                let shape = Shape::Sdf(distance(required(&node, "distance")?)?);
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "group" => {
+                check_keys(&node, &["add", "material", "transform", "children"])?;
+                let mut group = Group::default();
+                if let Some(children) = get(mapping(&node)?, "children") {
+                    for child in sequence(children)? {
+                        match self.shape(child, material.as_ref())? {
+                            Item::Object(o) => group.add_object(*o),
+                            Item::Group(g) => group.add_group(*g),
+                            Item::Csg(c) => group.add_csg(c),
+                        }
+                    }
+                }
+                group.set_transform(transform);
+                Item::Group(Box::new(group))
+            }
+            "obj" => {
+                check_keys(&node, &["add", "material", "transform", "file", "mesh"])?;
+                let file = required(&node, "file")?;
+                let path = self.directory.join(string(file)?);
+                let load = match get(mapping(&node)?, "mesh") {
+                    Some(v) if boolean(v)? => Parser::load_obj_file_as_meshes,
+                    _ => Parser::load_obj_file,
+                };
+                let parser = load(&path).map_err(|err| match err.line {
+                    0 => SceneError::new(file.line, err.message),
+                    _ => SceneError::new(file.line, format!("{}: {}", path.display(), err)),
+                })?;
+                for warning in parser.warnings.iter() {
+                    let message = format!("{}: {}", path.display(), warning);
+                    self.warnings.push(SceneError::new(file.line, message));
+                }
+                let mut group = parser.to_group();
+                if let Some(material) = material {
+                    group.set_material(&material);
+                }
+                group.set_transform(transform);
+                Item::Group(Box::new(group))
+            }
+            "bezier" => {
+                check_keys(
+                    &node,
+                    &[
+                        "add",
+                        "material",
+                        "transform",
+                        "shadow",
+                        "file",
+                        "tolerance",
+                    ],
+                )?;
+                let file = required(&node, "file")?;
+                let path = self.directory.join(string(file)?);
+                let patches = load_bezier_file(&path).map_err(|err| {
+                    SceneError::new(
+                        file.line,
+                        format!("could not read {}: {}", path.display(), err),
+                    )
+                })?;
+                let tolerance = match get(mapping(&node)?, "tolerance") {
+                    Some(v) => float(v)?,
+                    None => 0.01,
+                };
+                if tolerance <= 0.0 {
+                    return Err(SceneError::new(node.line, "tolerance must be positive"));
+                }
+                let shape = Shape::Mesh(tessellate(&patches, tolerance));
+                Item::Object(Box::new(object(&node, shape, transform, material)?))
+            }
+            "ply" | "stl" => {
+                check_keys(&node, &["add", "material", "transform", "file"])?;
+                let file = required(&node, "file")?;
+                let path = self.directory.join(string(file)?);
+                let load = match kind {
+                    "ply" => load_ply_file,
+                    _ => load_stl_file,
+                };
+                let mut group = load(&path).map_err(|err| {
+                    SceneError::new(
+                        file.line,
+                        format!("could not read {}: {}", path.display(), err),
+                    )
+                })?;
+                if let Some(material) = material {
+                    group.set_material(&material);
+                }
+                group.set_transform(transform);
+                Item::Group(Box::new(group))
+            }
+            "csg" => {
+                check_keys(
+                    &node,
+                    &["add", "material", "transform", "operation", "left", "right"],
+                )?;
+                let operation = required(&node, "operation")?;
+                let left = self.shape(required(&node, "left")?, material.as_ref())?;
+                let right = self.shape(required(&node, "right")?, material.as_ref())?;
+                let (left, right) = (left.member(), right.member());
+                let csg = match string(operation)? {
+                    "union" => CSG::Union(left, right),
+                    "intersection" => CSG::Intersection(left, right),
+                    "difference" => CSG::Difference(left, right),
+                    op => {
+                        return Err(SceneError::new(
+                            operation.line,
+                            format!("unknown csg operation '{}'", op),
+                        ))
+                    }
+                };
+                Item::Csg(csg.update_transform(&transform))
+            }
+            kind => {
+                return Err(SceneError::new(
+                    node.line,
+                    format!("unknown shape '{}'", kind),
+                ))
+            }
+        };
+
+        Ok(item)
+    }
+
+    // replaces `add: <name>` of a defined shape with the definition, keys of
+    // the item take precedence over the keys of the definition
+    fn resolve_shape(&self, node: &Node) -> Result<Node, SceneError> {
+        let mut node = node.clone();
+        for _ in 0..self.defines.len() {
+            let kind = required(&node, "add")?;
+            let definition = match self.defines.get(string(kind)?) {
+                Some(definition) => definition,
+                None => return Ok(node),
+            };
+            let mut entries = mapping(&node)?.to_vec();
+            entries.retain(|(key, _)| key != "add");
+            node = merge(definition, &Node::new(node.line, Value::Mapping(entries)))?;
+        }
+        Ok(node)
+    }
+
+    fn material(&self, node: &Node) -> Result<Material, SceneError> {
+        let node = match node.value {
+            Value::Scalar(_) => self.lookup(node)?,
+            _ => node,
+        };
+
+        let mut material = Material::default();
+        for (key, value) in mapping(node)? {
+            match key.as_str() {
+                "color" => material.color = triple(value, color)?,
+                "ambient" => material.ambient = float(value)?,
+                "diffuse" => material.diffuse = float(value)?,
+                "specular" => material.specular = float(value)?,
+                "shininess" => material.shininess = float(value)?,
+                "reflective" => material.reflective = float(value)?,
+                "transparency" => material.transparency = float(value)?,
+                "refractive-index" => material.refractive_index = float(value)?,
+                "pattern" => material.pattern = Some(Box::new(self.pattern(value)?)),
+                key => {
+                    return Err(SceneError::new(
+                        value.line,
+                        format!("unknown material attribute '{}'", key),
+                    ))
+                }
+            }
+        }
+        Ok(material)
+    }
+
+    fn pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        let kind = required(node, "type")?;
+        let mut pattern = match string(kind)? {
+            "image" => self.image_pattern(node)?,
+            "uv_checkers" => self.uv_checkers_pattern(node)?,
+            "align_check" => self.align_check_pattern(node)?,
+            "cube_map" => self.cube_map_pattern(node)?,
+            _ => self.two_color_pattern(node)?,
+        };
+        if let Some(transform) = get(mapping(node)?, "transform") {
+            pattern.set_transform(self.transform(transform)?);
+        }
+        if let Some(uv_mapping) = get(mapping(node)?, "mapping") {
+            pattern.set_mapping(match string(uv_mapping)? {
+                "spherical" => UvMapping::Spherical,
+                "planar" => UvMapping::Planar,
+                "cylindrical" => UvMapping::Cylindrical,
+                "cube" => UvMapping::Cube,
+                m => {
+                    return Err(SceneError::new(
+                        uv_mapping.line,
+                        format!("unknown mapping '{}'", m),
+                    ))
+                }
+            });
+        }
+        Ok(pattern)
+    }
+
+    fn uv_checkers_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        check_keys(
+            node,
+            &["type", "width", "height", "colors", "transform", "mapping"],
+        )?;
+        let width = float(required(node, "width")?)?;
+        let height = float(required(node, "height")?)?;
+        let colors = sequence(required(node, "colors")?)?;
+        if colors.len() != 2 {
+            return Err(SceneError::new(
+                node.line,
+                "a pattern needs exactly two colors",
+            ));
+        }
+        let a = Box::new(self.sub_pattern(&colors[0])?);
+        let b = Box::new(self.sub_pattern(&colors[1])?);
+        Ok(uv_checkers_pattern(width, height, a, b))
+    }
+
+    fn align_check_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        let corners = ["main", "ul", "ur", "bl", "br"];
+        check_keys(
+            node,
+            &[
+                "type",
+                "main",
+                "ul",
+                "ur",
+                "bl",
+                "br",
+                "transform",
+                "mapping",
+            ],
+        )?;
+        let [main, ul, ur, bl, br] = corners.map(|key| self.sub_pattern(required(node, key)?));
+        Ok(align_check_pattern(main?, ul?, ur?, bl?, br?))
+    }
+
+    fn cube_map_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        let faces = ["left", "front", "right", "back", "up", "down"];
+        check_keys(
+            node,
+            &[
+                "type",
+                "left",
+                "front",
+                "right",
+                "back",
+                "up",
+                "down",
+                "transform",
+            ],
+        )?;
+        let [left, front, right, back, up, down] =
+            faces.map(|key| self.sub_pattern(required(node, key)?));
+        Ok(cube_map_pattern([left?, front?, right?, back?, up?, down?]))
+    }
+
+    fn image_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        check_keys(
+            node,
+            &["type", "file", "filter", "wrap", "transform", "mapping"],
+        )?;
+        let file = required(node, "file")?;
+        let path = self.directory.join(string(file)?);
+        let canvas = Canvas::load(&path).map_err(|err| {
+            SceneError::new(
+                file.line,
+                format!("could not read {}: {}", path.display(), err),
+            )
+        })?;
+        let filter = match get(mapping(node)?, "filter") {
+            None => Filter::Bilinear,
+            Some(filter) => match string(filter)? {
+                "nearest" => Filter::Nearest,
+                "bilinear" => Filter::Bilinear,
+                f => {
+                    return Err(SceneError::new(
+                        filter.line,
+                        format!("unknown filter '{}'", f),
+                    ))
+                }
+            },
+        };
+        let wrap = match get(mapping(node)?, "wrap") {
+            None => Wrap::Repeat,
+            Some(wrap) => match string(wrap)? {
+                "repeat" => Wrap::Repeat,
+                "clamp" => Wrap::Clamp,
+                w => {
+                    return Err(SceneError::new(
+                        wrap.line,
+                        format!("unknown wrap mode '{}'", w),
+                    ))
+                }
+            },
+        };
+        Ok(image_pattern(canvas, filter, wrap))
+    }
+
+    fn two_color_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        check_keys(node, &["type", "colors", "transform", "mapping"])?;
+        let kind = required(node, "type")?;
+        let colors = sequence(required(node, "colors")?)?;
+        if colors.len() != 2 {
+            return Err(SceneError::new(
+                node.line,
+                "a pattern needs exactly two colors",
+            ));
+        }
+        let a = Box::new(self.sub_pattern(&colors[0])?);
+        let b = Box::new(self.sub_pattern(&colors[1])?);
+
+        let pattern = match string(kind)? {
+            "stripes" => stripe_pattern(a, b),
+            "gradient" => gradient_pattern(a, b),
+            "rings" => ring_pattern(a, b),
+            "checkers" => checkers_pattern(a, b),
+            "radial-gradient" => radial_gradient_pattern(a, b),
+            "blended" => Pattern::new(identity_matrix(), Renderer::Blended(a, b)),
+            k => {
+                return Err(SceneError::new(
+                    kind.line,
+                    format!("unknown pattern '{}'", k),
+                ))
+            }
+        };
+        Ok(pattern)
+    }
+
+    // a pattern entry is either a color or a nested pattern
+    fn sub_pattern(&self, node: &Node) -> Result<Pattern, SceneError> {
+        match node.value {
+            Value::Mapping(_) => self.pattern(node),
+            _ => Ok(solid_pattern(triple(node, color)?)),
+        }
+    }
+
+    fn transform(&self, node: &Node) -> Result<Matrix4x4, SceneError> {
+        let mut transform = identity_matrix();
+        for step in sequence(node)? {
+            let m = match &step.value {
+                Value::Scalar(_) => self.transform(self.lookup(step)?)?,
+                Value::Sequence(values) => operation(step, values)?,
+                _ => {
+                    return Err(SceneError::new(
+                        step.line,
+                        "expected a transformation or the name of a defined transformation",
+                    ))
+                }
+            };
+            // objects, groups and instances need the inverse of their transform
+            if m.inverse().is_err() {
+                return Err(SceneError::new(
+                    step.line,
+                    "transformation is not invertible",
+                ));
+            }
+            // transformations are applied in the order they are listed
+            transform = m * transform;
+        }
+        Ok(transform)
+    }
+}
+
+fn object(
+    node: &Node,
+    shape: Shape,
+    transform: Matrix4x4,
+    material: Option<Material>,
+) -> Result<Object, SceneError> {
+    let material = material.unwrap_or_else(Material::default);
+    let mut object = Object::new(shape, transform, material);
+    if let Some(shadow) = get(mapping(node)?, "shadow") {
+        object.throws_shaddow = boolean(shadow)?;
+    }
+    Ok(object)
+}
+
+fn operation(node: &Node, values: &[Node]) -> Result<Matrix4x4, SceneError> {
+    let (name, args) = match values.split_first() {
+        Some((name, args)) => (string(name)?, args),
+        None => return Err(SceneError::new(node.line, "empty transformation")),
+    };
+    let args = args.iter().map(float).collect::<Result<Vec<f64>, _>>()?;
+    let expected = match name {
+        "translate" | "scale" => 3,
+        "rotate-x" | "rotate-y" | "rotate-z" => 1,
+        "shear" => 6,
+        _ => {
+            return Err(SceneError::new(
+                node.line,
+                format!("unknown transformation '{}'", name),
+            ))
+        }
+    };
+    if args.len() != expected {
+        return Err(SceneError::new(
+            node.line,
+            format!(
+                "{} expects {} arguments, got {}",
+                name,
+                expected,
+                args.len()
+            ),
+        ));
+    }
+    Ok(match name {
+        "translate" => translation(args[0], args[1], args[2]),
+        "scale" => scaling(args[0], args[1], args[2]),
+        "rotate-x" => rotation_x(args[0]),
+        "rotate-y" => rotation_y(args[0]),
+        "rotate-z" => rotation_z(args[0]),
+        _ => shearing(args[0], args[1], args[2], args[3], args[4], args[5]),
+    })
+}
+
+fn merge(base: &Node, update: &Node) -> Result<Node, SceneError> {
+    let mut entries = mapping(base)?.to_vec();
+    for (key, value) in mapping(update)? {
+        match entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.clone(),
+            None => entries.push((key.clone(), value.clone())),
+        }
+    }
+    Ok(Node::new(update.line, Value::Mapping(entries)))
+}
+
+fn mapping(node: &Node) -> Result<&[(String, Node)], SceneError> {
+    match &node.value {
+        Value::Mapping(entries) => Ok(entries),
+        _ => Err(SceneError::new(node.line, "expected a mapping")),
+    }
+}
+
+fn sequence(node: &Node) -> Result<&[Node], SceneError> {
+    match &node.value {
+        Value::Sequence(items) => Ok(items),
+        _ => Err(SceneError::new(node.line, "expected a list")),
+    }
+}
+
+fn string(node: &Node) -> Result<&str, SceneError> {
+    match &node.value {
+        Value::Scalar(s) => Ok(s),
+        _ => Err(SceneError::new(node.line, "expected a value")),
+    }
+}
+
+fn get<'a>(entries: &'a [(String, Node)], key: &str) -> Option<&'a Node> {
+    entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn required<'a>(node: &'a Node, key: &str) -> Result<&'a Node, SceneError> {
+    get(mapping(node)?, key).ok_or_else(|| SceneError::new(node.line, format!("missing '{}'", key)))
+}
+
+fn check_keys(node: &Node, allowed: &[&str]) -> Result<(), SceneError> {
+    for (key, value) in mapping(node)? {
+        if !allowed.contains(&key.as_str()) {
+            return Err(SceneError::new(
+                value.line,
+                format!("unknown attribute '{}'", key),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn float(node: &Node) -> Result<f64, SceneError> {
+    let s = string(node)?;
+    s.parse::<f64>()
+        .map_err(|_| SceneError::new(node.line, format!("'{}' is not a number", s)))
+}
+
+fn usize_value(node: &Node) -> Result<usize, SceneError> {
+    let s = string(node)?;
+    match s.parse::<usize>() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(SceneError::new(
+            node.line,
+            format!("'{}' is not a positive integer", s),
+        )),
+    }
+}
+
+fn boolean(node: &Node) -> Result<bool, SceneError> {
+    match string(node)? {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        s => Err(SceneError::new(
+            node.line,
+            format!("'{}' is not true or false", s),
+        )),
+    }
+}
+
+// a list of [radius, y] pairs
+fn profile(node: &Node) -> Result<Vec<(f64, f64)>, SceneError> {
+    let points = sequence(node)?;
+    if points.len() < 2 {
+        return Err(SceneError::new(
+            node.line,
+            "a profile needs at least 2 points",
+        ));
+    }
+    let mut profile = Vec::with_capacity(points.len());
+    for p in points {
+        let values = sequence(p)?;
+        if values.len() != 2 {
+            return Err(SceneError::new(
+                p.line,
+                format!("expected 2 numbers, got {}", values.len()),
+            ));
+        }
+        let radius = float(&values[0])?;
+        if radius < 0.0 {
+            return Err(SceneError::new(p.line, "radius must not be negative"));
+        }
+        profile.push((radius, float(&values[1])?));
+    }
+    Ok(profile)
+}
+
+// a tree of distance functions, every node names what it is with its first
+// key, operators that change one shape take it from "of"
+fn distance(node: &Node) -> Result<Sdf, SceneError> {
+    let entries = mapping(node)?;
+    let Some((kind, value)) = entries.first() else {
+        return Err(SceneError::new(node.line, "expected a distance function"));
+    };
+    let numbers = |count: usize| -> Result<Vec<f64>, SceneError> {
+        let values = sequence(value)?;
+        if values.len() != count {
+            return Err(SceneError::new(
+                value.line,
+                format!("expected {} numbers, got {}", count, values.len()),
+            ));
+        }
+        values.iter().map(float).collect()
+    };
+    let shapes = |least: usize| -> Result<Vec<Sdf>, SceneError> {
+        let items = sequence(value)?;
+        if items.len() < least {
+            return Err(SceneError::new(
+                value.line,
+                format!("expected at least {} shapes, got {}", least, items.len()),
+            ));
+        }
+        items.iter().map(distance).collect()
+    };
+    let of = || distance(required(node, "of")?);
+    let blend = || float(required(node, "k")?);
+    let sdf = match kind.as_str() {
+        "sphere" => {
+            check_keys(node, &["sphere"])?;
+            Sdf::Sphere(float(value)?)
+        }
+        "box" => {
+            check_keys(node, &["box"])?;
+            let v = numbers(3)?;
+            Sdf::Box(v[0], v[1], v[2])
+        }
+        "torus" => {
+            check_keys(node, &["torus"])?;
+            let v = numbers(2)?;
+            Sdf::Torus(v[0], v[1])
+        }
+        "cylinder" => {
+            check_keys(node, &["cylinder"])?;
+            let v = numbers(2)?;
+            Sdf::Cylinder(v[0], v[1])
+        }
+        "union" | "intersection" => {
+            check_keys(node, &[kind.as_str()])?;
+            let combine = match kind.as_str() {
+                "union" => Sdf::union,
+                _ => Sdf::intersection,
+            };
+            shapes(2)?.into_iter().reduce(combine).unwrap()
+        }
+        "smooth-union" => {
+            check_keys(node, &["smooth-union", "k"])?;
+            let k = blend()?;
+            shapes(2)?
+                .into_iter()
+                .reduce(|a, b| Sdf::smooth_union(a, b, k))
+                .unwrap()
+        }
+        "subtraction" | "smooth-subtraction" => {
+            // everything after the first shape is cut out of it
+            let k = match kind.as_str() {
+                "subtraction" => {
+                    check_keys(node, &["subtraction"])?;
+                    0.0
+                }
+                _ => {
+                    check_keys(node, &["smooth-subtraction", "k"])?;
+                    blend()?
+                }
+            };
+            let mut shapes = shapes(2)?.into_iter();
+            let first = shapes.next().unwrap();
+            shapes.fold(first, |a, b| {
+                if k == 0.0 {
+                    Sdf::subtraction(a, b)
+                } else {
+                    Sdf::smooth_subtraction(a, b, k)
+                }
+            })
+        }
+        "translate" => {
+            check_keys(node, &["translate", "of"])?;
+            let v = numbers(3)?;
+            of()?.translate(v[0], v[1], v[2])
+        }
+        "scale" => {
+            check_keys(node, &["scale", "of"])?;
+            let factor = float(value)?;
+            if factor <= 0.0 {
+                return Err(SceneError::new(value.line, "scale must be positive"));
+            }
+            of()?.scale(factor)
+        }
+        "twist" => {
+            check_keys(node, &["twist", "of"])?;
+            of()?.twist(float(value)?)
+        }
+        "bend" => {
+            check_keys(node, &["bend", "of"])?;
+            of()?.bend(float(value)?)
+        }
+        "repeat" => {
+            check_keys(node, &["repeat", "count", "of"])?;
+            let v = numbers(3)?;
+            let count = triple(required(node, "count")?, vector)?;
+            let counts = [count.x, count.y, count.z];
+            if counts.iter().any(|c| *c < 0.0 || c.fract() != 0.0) {
+                return Err(SceneError::new(
+                    node.line,
+                    "repeat counts must be positive integers",
+                ));
+            }
+            of()?.repeat([v[0], v[1], v[2]], counts.map(|c| c as u32))
+        }
+        other => {
+            return Err(SceneError::new(
+                node.line,
+                format!("unknown distance function '{}'", other),
+            ))
+        }
+    };
+    Ok(sdf)
+}
+
+fn triple(node: &Node, build: fn(f64, f64, f64) -> Tuple) -> Result<Tuple, SceneError> {
+    let values = sequence(node)?;
+    if values.len() != 3 {
+        return Err(SceneError::new(
+            node.line,
+            format!("expected 3 numbers, got {}", values.len()),
+        ));
+    }
+    Ok(build(
+        float(&values[0])?,
+        float(&values[1])?,
+        float(&values[2])?,
+    ))
+}
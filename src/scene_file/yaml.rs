@@ -0,0 +1,398 @@
+// A small subset of YAML, just enough for the scene files: block sequences and
+// mappings, flow sequences and mappings (`[ 1, 2, 3 ]`, `{ a: 1 }`), plain and
+// quoted scalars and `#` comments. Every node remembers the line it starts on.
+use super::SceneError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(String, Node)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub line: usize,
+    pub value: Value,
+}
+
+impl Node {
+    pub fn new(line: usize, value: Value) -> Self {
+        Node { line, value }
+    }
+}
+
+#[derive(Debug)]
+struct Line {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+pub fn parse(content: &str) -> Result<Node, SceneError> {
+    let lines = split_lines(content)?;
+    if lines.is_empty() {
+        return Ok(Node::new(1, Value::Null));
+    }
+
+    let mut parser = BlockParser { lines, position: 0 };
+    let indent = parser.lines[0].indent;
+    let node = parser.block(indent)?;
+    if let Some(line) = parser.lines.get(parser.position) {
+        return Err(SceneError::new(line.number, "unexpected indentation"));
+    }
+    Ok(node)
+}
+
+fn split_lines(content: &str) -> Result<Vec<Line>, SceneError> {
+    let mut lines = Vec::new();
+    for (i, raw) in content.lines().enumerate() {
+        let number = i + 1;
+        let text = strip_comment(raw).trim_end();
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed == "---" {
+            continue;
+        }
+        let whitespace = &text[..text.len() - trimmed.len()];
+        if whitespace.contains('\t') {
+            return Err(SceneError::new(
+                number,
+                "tabs are not allowed for indentation",
+            ));
+        }
+        lines.push(Line {
+            number,
+            indent: whitespace.len(),
+            text: trimmed.to_string(),
+        });
+    }
+    Ok(lines)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..i],
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+// splits `key: value` into its parts, flow collections and quoted scalars are
+// never treated as keys
+fn split_entry(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with(['[', '{', '"', '\'']) {
+        return None;
+    }
+    let bytes = text.as_bytes();
+    for (i, c) in text.char_indices() {
+        if c == '[' || c == '{' {
+            return None;
+        }
+        if c == ':' && (i + 1 == bytes.len() || bytes[i + 1] == b' ') {
+            let key = text[..i].trim();
+            if key.is_empty() {
+                return None;
+            }
+            return Some((key, text[i + 1..].trim()));
+        }
+    }
+    None
+}
+
+struct BlockParser {
+    lines: Vec<Line>,
+    position: usize,
+}
+
+impl BlockParser {
+    fn block(&mut self, indent: usize) -> Result<Node, SceneError> {
+        if is_sequence_item(&self.lines[self.position].text) {
+            self.sequence(indent)
+        } else {
+            self.mapping(indent)
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.position].number;
+        let mut items = Vec::new();
+
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent < indent || !is_sequence_item(&line.text) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(SceneError::new(line.number, "unexpected indentation"));
+            }
+
+            let number = line.number;
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.position += 1;
+                items.push(self.nested(indent, number)?);
+                continue;
+            }
+
+            if is_sequence_item(rest) || split_entry(rest).is_some() {
+                // "- key: value" opens a block whose indentation is the column of "key"
+                let item_indent = indent + line.text.len() - rest.len();
+                self.lines[self.position] = Line {
+                    number,
+                    indent: item_indent,
+                    text: rest.to_string(),
+                };
+                items.push(self.block(item_indent)?);
+            } else {
+                let rest = rest.to_string();
+                self.position += 1;
+                items.push(self.flow(rest, number)?);
+            }
+        }
+
+        Ok(Node::new(start, Value::Sequence(items)))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.position].number;
+        let mut entries: Vec<(String, Node)> = Vec::new();
+
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent {
+                return Err(SceneError::new(line.number, "unexpected indentation"));
+            }
+
+            let number = line.number;
+            let (key, rest) = match split_entry(&line.text) {
+                Some((key, rest)) if !is_sequence_item(&line.text) => {
+                    (key.to_string(), rest.to_string())
+                }
+                _ => return Err(SceneError::new(number, "expected 'key: value'")),
+            };
+            if entries.iter().any(|(k, _)| k == &key) {
+                return Err(SceneError::new(number, format!("duplicate key '{}'", key)));
+            }
+            self.position += 1;
+
+            let value = if rest.is_empty() {
+                match self.lines.get(self.position) {
+                    // a list may be written at the same indentation as its key
+                    Some(next) if next.indent == indent && is_sequence_item(&next.text) => {
+                        self.sequence(indent)?
+                    }
+                    _ => self.nested(indent, number)?,
+                }
+            } else {
+                self.flow(rest, number)?
+            };
+            entries.push((key, value));
+        }
+
+        Ok(Node::new(start, Value::Mapping(entries)))
+    }
+
+    // the block that follows a key or dash without a value, if any
+    fn nested(&mut self, indent: usize, number: usize) -> Result<Node, SceneError> {
+        match self.lines.get(self.position) {
+            Some(next) if next.indent > indent => {
+                let indent = next.indent;
+                self.block(indent)
+            }
+            _ => Ok(Node::new(number, Value::Null)),
+        }
+    }
+
+    fn flow(&mut self, mut text: String, number: usize) -> Result<Node, SceneError> {
+        if !text.starts_with(['[', '{']) {
+            return Ok(Node::new(number, scalar(&text, number)?));
+        }
+
+        // flow collections may continue on the following lines
+        while !is_balanced(&text) {
+            match self.lines.get(self.position) {
+                Some(next) => {
+                    text.push(' ');
+                    text.push_str(&next.text);
+                    self.position += 1;
+                }
+                None => return Err(SceneError::new(number, "unterminated flow collection")),
+            }
+        }
+
+        let mut parser = FlowParser {
+            chars: text.chars().collect(),
+            position: 0,
+            line: number,
+        };
+        let node = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position < parser.chars.len() {
+            return Err(SceneError::new(
+                number,
+                "unexpected characters after flow collection",
+            ));
+        }
+        Ok(node)
+    }
+}
+
+fn is_balanced(text: &str) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '[') | (None, '{') => depth += 1,
+            (None, ']') | (None, '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+fn scalar(text: &str, line: usize) -> Result<Value, SceneError> {
+    let text = text.trim();
+    if text.is_empty() || text == "~" || text == "null" {
+        return Ok(Value::Null);
+    }
+    for quote in ['"', '\''] {
+        if text.starts_with(quote) {
+            if text.len() < 2 || !text.ends_with(quote) {
+                return Err(SceneError::new(line, "unterminated quoted string"));
+            }
+            return Ok(Value::Scalar(text[1..text.len() - 1].to_string()));
+        }
+    }
+    Ok(Value::Scalar(text.to_string()))
+}
+
+struct FlowParser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+}
+
+impl FlowParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: &[char]) -> Result<char, SceneError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if expected.contains(&c) => {
+                self.position += 1;
+                Ok(c)
+            }
+            Some(c) => Err(SceneError::new(
+                self.line,
+                format!("unexpected '{}' in flow collection", c),
+            )),
+            None => Err(SceneError::new(self.line, "unterminated flow collection")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, SceneError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('[') => self.sequence(),
+            Some('{') => self.mapping(),
+            _ => {
+                let text = self.token(&[',', ']', '}'])?;
+                Ok(Node::new(self.line, scalar(&text, self.line)?))
+            }
+        }
+    }
+
+    fn sequence(&mut self) -> Result<Node, SceneError> {
+        self.position += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Node::new(self.line, Value::Sequence(items)));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.expect(&[',', ']'])? == ']' {
+                return Ok(Node::new(self.line, Value::Sequence(items)));
+            }
+        }
+    }
+
+    fn mapping(&mut self) -> Result<Node, SceneError> {
+        self.position += 1;
+        let mut entries: Vec<(String, Node)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Node::new(self.line, Value::Mapping(entries)));
+        }
+        loop {
+            let key = self.token(&[':', ',', '}'])?;
+            self.expect(&[':'])?;
+            let key = match scalar(&key, self.line)? {
+                Value::Scalar(key) => key,
+                _ => return Err(SceneError::new(self.line, "expected a key")),
+            };
+            if entries.iter().any(|(k, _)| k == &key) {
+                return Err(SceneError::new(
+                    self.line,
+                    format!("duplicate key '{}'", key),
+                ));
+            }
+            let value = self.value()?;
+            entries.push((key, value));
+            if self.expect(&[',', '}'])? == '}' {
+                return Ok(Node::new(self.line, Value::Mapping(entries)));
+            }
+        }
+    }
+
+    // reads a plain or quoted scalar up to one of the terminators
+    fn token(&mut self, terminators: &[char]) -> Result<String, SceneError> {
+        self.skip_whitespace();
+        let start = self.position;
+        if let Some(quote @ ('"' | '\'')) = self.peek() {
+            self.position += 1;
+            while let Some(c) = self.peek() {
+                self.position += 1;
+                if c == quote {
+                    return Ok(self.chars[start..self.position].iter().collect());
+                }
+            }
+            return Err(SceneError::new(self.line, "unterminated quoted string"));
+        }
+        while let Some(c) = self.peek() {
+            if terminators.contains(&c) || c == '[' || c == '{' {
+                break;
+            }
+            self.position += 1;
+        }
+        Ok(self.chars[start..self.position].iter().collect())
+    }
+}
@@ -0,0 +1,341 @@
+// Shapes given by signed distance functions, negative inside. Rays are sphere
+// traced: the distance to the surface is a step that cannot pass through it.
+// Operators that bend space stretch distances, so they scale them down by how
+// much they stretch to keep the steps safe. Every node knows conservative
+// bounds, the tracing only runs where the ray crosses them.
+use crate::{
+    groups::AABB,
+    intersections::ShapeHit,
+    rays::Ray,
+    tuples::{point, vector, Tuple},
+};
+
+const HIT_EPSILON: f64 = 1e-6;
+const MAX_STEPS: usize = 1024;
+const NORMAL_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sdf {
+    Sphere(f64),
+    // half the size along every axis
+    Box(f64, f64, f64),
+    Torus(f64, f64),
+    // radius and half the height
+    Cylinder(f64, f64),
+    Union(Box<Sdf>, Box<Sdf>),
+    Intersection(Box<Sdf>, Box<Sdf>),
+    Subtraction(Box<Sdf>, Box<Sdf>),
+    // the blend radius comes last
+    SmoothUnion(Box<Sdf>, Box<Sdf>, f64),
+    SmoothSubtraction(Box<Sdf>, Box<Sdf>, f64),
+    Translate(Box<Sdf>, f64, f64, f64),
+    Scale(Box<Sdf>, f64),
+    // radians per unit along y and along x, with the factor that keeps the
+    // distances below the real ones
+    Twist(Box<Sdf>, f64, f64),
+    Bend(Box<Sdf>, f64, f64),
+    // copies on a grid with the spacing, from -count to count on every axis
+    Repeat(Box<Sdf>, [f64; 3], [u32; 3]),
+}
+
+impl Sdf {
+    pub fn union(a: Sdf, b: Sdf) -> Sdf {
+        Sdf::Union(Box::new(a), Box::new(b))
+    }
+
+    pub fn intersection(a: Sdf, b: Sdf) -> Sdf {
+        Sdf::Intersection(Box::new(a), Box::new(b))
+    }
+
+    // a with b cut out of it
+    pub fn subtraction(a: Sdf, b: Sdf) -> Sdf {
+        Sdf::Subtraction(Box::new(a), Box::new(b))
+    }
+
+    pub fn smooth_union(a: Sdf, b: Sdf, k: f64) -> Sdf {
+        Sdf::SmoothUnion(Box::new(a), Box::new(b), k)
+    }
+
+    pub fn smooth_subtraction(a: Sdf, b: Sdf, k: f64) -> Sdf {
+        Sdf::SmoothSubtraction(Box::new(a), Box::new(b), k)
+    }
+
+    pub fn translate(self, x: f64, y: f64, z: f64) -> Sdf {
+        Sdf::Translate(Box::new(self), x, y, z)
+    }
+
+    pub fn scale(self, factor: f64) -> Sdf {
+        Sdf::Scale(Box::new(self), factor)
+    }
+
+    // the rotation around y grows with the height, points far from the axis
+    // move fastest
+    pub fn twist(self, amount: f64) -> Sdf {
+        let b = self.bounds();
+        let radius = b
+            .min
+            .x
+            .abs()
+            .max(b.max.x.abs())
+            .hypot(b.min.z.abs().max(b.max.z.abs()));
+        let factor = 1.0 / (1.0 + (amount * radius).powi(2)).sqrt();
+        Sdf::Twist(Box::new(self), amount, factor)
+    }
+
+    // the rotation around z grows along x
+    pub fn bend(self, amount: f64) -> Sdf {
+        let b = self.bounds();
+        let radius = b
+            .min
+            .x
+            .abs()
+            .max(b.max.x.abs())
+            .hypot(b.min.y.abs().max(b.max.y.abs()));
+        let factor = 1.0 / (1.0 + (amount * radius).powi(2)).sqrt();
+        Sdf::Bend(Box::new(self), amount, factor)
+    }
+
+    pub fn repeat(self, spacing: [f64; 3], count: [u32; 3]) -> Sdf {
+        Sdf::Repeat(Box::new(self), spacing, count)
+    }
+
+    pub fn distance(&self, p: &Tuple) -> f64 {
+        match self {
+            Sdf::Sphere(radius) => vector(p.x, p.y, p.z).magnitude() - radius,
+            Sdf::Box(x, y, z) => {
+                let q = vector(p.x.abs() - x, p.y.abs() - y, p.z.abs() - z);
+                let outside = vector(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).magnitude();
+                outside + q.x.max(q.y).max(q.z).min(0.0)
+            }
+            Sdf::Torus(major, minor) => (p.x.hypot(p.z) - major).hypot(p.y) - minor,
+            Sdf::Cylinder(radius, half_height) => {
+                let dr = p.x.hypot(p.z) - radius;
+                let dy = p.y.abs() - half_height;
+                dr.max(dy).min(0.0) + dr.max(0.0).hypot(dy.max(0.0))
+            }
+            Sdf::Union(a, b) => a.distance(p).min(b.distance(p)),
+            Sdf::Intersection(a, b) => a.distance(p).max(b.distance(p)),
+            Sdf::Subtraction(a, b) => a.distance(p).max(-b.distance(p)),
+            Sdf::SmoothUnion(a, b, k) => smooth_min(a.distance(p), b.distance(p), *k),
+            Sdf::SmoothSubtraction(a, b, k) => -smooth_min(-a.distance(p), b.distance(p), *k),
+            Sdf::Translate(a, x, y, z) => a.distance(&point(p.x - x, p.y - y, p.z - z)),
+            Sdf::Scale(a, factor) => {
+                a.distance(&point(p.x / factor, p.y / factor, p.z / factor)) * factor
+            }
+            Sdf::Twist(a, amount, factor) => {
+                let (sin, cos) = (-amount * p.y).sin_cos();
+                let q = point(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+                a.distance(&q) * factor
+            }
+            Sdf::Bend(a, amount, factor) => {
+                let (sin, cos) = (-amount * p.x).sin_cos();
+                let q = point(cos * p.x - sin * p.y, sin * p.x + cos * p.y, p.z);
+                a.distance(&q) * factor
+            }
+            Sdf::Repeat(a, spacing, count) => {
+                let cell = |v: f64, s: f64, n: u32| {
+                    if s == 0.0 {
+                        return v;
+                    }
+                    v - s * (v / s).round().clamp(-(n as f64), n as f64)
+                };
+                let q = point(
+                    cell(p.x, spacing[0], count[0]),
+                    cell(p.y, spacing[1], count[1]),
+                    cell(p.z, spacing[2], count[2]),
+                );
+                a.distance(&q)
+            }
+        }
+    }
+
+    pub fn bounds(&self) -> AABB {
+        match self {
+            Sdf::Sphere(r) => cube(*r, *r, *r),
+            Sdf::Box(x, y, z) => cube(*x, *y, *z),
+            Sdf::Torus(major, minor) => cube(major + minor, *minor, major + minor),
+            Sdf::Cylinder(radius, half_height) => cube(*radius, *half_height, *radius),
+            Sdf::Union(a, b) => union(&a.bounds(), &b.bounds()),
+            Sdf::Intersection(a, b) => intersection(&a.bounds(), &b.bounds()),
+            Sdf::Subtraction(a, _) => a.bounds(),
+            // blending never moves the surface further than the radius, and
+            // a subtraction only takes away from the first shape
+            Sdf::SmoothUnion(a, b, k) => grow(&union(&a.bounds(), &b.bounds()), *k),
+            Sdf::SmoothSubtraction(a, _, _) => a.bounds(),
+            Sdf::Translate(a, x, y, z) => {
+                let b = a.bounds();
+                AABB {
+                    min: point(b.min.x + x, b.min.y + y, b.min.z + z),
+                    max: point(b.max.x + x, b.max.y + y, b.max.z + z),
+                }
+            }
+            Sdf::Scale(a, factor) => {
+                let b = a.bounds();
+                let f = factor.abs();
+                AABB {
+                    min: point(b.min.x * f, b.min.y * f, b.min.z * f),
+                    max: point(b.max.x * f, b.max.y * f, b.max.z * f),
+                }
+            }
+            // rotations keep the distance to their axis
+            Sdf::Twist(a, _, _) => {
+                let b = a.bounds();
+                let r = b
+                    .min
+                    .x
+                    .abs()
+                    .max(b.max.x.abs())
+                    .hypot(b.min.z.abs().max(b.max.z.abs()));
+                AABB {
+                    min: point(-r, b.min.y, -r),
+                    max: point(r, b.max.y, r),
+                }
+            }
+            Sdf::Bend(a, _, _) => {
+                let b = a.bounds();
+                let r = b
+                    .min
+                    .x
+                    .abs()
+                    .max(b.max.x.abs())
+                    .hypot(b.min.y.abs().max(b.max.y.abs()));
+                AABB {
+                    min: point(-r, -r, b.min.z),
+                    max: point(r, r, b.max.z),
+                }
+            }
+            Sdf::Repeat(a, spacing, count) => {
+                let b = a.bounds();
+                let reach = |i: usize| spacing[i].abs() * count[i] as f64;
+                AABB {
+                    min: point(b.min.x - reach(0), b.min.y - reach(1), b.min.z - reach(2)),
+                    max: point(b.max.x + reach(0), b.max.y + reach(1), b.max.z + reach(2)),
+                }
+            }
+        }
+    }
+
+    // every crossing of the surface between the bounds, a ray touching it
+    // enters and leaves at the same t
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Vec<ShapeHit>) {
+        let b = self.bounds();
+        let (x_min, x_max) = slab(b.min.x, b.max.x, ray.origin.x, ray.direction.x);
+        let (y_min, y_max) = slab(b.min.y, b.max.y, ray.origin.y, ray.direction.y);
+        let (z_min, z_max) = slab(b.min.z, b.max.z, ray.origin.z, ray.direction.z);
+        let t_enter = x_min.max(y_min).max(z_min);
+        let t_exit = x_max.min(y_max).min(z_max);
+        let length = ray.direction.magnitude();
+        if t_enter > t_exit || length == 0.0 {
+            return;
+        }
+
+        let mut t = t_enter;
+        // the side of the surface the ray was on before it came close to it,
+        // the shape never reaches outside its bounds
+        let mut side = 1.0;
+        let mut near = false;
+        // where the ray came close to the surface last
+        let mut touched = t;
+        for _ in 0..MAX_STEPS {
+            if t > t_exit {
+                break;
+            }
+            let d = self.distance(&ray.position(t));
+            if d.abs() < HIT_EPSILON {
+                if !near {
+                    xs.push(ShapeHit::at(t));
+                    touched = t;
+                    near = true;
+                }
+            } else {
+                if near && d.signum() == side {
+                    xs.push(ShapeHit::at(touched));
+                }
+                near = false;
+                side = d.signum();
+            }
+            t += d.abs().max(HIT_EPSILON) / length;
+        }
+        // leaving the bounds from inside the shape is a crossing as well
+        if near && side > 0.0 {
+            xs.push(ShapeHit::at(touched));
+        }
+    }
+
+    // the gradient of the distance
+    pub fn normal_at(&self, p: &Tuple) -> Tuple {
+        let h = NORMAL_EPSILON;
+        let d = |x: f64, y: f64, z: f64| self.distance(&point(p.x + x, p.y + y, p.z + z));
+        vector(
+            d(h, 0.0, 0.0) - d(-h, 0.0, 0.0),
+            d(0.0, h, 0.0) - d(0.0, -h, 0.0),
+            d(0.0, 0.0, h) - d(0.0, 0.0, -h),
+        )
+        .normalize()
+    }
+}
+
+// the polynomial smooth minimum, it differs from min by at most k/4
+fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    if k <= 0.0 {
+        return a.min(b);
+    }
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+    b + (a - b) * h - k * h * (1.0 - h)
+}
+
+fn cube(x: f64, y: f64, z: f64) -> AABB {
+    AABB {
+        min: point(-x, -y, -z),
+        max: point(x, y, z),
+    }
+}
+
+fn union(a: &AABB, b: &AABB) -> AABB {
+    AABB {
+        min: point(
+            a.min.x.min(b.min.x),
+            a.min.y.min(b.min.y),
+            a.min.z.min(b.min.z),
+        ),
+        max: point(
+            a.max.x.max(b.max.x),
+            a.max.y.max(b.max.y),
+            a.max.z.max(b.max.z),
+        ),
+    }
+}
+
+fn intersection(a: &AABB, b: &AABB) -> AABB {
+    AABB {
+        min: point(
+            a.min.x.max(b.min.x),
+            a.min.y.max(b.min.y),
+            a.min.z.max(b.min.z),
+        ),
+        max: point(
+            a.max.x.min(b.max.x),
+            a.max.y.min(b.max.y),
+            a.max.z.min(b.max.z),
+        ),
+    }
+}
+
+fn grow(b: &AABB, by: f64) -> AABB {
+    AABB {
+        min: point(b.min.x - by, b.min.y - by, b.min.z - by),
+        max: point(b.max.x + by, b.max.y + by, b.max.z + by),
+    }
+}
+
+fn slab(min: f64, max: f64, origin: f64, direction: f64) -> (f64, f64) {
+    if direction == 0.0 {
+        if origin < min || origin > max {
+            return (f64::INFINITY, f64::NEG_INFINITY);
+        }
+        return (f64::NEG_INFINITY, f64::INFINITY);
+    }
+    let t1 = (min - origin) / direction;
+    let t2 = (max - origin) / direction;
+    (t1.min(t2), t1.max(t2))
+}
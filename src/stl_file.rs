@@ -0,0 +1,164 @@
+// Stereolithography meshes: a flat list of triangles with facet normals,
+// stored either as text or as an 80 byte header, a triangle count and 50
+// bytes per triangle. Facet normals are recomputed from the vertices.
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::Path,
+};
+
+use crate::{
+    groups::Group,
+    objects::{triangle, Shape},
+    tuples::{cross, point, vector, Tuple},
+};
+
+const HEADER_SIZE: usize = 80;
+const TRIANGLE_SIZE: usize = 50;
+
+pub fn load_stl_file(path: &Path) -> Result<Group> {
+    parse_stl_file(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn parse_stl_file(reader: &mut dyn Read) -> Result<Group> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    // binary headers may start with "solid" as well, so the size decides first
+    let binary_count = data
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize);
+    let triangles = match binary_count {
+        Some(count) if data.len() == HEADER_SIZE + 4 + count * TRIANGLE_SIZE => binary(&data)?,
+        _ if data.starts_with(b"solid") => ascii(&data)?,
+        Some(_) => return Err(invalid_data("stl file is truncated")),
+        None => return Err(invalid_data("not an stl file")),
+    };
+
+    let mut group = Group::default();
+    for [p1, p2, p3] in triangles {
+        group.add_object(triangle(p1, p2, p3));
+    }
+    Ok(group)
+}
+
+fn binary(data: &[u8]) -> Result<Vec<[Tuple; 3]>> {
+    let float =
+        |offset: usize| f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as f64;
+    let vertex = |offset: usize| point(float(offset), float(offset + 4), float(offset + 8));
+
+    let start = HEADER_SIZE + 4;
+    let count = (data.len() - start) / TRIANGLE_SIZE;
+    // each triangle is a normal, three vertices and an unused attribute
+    let triangles = (0..count)
+        .map(|i| start + i * TRIANGLE_SIZE + 12)
+        .map(|offset| [vertex(offset), vertex(offset + 12), vertex(offset + 24)])
+        .collect();
+    Ok(triangles)
+}
+
+fn ascii(data: &[u8]) -> Result<Vec<[Tuple; 3]>> {
+    let text = String::from_utf8_lossy(data);
+    let mut triangles = Vec::new();
+    let mut vertices = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            ["vertex", x, y, z] => {
+                let coordinate = |field: &str| {
+                    field.parse::<f64>().map_err(|_| {
+                        invalid_data(format!("line {}: invalid number '{}'", number + 1, field))
+                    })
+                };
+                vertices.push(point(coordinate(x)?, coordinate(y)?, coordinate(z)?));
+            }
+            ["endfacet"] => {
+                let facet: [Tuple; 3] = std::mem::take(&mut vertices).try_into().map_err(|_| {
+                    invalid_data(format!("line {}: a facet needs 3 vertices", number + 1))
+                })?;
+                triangles.push(facet);
+            }
+            // solid, facet normal, outer loop, endloop and endsolid carry
+            // nothing that is not recomputed
+            _ => {}
+        }
+    }
+    Ok(triangles)
+}
+
+// the triangles of the group with their transformations applied
+fn triangles(group: &Group) -> Vec<[Tuple; 3]> {
+    let mut triangles = Vec::new();
+    for object in group.objects() {
+        let corners = match &object.shape {
+            Shape::Triangle(t) => [&t.p1, &t.p2, &t.p3],
+            Shape::SmoothTriangle(t) => [&t.p1, &t.p2, &t.p3],
+            Shape::Mesh(m) => {
+                for face in 0..m.len() {
+                    triangles.push(m.corners(face).map(|p| object.transform() * p));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        triangles.push(corners.map(|p| object.transform() * p));
+    }
+    triangles
+}
+
+// counter clockwise vertices face the normal, as in the right handed
+// coordinates of CAD tools
+fn facet_normal(corners: &[Tuple; 3]) -> Tuple {
+    let normal = cross(&(&corners[1] - &corners[0]), &(&corners[2] - &corners[0]));
+    if normal.magnitude() == 0.0 {
+        return vector(0.0, 0.0, 0.0);
+    }
+    normal.normalize()
+}
+
+pub fn save_stl_file(group: &Group, path: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_stl(group, &mut writer)?;
+    writer.flush()
+}
+
+pub fn write_stl(group: &Group, writer: &mut dyn Write) -> Result<()> {
+    let triangles = triangles(group);
+    let mut header = [0u8; HEADER_SIZE];
+    let title = b"binary stl written by lab-raytracing-rs";
+    header[..title.len()].copy_from_slice(title);
+    writer.write_all(&header)?;
+    writer.write_all(&(triangles.len() as u32).to_le_bytes())?;
+
+    let mut record = Vec::with_capacity(TRIANGLE_SIZE);
+    for corners in &triangles {
+        record.clear();
+        for t in std::iter::once(&facet_normal(corners)).chain(corners) {
+            for value in [t.x, t.y, t.z] {
+                record.extend_from_slice(&(value as f32).to_le_bytes());
+            }
+        }
+        record.extend_from_slice(&[0, 0]);
+        writer.write_all(&record)?;
+    }
+    Ok(())
+}
+
+pub fn write_stl_ascii(group: &Group, writer: &mut dyn Write, name: &str) -> Result<()> {
+    writeln!(writer, "solid {}", name)?;
+    for corners in &triangles(group) {
+        let n = facet_normal(corners);
+        writeln!(writer, "  facet normal {} {} {}", n.x, n.y, n.z)?;
+        writeln!(writer, "    outer loop")?;
+        for p in corners {
+            writeln!(writer, "      vertex {} {} {}", p.x, p.y, p.z)?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid {}", name)
+}
+
+fn invalid_data(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
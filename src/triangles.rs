@@ -1,4 +1,7 @@
-use crate::tuples::{cross, Tuple};
+use crate::{
+    rays::Ray,
+    tuples::{cross, dot, Tuple},
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Triangle {
@@ -8,6 +11,8 @@ pub struct Triangle {
     pub e1: Tuple,
     pub e2: Tuple,
     pub normal: Tuple,
+    // texture coordinates of the three corners
+    pub uvs: Option<[(f64, f64); 3]>,
 }
 
 impl Triangle {
@@ -22,6 +27,7 @@ impl Triangle {
             e1,
             e2,
             normal,
+            uvs: None,
         }
     }
 }
@@ -37,6 +43,8 @@ pub struct SmoothTriangle {
     pub e1: Tuple,
     pub e2: Tuple,
     pub normal: Tuple,
+    // texture coordinates of the three corners
+    pub uvs: Option<[(f64, f64); 3]>,
 }
 
 impl SmoothTriangle {
@@ -54,6 +62,52 @@ impl SmoothTriangle {
             e1,
             e2,
             normal,
+            uvs: None,
         }
     }
 }
+
+// interpolates the corner texture coordinates with the barycentric u and v of
+// a hit, which are used directly when the corners have none
+pub fn texture_uv(uvs: &Option<[(f64, f64); 3]>, hit_uv: &(f64, f64)) -> (f64, f64) {
+    match uvs {
+        Some([a, b, c]) => {
+            let (u, v) = *hit_uv;
+            let w = 1.0 - u - v;
+            (a.0 * w + b.0 * u + c.0 * v, a.1 * w + b.1 * u + c.1 * v)
+        }
+        None => *hit_uv,
+    }
+}
+
+// the t of the hit with the triangle at p1 with the edges e1 and e2, and the
+// barycentric u and v of the hit
+pub fn intersect_triangle(
+    ray: &Ray,
+    p1: &Tuple,
+    e1: &Tuple,
+    e2: &Tuple,
+) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = cross(&ray.direction, e2);
+    let det = dot(e1, &dir_cross_e2);
+
+    if det.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = &ray.origin - p1;
+    let u = f * dot(&p1_to_origin, &dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = cross(&p1_to_origin, e1);
+    let v = f * dot(&ray.direction, &origin_cross_e1);
+    if v < 0.0 || (u + v) > 1.0 {
+        return None;
+    }
+
+    let t = f * dot(e2, &origin_cross_e1);
+    Some((t, u, v))
+}
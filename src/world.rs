@@ -1,66 +1,112 @@
+use crate::bvh::{self, BvhStats, LinearBvh};
 use crate::csg::CSG;
 use crate::groups::{Group, GroupMember};
-use crate::intersections::Intersection;
+use crate::instances::Instance;
+use crate::intersections::{Hit, Intersection, HIT_EPSILON};
 use crate::lights::Pointlight;
 use crate::objects::Object;
 use crate::rays::Ray;
 use crate::tuples::Tuple;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 #[derive(Debug)]
 pub struct World {
-    pub objects: Vec<GroupMember>,
+    // the bounding volume hierarchy is built from these on the first
+    // intersection and kept until they change
+    objects: Vec<GroupMember>,
     pub light: Option<Pointlight>,
+    linear_bvh: OnceLock<LinearBvh>,
 }
 
 impl World {
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         World {
             objects: Vec::new(),
             light: None,
+            linear_bvh: OnceLock::new(),
         }
     }
 
     pub fn add_object(&mut self, obj: Object) {
         self.objects.push(GroupMember::Object(Arc::new(obj)));
+        self.objects_changed();
     }
 
     pub fn add_group(&mut self, obj: Group) {
         self.objects.push(GroupMember::SubGroup(Arc::new(obj)));
+        self.objects_changed();
+    }
+
+    pub fn add_instance(&mut self, instance: Instance) {
+        self.objects.push(GroupMember::Instance(Arc::new(instance)));
+        self.objects_changed();
     }
 
     pub fn add_csg(&mut self, csg: CSG) {
         self.objects.push(GroupMember::CSG(Arc::new(csg)));
+        self.objects_changed();
+    }
+
+    // sorts the objects into a bounding volume hierarchy, planes stay in front.
+    // The first intersection does the same on its own, this only makes the
+    // hierarchy visible in the objects and tells how it turned out.
+    pub fn build_bvh(&mut self) -> BvhStats {
+        let (objects, stats) = bvh::build(std::mem::take(&mut self.objects));
+        self.objects = objects;
+        self.linear_bvh = OnceLock::from(LinearBvh::new(&self.objects));
+        stats
+    }
+
+    pub fn objects(&self) -> &[GroupMember] {
+        &self.objects
+    }
+
+    // the hierarchy is built again after changes through the returned list
+    pub fn objects_mut(&mut self) -> &mut Vec<GroupMember> {
+        self.objects_changed();
+        &mut self.objects
+    }
+
+    fn objects_changed(&mut self) {
+        self.linear_bvh = OnceLock::new();
+    }
+
+    pub fn linear_bvh(&self) -> &LinearBvh {
+        self.linear_bvh.get_or_init(|| {
+            let (objects, _) = bvh::build(self.objects.clone());
+            LinearBvh::new(&objects)
+        })
     }
 
     pub fn insersect(&self, ray: &Ray) -> Vec<Intersection> {
-        let mut v = Vec::with_capacity(self.objects.len());
-        for obj in self.objects.iter() {
-            let mut intersections = obj.intersect(ray);
-            v.append(&mut intersections);
-        }
+        let mut hits = Vec::new();
+        self.intersect_into(ray, &mut hits);
+        let mut v: Vec<Intersection> = hits.iter().map(Hit::to_intersection).collect();
         v.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
         v
     }
 
+    // appends the hits inside the interval of the ray unsorted, they borrow
+    // the objects from the world
+    pub fn intersect_into<'a>(&'a self, ray: &Ray, xs: &mut Vec<Hit<'a>>) {
+        self.linear_bvh().intersect(ray, xs)
+    }
+
+    // the hit of the ray inside its interval without collecting and sorting
+    // every intersection, boxes behind the nearest hit so far are skipped
+    pub fn closest_hit(&self, ray: &Ray) -> Option<Intersection> {
+        let ray = ray.clone().between(ray.t_min.max(HIT_EPSILON), ray.t_max);
+        self.linear_bvh().closest_hit(&ray)
+    }
+
+    // any hit between the point and the light is enough, the search ends
+    // there instead of sorting everything along the way
     pub fn is_shadowed(&self, point: Tuple) -> bool {
         let v = &self.light.as_ref().unwrap().position - &point;
         let distance = v.magnitude();
         let direction = v.normalize();
-        let r = Ray::new(point, direction);
-
-        for i in self.insersect(&r).iter() {
-            if i.t < 0.0001 {
-                continue;
-            }
-            if i.t > distance {
-                continue;
-            }
-            if i.object.throws_shaddow {
-                return true;
-            }
-        }
-
-        false
+        let r = Ray::new(point, direction).between(HIT_EPSILON, distance);
+        self.linear_bvh().any_hit(&r, |o| o.throws_shaddow)
     }
 }
@@ -1,19 +1,23 @@
 use async_trait::async_trait;
 use cucumber::WorldInit;
+use lab_raytracing_rs::bvh::BvhStats;
 use lab_raytracing_rs::camera::Camera;
 use lab_raytracing_rs::canvas::Canvas;
 use lab_raytracing_rs::csg::CSG;
 use lab_raytracing_rs::groups::{Group, GroupMember};
+use lab_raytracing_rs::instances::Instance;
 use lab_raytracing_rs::intersections::{
     prepare_computations, Intersection, IntersectionPrecomputations,
 };
 use lab_raytracing_rs::lights::Pointlight;
 use lab_raytracing_rs::materials::Material;
 use lab_raytracing_rs::matrices::{identity_matrix, Matrix2x2, Matrix3x3, Matrix4x4};
-use lab_raytracing_rs::obj_file::Parser;
+use lab_raytracing_rs::obj_file::{ObjError, Parser};
 use lab_raytracing_rs::objects::{default_cube, default_sphere, Object};
+use lab_raytracing_rs::patches::BezierPatch;
 use lab_raytracing_rs::patterns::{test_pattern, Pattern};
 use lab_raytracing_rs::rays::Ray;
+use lab_raytracing_rs::scene_file::SceneError;
 use lab_raytracing_rs::tuples::{color, point, vector, Tuple};
 use lab_raytracing_rs::world::World;
 use std::collections::HashMap;
@@ -31,6 +35,7 @@ pub struct MyWorld {
     image: Canvas,
     camera: Camera,
     ppm: String,
+    image_file: Vec<u8>,
     in_shadow: bool,
     matrices: HashMap<String, Matrix>,
     intersections: HashMap<String, Intersection>,
@@ -50,7 +55,14 @@ pub struct MyWorld {
     g1: Group,
     g2: Group,
     csg: CSG,
+    instances: HashMap<String, Instance>,
+    patches: Vec<BezierPatch>,
+    bvh_stats: Option<BvhStats>,
     result: bool,
+    scene_error: Option<SceneError>,
+    obj_error: Option<ObjError>,
+    load_error: Option<String>,
+    roots: Vec<f64>,
 }
 
 #[derive(Debug)]
@@ -73,17 +85,12 @@ impl cucumber::World for MyWorld {
             canvas: Canvas::new(0, 0),
             image: Canvas::new(0, 0),
             ppm: "".to_string(),
+            image_file: Vec::new(),
             in_shadow: true,
             matrices: HashMap::new(),
             intersections: HashMap::new(),
-            r: Ray {
-                origin: point(0.0, 0.0, 0.0),
-                direction: vector(1.0, 1.0, 1.0),
-            },
-            r2: Ray {
-                origin: point(0.0, 0.0, 0.0),
-                direction: vector(1.0, 1.0, 1.0),
-            },
+            r: Ray::new(point(0.0, 0.0, 0.0), vector(1.0, 1.0, 1.0)),
+            r2: Ray::new(point(0.0, 0.0, 0.0), vector(1.0, 1.0, 1.0)),
             objects: HashMap::new(),
             files: HashMap::new(),
             parser: Parser::new(),
@@ -98,11 +105,10 @@ impl cucumber::World for MyWorld {
                     object: Arc::new(default_sphere()),
                     u: 0.0,
                     v: 0.0,
+                    face: 0,
+                    instances: Vec::new(),
                 },
-                &Ray {
-                    origin: point(2.0, 0.0, 0.0),
-                    direction: vector(1.0, 0.0, 0.0),
-                },
+                &Ray::new(point(2.0, 0.0, 0.0), vector(1.0, 0.0, 0.0)),
                 &Vec::new(),
             ),
             pattern: test_pattern(),
@@ -113,7 +119,14 @@ impl cucumber::World for MyWorld {
                 GroupMember::Object(Arc::new(default_sphere())),
                 GroupMember::Object(Arc::new(default_cube())),
             ),
+            instances: HashMap::new(),
+            patches: Vec::new(),
+            bvh_stats: None,
             result: true,
+            scene_error: None,
+            obj_error: None,
+            load_error: None,
+            roots: Vec::new(),
         };
         world.insert4x4("identity_matrix".to_string(), identity_matrix());
         Ok(world)
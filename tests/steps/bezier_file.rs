@@ -0,0 +1,42 @@
+use crate::{
+    steps::tuples::{eq_tuples_similar, parse_point},
+    MyWorld,
+};
+use cucumber::{then, when};
+use lab_raytracing_rs::bezier_file::parse_bezier_file;
+
+#[when("patches ← parse_bezier_file(file)")]
+async fn parse_bezier(world: &mut MyWorld) {
+    let content = world.files.get("file").unwrap();
+    match parse_bezier_file(&mut content.as_bytes()) {
+        Ok(patches) => {
+            world.patches = patches;
+            world.load_error = None;
+        }
+        Err(err) => world.load_error = Some(err.to_string()),
+    }
+}
+
+#[then(regex = r"^patches.count = ([0-9]+)$")]
+async fn compare_patch_count(world: &mut MyWorld, desired: usize) {
+    assert_eq!(world.patches.len(), desired);
+}
+
+#[then(
+    regex = r"^patches\[([0-9]+)\].points\[([0-9]+)\] = point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_control_point(
+    world: &mut MyWorld,
+    patch: usize,
+    index: usize,
+    x: String,
+    y: String,
+    z: String,
+) {
+    let value = &world.patches[patch].points[index];
+    assert!(
+        eq_tuples_similar(value, &parse_point(&[x, y, z])),
+        "{:?}",
+        value
+    );
+}
@@ -0,0 +1,170 @@
+use crate::MyWorld;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{
+    groups::{Group, GroupMember},
+    intersections::HIT_EPSILON,
+    objects::{default_plane, default_sphere, Shape},
+    transformations::translation,
+};
+use std::sync::Arc;
+
+// the first sphere is one spacing away from the origin
+#[given(regex = r"^([0-9]+) spheres in a row ([0-9.]+) apart are added to (w|g)$")]
+async fn add_row_of_spheres(world: &mut MyWorld, count: usize, spacing: f64, target: String) {
+    for i in 1..=count {
+        let mut sphere = default_sphere();
+        sphere.set_transform(translation(spacing * i as f64, 0.0, 0.0));
+        match target.as_str() {
+            "w" => world.w.add_object(sphere),
+            _ => world.g.add_object(sphere),
+        }
+    }
+}
+
+#[given("a plane is added to w")]
+async fn add_plane(world: &mut MyWorld) {
+    world.w.add_object(default_plane());
+}
+
+#[given(regex = r"^(g|csg) is added to w$")]
+async fn add_to_world(world: &mut MyWorld, name: String) {
+    match name.as_str() {
+        "g" => world.w.add_group(world.g.clone()),
+        _ => world.w.add_csg(world.csg.clone()),
+    }
+}
+
+#[when("stats ← build_bvh(w)")]
+async fn build_world_bvh(world: &mut MyWorld) {
+    world.bvh_stats = Some(world.w.build_bvh());
+}
+
+#[then(
+    regex = r"^stats.(trees|members|unbounded|nodes|leaves|depth|min_leaf|max_leaf) = ([0-9]+)$"
+)]
+async fn compare_stats_count(world: &mut MyWorld, field: String, desired: usize) {
+    let stats = world.bvh_stats.as_ref().unwrap();
+    let value = match field.as_str() {
+        "trees" => stats.trees,
+        "members" => stats.members,
+        "unbounded" => stats.unbounded,
+        "nodes" => stats.nodes,
+        "leaves" => stats.leaves,
+        "depth" => stats.depth,
+        "min_leaf" => stats.min_leaf,
+        _ => stats.max_leaf,
+    };
+    assert_eq!(value, desired, "{}", stats);
+}
+
+#[then(regex = r"^stats.cost = ([0-9.]+)$")]
+async fn compare_stats_cost(world: &mut MyWorld, desired: f64) {
+    let stats = world.bvh_stats.as_ref().unwrap();
+    assert!((stats.cost - desired).abs() < 0.0001, "{}", stats);
+}
+
+#[then("member 1 of w is a plane")]
+async fn first_member_is_plane(world: &mut MyWorld) {
+    match &world.w.objects()[0] {
+        GroupMember::Object(o) => assert_eq!(o.shape, Shape::Plane),
+        _ => panic!("not an object"),
+    }
+}
+
+#[then(regex = r"^member 1 of w is a group of ([0-9]+) with the transform of g$")]
+async fn first_member_is_group(world: &mut MyWorld, desired: usize) {
+    match &world.w.objects()[0] {
+        GroupMember::SubGroup(g) => {
+            assert_eq!(g.len(), desired);
+            assert_eq!(g.transform(), world.g.transform());
+        }
+        _ => panic!("not a group"),
+    }
+}
+
+#[then("w holds the csg whole")]
+async fn world_holds_csg(world: &mut MyWorld) {
+    fn holds(members: &[GroupMember], csg: &GroupMember) -> bool {
+        members.iter().any(|m| match m {
+            GroupMember::SubGroup(g) => holds(g.members(), csg),
+            m => m == csg,
+        })
+    }
+    let csg = GroupMember::CSG(Arc::new(world.csg.clone()));
+    assert!(holds(world.w.objects(), &csg));
+}
+
+#[then(regex = r"^the instances in w share a group of ([0-9]+)$")]
+async fn instances_share_group(world: &mut MyWorld, desired: usize) {
+    fn groups(members: &[GroupMember]) -> Vec<Arc<Group>> {
+        members
+            .iter()
+            .flat_map(|m| match m {
+                GroupMember::SubGroup(g) => groups(g.members()),
+                GroupMember::Instance(i) => match i.member() {
+                    GroupMember::SubGroup(g) => vec![g.clone()],
+                    _ => vec![],
+                },
+                _ => vec![],
+            })
+            .collect()
+    }
+    let groups = groups(world.w.objects());
+    assert_eq!(groups.len(), 2);
+    assert!(Arc::ptr_eq(&groups[0], &groups[1]));
+    assert_eq!(groups[0].len(), desired);
+}
+
+#[then(regex = r"^w is laid out in ([0-9]+) nodes? over ([0-9]+) members$")]
+async fn compare_layout(world: &mut MyWorld, nodes: usize, members: usize) {
+    let bvh = world.w.linear_bvh();
+    assert_eq!(bvh.nodes(), nodes);
+    assert_eq!(bvh.members().len(), members);
+}
+
+#[then(regex = r"^the leaves of w along r begin at x = (.*)$")]
+async fn compare_leaf_order(world: &mut MyWorld, desired: String) {
+    let desired: Vec<f64> = desired.split(", ").map(|x| x.parse().unwrap()).collect();
+    let mut leaves = Vec::new();
+    world.w.linear_bvh().traverse(&world.r, |members| {
+        let min = members
+            .iter()
+            .map(|m| m.bounds().unwrap().min.x)
+            .fold(f64::INFINITY, f64::min);
+        leaves.push(min);
+        world.r.t_max
+    });
+    assert_eq!(leaves, desired);
+}
+
+// an any hit query ends the traversal at the first leaf with a hit
+#[then(regex = r"^the leaves of w along r up to (the first|any) hit begin at x = (.*)$")]
+async fn compare_pruned_leaves(world: &mut MyWorld, query: String, desired: String) {
+    let desired: Vec<f64> = desired.split(", ").map(|x| x.parse().unwrap()).collect();
+    let ray = world.r.clone().between(HIT_EPSILON, f64::INFINITY);
+    let mut leaves = Vec::new();
+    world.w.linear_bvh().traverse(&ray, |members| {
+        let min = members
+            .iter()
+            .map(|m| m.bounds().unwrap().min.x)
+            .fold(f64::INFINITY, f64::min);
+        leaves.push(min);
+        let t = members
+            .iter()
+            .flat_map(|m| m.intersect(&ray))
+            .map(|i| i.t)
+            .filter(|t| *t >= HIT_EPSILON)
+            .fold(f64::INFINITY, f64::min);
+        match query.as_str() {
+            "any" if t.is_finite() => f64::NEG_INFINITY,
+            _ => t,
+        }
+    });
+    assert_eq!(leaves, desired);
+}
+
+#[then(regex = r"^any_hit\(w, r\) is (true|false)$")]
+async fn compare_any_hit(world: &mut MyWorld, desired: bool) {
+    let ray = world.r.clone().between(HIT_EPSILON, f64::INFINITY);
+    assert_eq!(world.w.linear_bvh().any_hit(&ray, |_| true), desired);
+}
@@ -2,7 +2,7 @@ use crate::MyWorld;
 use approx::assert_abs_diff_eq;
 use cucumber::{given, then, when};
 use lab_raytracing_rs::{
-    camera::Camera,
+    camera::{AntiAliasing, Camera, Renderer},
     transformations::{rotation_y, translation, view_transform},
 };
 use std::f64::consts::PI;
@@ -71,3 +71,41 @@ async fn set_camera_transformation(world: &mut MyWorld) {
 async fn render_image(world: &mut MyWorld) {
     world.image = world.camera.render(&world.w);
 }
+
+#[when(regex = r#"^antialiasing ← parse_antialiasing\("(\w+)"\)$"#)]
+async fn parse_antialiasing(world: &mut MyWorld, value: String) {
+    world.camera.antialiasing = AntiAliasing::parse(&value).unwrap();
+}
+
+#[then(regex = r"^antialiasing = (off|fast|dynamic|raster\(([0-9]+)\))$")]
+async fn compare_antialiasing(world: &mut MyWorld, mode: String, raster: String) {
+    let desired = match mode.as_str() {
+        "off" => AntiAliasing::Off,
+        "fast" => AntiAliasing::Fast,
+        "dynamic" => AntiAliasing::Dynamic,
+        _ => AntiAliasing::Raster(raster.parse().unwrap()),
+    };
+    assert_eq!(world.camera.antialiasing, desired);
+}
+
+#[when(regex = r#"^renderer ← parse_renderer\("(\w+)"\)$"#)]
+async fn parse_renderer(world: &mut MyWorld, value: String) {
+    world.camera.renderer = Renderer::parse(&value).unwrap();
+}
+
+#[then(regex = r"^renderer = (single threaded|multithreaded\(([0-9]+)\))$")]
+async fn compare_renderer(world: &mut MyWorld, renderer: String, cores: String) {
+    let desired = match renderer.as_str() {
+        "single threaded" => Renderer::SingleThreaded,
+        _ => Renderer::Multithreaded(cores.parse().unwrap()),
+    };
+    assert_eq!(world.camera.renderer, desired);
+}
+
+#[then(regex = r#"^parsing "([-\w]+)" as (antialiasing|renderer) fails$"#)]
+async fn parse_setting_fails(_world: &mut MyWorld, value: String, setting: String) {
+    match setting.as_str() {
+        "antialiasing" => assert!(AntiAliasing::parse(&value).is_err()),
+        _ => assert!(Renderer::parse(&value).is_err()),
+    }
+}
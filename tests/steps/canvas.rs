@@ -1,11 +1,23 @@
 use super::tuples::{eq_tuples_similar, parse_color};
 use crate::MyWorld;
+use approx::assert_abs_diff_eq;
 use cucumber::{gherkin::Step, given, then, when};
-use lab_raytracing_rs::{canvas::Canvas, tuples::color};
+use lab_raytracing_rs::{
+    canvas::{BitDepth, Canvas, PngFormat},
+    tuples::color,
+};
+use std::path::Path;
 
-#[given(regex = r"^c ← canvas\(([0-9]+), ([0-9]+)\)$")]
-async fn create_canvas(world: &mut MyWorld, w: usize, h: usize) {
-    world.canvas = Canvas::new(w, h);
+#[given(regex = r"^(c|image) ← canvas\(([0-9]+), ([0-9]+)\)$")]
+async fn create_canvas(world: &mut MyWorld, target: String, w: usize, h: usize) {
+    *canvas_mut(world, &target) = Canvas::new(w, h);
+}
+
+fn canvas_mut<'a>(world: &'a mut MyWorld, name: &str) -> &'a mut Canvas {
+    match name {
+        "c" => &mut world.canvas,
+        _ => &mut world.image,
+    }
 }
 
 #[then(regex = r"^c.(width|height) = ([0-9]+)$")]
@@ -28,23 +40,27 @@ async fn every_pixel_is_colored(world: &mut MyWorld, x: String, y: String, z: St
     }
 }
 
-#[when(regex = r"^every pixel of c is set to color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$")]
-async fn color_every_pixel(world: &mut MyWorld, x: String, y: String, z: String) {
+#[when(regex = r"^every pixel of (c|image) is set to color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$")]
+async fn color_every_pixel(world: &mut MyWorld, target: String, x: String, y: String, z: String) {
     let color = parse_color(&[x, y, z]);
-    world.canvas.fill(color);
+    canvas_mut(world, &target).fill(color);
 }
 
-#[when(regex = r"^write_pixel\(c, ([0-9]+), ([0-9]+), (\w+)\)$")]
-async fn write_pixel(world: &mut MyWorld, w: usize, h: usize, color: String) {
+#[when(regex = r"^write_pixel\((c|image), ([0-9]+), ([0-9]+), (\w+)\)$")]
+async fn write_pixel(world: &mut MyWorld, target: String, w: usize, h: usize, color: String) {
     let color = world.tuples.get(&color).unwrap().clone();
-    world.canvas.set(w, h, color);
+    canvas_mut(world, &target).set(w, h, color);
 }
 
-#[then(regex = r"^pixel_at\(c, ([-0-9.]+), ([-0-9.]+)\) = (red)$")]
-async fn compare_pixel(world: &mut MyWorld, w: usize, h: usize, desired: String) {
-    let color = world.canvas.at(w, h);
+#[then(regex = r"^pixel_at\((c|image), ([-0-9.]+), ([-0-9.]+)\) = (red|black|c1|c2|c3)$")]
+async fn compare_pixel(world: &mut MyWorld, canvas: String, w: usize, h: usize, desired: String) {
+    let canvas = match canvas.as_str() {
+        "c" => &world.canvas,
+        _ => &world.image,
+    };
+    let color = canvas.at(w, h);
     let desired = world.tuples.get(&desired).unwrap();
-    eq_tuples_similar(color, desired);
+    assert!(eq_tuples_similar(color, desired));
 }
 
 #[then(
@@ -90,3 +106,141 @@ async fn compare_ppm_lines(world: &mut MyWorld, beginning: usize, end: usize, st
 async fn compare_ppm_end(world: &mut MyWorld) {
     assert_eq!('\n', world.ppm.chars().last().unwrap());
 }
+
+#[when(regex = r"^write_alpha\(c, ([0-9]+), ([0-9]+), ([0-9.]+)\)$")]
+async fn write_alpha(world: &mut MyWorld, w: usize, h: usize, alpha: f64) {
+    world.canvas.set_alpha(w, h, alpha);
+}
+
+#[when(regex = r"^png ← canvas_to_png\(c(?:, (8|16) bits, alpha)?\)$")]
+async fn canvas_to_png(world: &mut MyWorld, depth: String) {
+    let mut png = Vec::new();
+    match depth.as_str() {
+        "" => world.canvas.png(&mut png),
+        _ => {
+            let format = PngFormat {
+                depth: match depth.as_str() {
+                    "8" => BitDepth::Eight,
+                    _ => BitDepth::Sixteen,
+                },
+                alpha: true,
+            };
+            world.canvas.png_with(&mut png, &format)
+        }
+    }
+    .expect("failed to write png");
+    world.image_file = png;
+}
+
+pub fn parse_bytes(step: &Step) -> Vec<u8> {
+    let hex = step.docstring.as_ref().unwrap();
+    hex.split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16).unwrap())
+        .collect()
+}
+
+#[then(regex = r"^bytes ([0-9]+)-([0-9]+) of (?:png|ppm|pfm|hdr|stl) are$")]
+async fn compare_png_bytes(world: &mut MyWorld, beginning: usize, end: usize, step: &Step) {
+    assert_eq!(
+        &world.image_file[beginning - 1..end],
+        parse_bytes(step).as_slice()
+    );
+}
+
+#[then(regex = r"^(?:png|pfm|hdr) ends with$")]
+async fn compare_png_end(world: &mut MyWorld, step: &Step) {
+    let desired = parse_bytes(step);
+    assert!(world.image_file.ends_with(&desired));
+}
+
+#[when(regex = r"^ppm ← canvas_to_binary_ppm\(c, (8|16) bits\)$")]
+async fn canvas_to_binary_ppm(world: &mut MyWorld, depth: usize) {
+    let depth = match depth {
+        8 => BitDepth::Eight,
+        _ => BitDepth::Sixteen,
+    };
+    let mut bytes = Vec::new();
+    world
+        .canvas
+        .ppm_binary(&mut bytes, depth)
+        .expect("failed to write ppm");
+    world.image_file = bytes;
+}
+
+#[when(regex = r"^(pfm|hdr) ← canvas_to_(?:pfm|hdr)\(c\)$")]
+async fn canvas_to_float_image(world: &mut MyWorld, format: String) {
+    let mut bytes = Vec::new();
+    match format.as_str() {
+        "pfm" => world.canvas.pfm(&mut bytes),
+        _ => world.canvas.hdr(&mut bytes),
+    }
+    .expect("failed to write image");
+    world.image_file = bytes;
+}
+
+#[then(regex = r"^(?:ppm|pfm|hdr|stl) starts with$")]
+async fn compare_image_header(world: &mut MyWorld, step: &Step) {
+    let header = format!("{}\n", step.docstring.as_ref().unwrap().trim());
+    let start = &world.image_file[..header.len().min(world.image_file.len())];
+    assert_eq!(String::from_utf8_lossy(start), header);
+}
+
+#[when(regex = r"^image ← canvas_from_(ppm|pfm|hdr|png)\((?:ppm|pfm|hdr|png)\)$")]
+async fn canvas_from_image_file(world: &mut MyWorld, format: String) {
+    // files written by hand take precedence over the ones written by a canvas
+    let reader = &mut match world.files.get(&format) {
+        Some(content) => content.as_bytes(),
+        None => world.image_file.as_slice(),
+    };
+    world.image = match format.as_str() {
+        "ppm" => Canvas::from_ppm(reader),
+        "pfm" => Canvas::from_pfm(reader),
+        "png" => Canvas::from_png(reader),
+        _ => Canvas::from_hdr(reader),
+    }
+    .expect("failed to read image");
+}
+
+#[when(regex = r#"^image ← load_canvas\("(.*)"\)$"#)]
+async fn load_canvas(world: &mut MyWorld, path: String) {
+    let path = Path::new("./features/").join(path);
+    world.image = Canvas::load(&path).expect("failed to load image");
+}
+
+#[then(regex = r"^alpha_at\(image, ([0-9]+), ([0-9]+)\) = ([0-9.]+)$")]
+async fn compare_alpha(world: &mut MyWorld, w: usize, h: usize, desired: f64) {
+    assert_abs_diff_eq!(world.image.alpha_at(w, h), desired, epsilon = 0.0001);
+}
+
+#[then(regex = r"^image.(width|height) = ([0-9]+)$")]
+async fn compare_image_size(world: &mut MyWorld, attribute: String, desired: usize) {
+    let value = match attribute.as_str() {
+        "width" => world.image.width,
+        _ => world.image.height,
+    };
+    assert_eq!(value, desired);
+}
+
+#[then(regex = r"^every pixel of image equals c$")]
+async fn compare_images(world: &mut MyWorld) {
+    assert_eq!(world.image.pixels, world.canvas.pixels);
+}
+
+#[then(regex = r#"^reading "(.*)" as (ppm|pfm|hdr|png) fails with "(.*)"$"#)]
+async fn reading_image_fails(
+    _world: &mut MyWorld,
+    content: String,
+    format: String,
+    message: String,
+) {
+    let content = content.replace("\\n", "\n").replace("\\0", "\0");
+    let reader = &mut content.into_bytes();
+    let reader = &mut reader.as_slice();
+    let result = match format.as_str() {
+        "ppm" => Canvas::from_ppm(reader),
+        "pfm" => Canvas::from_pfm(reader),
+        "png" => Canvas::from_png(reader),
+        _ => Canvas::from_hdr(reader),
+    };
+    assert_eq!(result.unwrap_err().to_string(), message);
+}
@@ -0,0 +1,106 @@
+use crate::MyWorld;
+use approx::assert_abs_diff_eq;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{
+    camera::{AntiAliasing, Renderer},
+    canvas::{compare, Canvas},
+    scene_file::Scene,
+};
+use std::{env, fs, path::Path};
+
+#[when(regex = r"^the (left|right) half of (c|image) is set to (c1|c2)$")]
+async fn color_half(world: &mut MyWorld, half: String, target: String, color: String) {
+    let color = world.tuples.get(&color).unwrap().clone();
+    let canvas = match target.as_str() {
+        "c" => &mut world.canvas,
+        _ => &mut world.image,
+    };
+    let columns = match half.as_str() {
+        "left" => 0..canvas.width / 2,
+        _ => canvas.width / 2..canvas.width,
+    };
+    for h in 0..canvas.height {
+        for w in columns.clone() {
+            canvas.set(w, h, color.clone());
+        }
+    }
+}
+
+#[when(regex = r"^c ← (difference|heatmap)\(c, image\)$")]
+async fn compare_canvases(world: &mut MyWorld, operation: String) {
+    world.canvas = match operation.as_str() {
+        "difference" => compare::difference(&world.canvas, &world.image),
+        _ => compare::heatmap(&world.canvas, &world.image),
+    };
+}
+
+#[then(regex = r"^(rmse|psnr|ssim)\(c, image\) = ([-0-9.]+|infinity)$")]
+async fn compare_metric(world: &mut MyWorld, metric: String, desired: String) {
+    let value = metric_of(world, &metric);
+    match desired.as_str() {
+        "infinity" => assert_eq!(value, f64::INFINITY),
+        _ => assert_abs_diff_eq!(value, desired.parse::<f64>().unwrap(), epsilon = 0.0001),
+    }
+}
+
+#[then(regex = r"^(rmse|psnr|ssim)\(c, image\) (<|>) ([-0-9.]+)$")]
+async fn bound_metric(world: &mut MyWorld, metric: String, comparison: String, bound: f64) {
+    let value = metric_of(world, &metric);
+    match comparison.as_str() {
+        "<" => assert!(value < bound, "{} is {}", metric, value),
+        _ => assert!(value > bound, "{} is {}", metric, value),
+    }
+}
+
+fn metric_of(world: &MyWorld, metric: &str) -> f64 {
+    match metric {
+        "rmse" => compare::rmse(&world.canvas, &world.image),
+        "psnr" => compare::psnr(&world.canvas, &world.image),
+        _ => compare::ssim(&world.canvas, &world.image),
+    }
+}
+
+#[given(regex = r#"^w, c ← the scene file "([\w/\.-]+)" at ([0-9]+)x([0-9]+)$"#)]
+async fn load_scene(world: &mut MyWorld, path: String, hsize: usize, vsize: usize) {
+    let scene = Scene::load_scene_file(Path::new(&path)).unwrap();
+    world.w = scene.world;
+    world.camera = scene.camera;
+    world.camera.set_resolution(hsize, vsize);
+    world.camera.antialiasing = AntiAliasing::Off;
+    world.camera.renderer = Renderer::SingleThreaded;
+}
+
+#[then(
+    regex = r#"^image matches "([\w/\.-]+)" with rmse below ([0-9.]+) and ssim above ([0-9.]+)$"#
+)]
+async fn compare_golden_image(world: &mut MyWorld, path: String, rmse: f64, ssim: f64) {
+    let path = Path::new(&path);
+    if env::var("UPDATE_GOLDEN").is_ok() {
+        world.image.save(path).unwrap();
+        return;
+    }
+
+    let golden = Canvas::load(path).unwrap();
+    let comparison = compare::compare(&golden, &world.image);
+    if comparison.rmse < rmse && comparison.ssim > ssim {
+        return;
+    }
+
+    // keep the render and where it differs around for inspection
+    let output = Path::new("target/golden");
+    let name = path.file_stem().unwrap().to_str().unwrap();
+    fs::create_dir_all(output).unwrap();
+    let actual = output.join(format!("{}-actual.png", name));
+    let heatmap = output.join(format!("{}-heatmap.png", name));
+    world.image.save(&actual).unwrap();
+    compare::heatmap(&golden, &world.image)
+        .save(&heatmap)
+        .unwrap();
+    panic!(
+        "{} drifted from its golden image: {:?}, see {} and {}",
+        path.display(),
+        comparison,
+        actual.display(),
+        heatmap.display()
+    );
+}
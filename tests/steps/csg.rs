@@ -17,12 +17,32 @@ async fn create_csg_union(
 ) {
     let shape_1 = GroupMember::Object(world.objects.get(&shape_1).unwrap().clone());
     let shape_2 = GroupMember::Object(world.objects.get(&shape_2).unwrap().clone());
-    world.csg = match operation.as_str() {
-        "union" => CSG::Union(shape_1, shape_2),
-        "difference" => CSG::Difference(shape_1, shape_2),
-        "intersection" => CSG::Intersection(shape_1, shape_2),
+    world.csg = csg(&operation, shape_1, shape_2);
+}
+
+#[given(regex = r#"^csg ← csg\("(union|difference|intersection)", (i1), (i2)\)$"#)]
+async fn create_csg_of_instances(
+    world: &mut MyWorld,
+    operation: String,
+    instance_1: String,
+    instance_2: String,
+) {
+    let instance_1 = world.instances.get(&instance_1).unwrap().clone();
+    let instance_2 = world.instances.get(&instance_2).unwrap().clone();
+    world.csg = csg(
+        &operation,
+        GroupMember::Instance(Arc::new(instance_1)),
+        GroupMember::Instance(Arc::new(instance_2)),
+    );
+}
+
+fn csg(operation: &str, left: GroupMember, right: GroupMember) -> CSG {
+    match operation {
+        "union" => CSG::Union(left, right),
+        "difference" => CSG::Difference(left, right),
+        "intersection" => CSG::Intersection(left, right),
         _ => panic!("operation not covered"),
-    };
+    }
 }
 
 #[given("csg ← csg(\"union\", sphere(), cube())")]
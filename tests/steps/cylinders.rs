@@ -61,6 +61,8 @@ async fn assign_cylinder_attribute(
         },
         _ => panic!("expected shape of kind cylinder"),
     };
+    // the bounds follow the new shape
+    obj.set_transform(obj.transform().clone());
     world.objects.insert(shape, Arc::new(obj));
 }
 
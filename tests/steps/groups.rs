@@ -82,6 +82,7 @@ async fn intersect_group(world: &mut MyWorld) {
     world.xs = world.g.intersect(&world.r);
 }
 
+#[given(regex = r"^(t1|t2|t3) ← (first|second|third) child of (g|g1|g2)$")]
 #[when(regex = r"^(t1|t2|t3) ← (first|second|third) child of (g|g1|g2)$")]
 async fn assign_object_from_group(
     world: &mut MyWorld,
@@ -0,0 +1,47 @@
+use crate::MyWorld;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{
+    heightfields::Heightfield,
+    objects::{heightfield, Shape},
+};
+use std::sync::Arc;
+
+#[given(regex = r"^shape ← heightfield\(([0-9]+), ([0-9]+), ([-0-9., ]+)\)$")]
+async fn assign_heightfield(world: &mut MyWorld, columns: usize, rows: usize, heights: String) {
+    let heights = heights
+        .split(',')
+        .map(|h| h.trim().parse().unwrap())
+        .collect();
+    let shape = heightfield(Heightfield::new(columns, rows, heights).unwrap());
+    world.objects.insert("shape".to_string(), Arc::new(shape));
+}
+
+#[given("shape ← heightfield_from_canvas(c)")]
+#[when("shape ← heightfield_from_canvas(c)")]
+async fn assign_heightfield_from_canvas(world: &mut MyWorld) {
+    let shape = heightfield(Heightfield::from_canvas(&world.canvas).unwrap());
+    world.objects.insert("shape".to_string(), Arc::new(shape));
+}
+
+#[then(regex = r#"^heightfield\(([0-9]+), ([0-9]+), ([-0-9., ]+)\) fails with "(.+)"$"#)]
+async fn reject_heightfield(
+    _world: &mut MyWorld,
+    columns: usize,
+    rows: usize,
+    heights: String,
+    desired: String,
+) {
+    let heights = heights
+        .split(',')
+        .map(|h| h.trim().parse().unwrap())
+        .collect();
+    assert_eq!(Heightfield::new(columns, rows, heights), Err(desired));
+}
+
+#[then(regex = r"^shape is a heightfield with ([0-9]+) by ([0-9]+) samples$")]
+async fn compare_heightfield_size(world: &mut MyWorld, columns: usize, rows: usize) {
+    match &world.objects.get("shape").unwrap().shape {
+        Shape::Heightfield(h) => assert_eq!((h.columns(), h.rows()), (columns, rows)),
+        _ => panic!("not a heightfield"),
+    }
+}
@@ -0,0 +1,140 @@
+use crate::MyWorld;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{
+    groups::{GroupMember, AABB},
+    instances::Instance,
+    transformations::{scaling, translation},
+};
+use std::sync::Arc;
+
+#[given(regex = r"^(i1|i2) ← instance\(g\)$")]
+async fn assign_instance(world: &mut MyWorld, name: String) {
+    let member = GroupMember::SubGroup(Arc::new(world.g.clone()));
+    world.instances.insert(name, Instance::new(member).unwrap());
+}
+
+#[given("i2 ← instance of the member of i1")]
+async fn assign_sharing_instance(world: &mut MyWorld) {
+    let member = world.instances.get("i1").unwrap().member().clone();
+    world
+        .instances
+        .insert("i2".to_string(), Instance::new(member).unwrap());
+}
+
+#[given("i2 ← instance(i1)")]
+async fn assign_nested_instance(world: &mut MyWorld) {
+    let i1 = world.instances.get("i1").unwrap().clone();
+    let member = GroupMember::Instance(Arc::new(i1));
+    world
+        .instances
+        .insert("i2".to_string(), Instance::new(member).unwrap());
+}
+
+#[given(regex = r"^i1 ← instance\(i1\) ([0-9]+) times$")]
+async fn nest_instance(world: &mut MyWorld, times: usize) {
+    for _ in 0..times {
+        let i1 = world.instances.remove("i1").unwrap();
+        let member = GroupMember::Instance(Arc::new(i1));
+        world
+            .instances
+            .insert("i1".to_string(), Instance::new(member).unwrap());
+    }
+}
+
+#[then("instance(i1) is rejected")]
+async fn reject_nested_instance(world: &mut MyWorld) {
+    let i1 = world.instances.get("i1").unwrap().clone();
+    let err = Instance::new(GroupMember::Instance(Arc::new(i1))).unwrap_err();
+    assert_eq!(err, "instances are nested deeper than 8");
+}
+
+#[given(
+    regex = r"^set_transform\((i1|i2), (scaling|translation)\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)\)$"
+)]
+async fn transform_instance(
+    world: &mut MyWorld,
+    name: String,
+    kind: String,
+    x: f64,
+    y: f64,
+    z: f64,
+) {
+    let transform = match kind.as_str() {
+        "scaling" => scaling(x, y, z),
+        "translation" => translation(x, y, z),
+        _ => panic!("transformation not covered"),
+    };
+    world
+        .instances
+        .get_mut(&name)
+        .unwrap()
+        .set_transform(transform);
+}
+
+#[given(regex = r"^set_material\((i1|i2), m\)$")]
+async fn set_instance_material(world: &mut MyWorld, name: String) {
+    let m = world.m.clone();
+    world.instances.get_mut(&name).unwrap().set_material(&m);
+}
+
+#[given(regex = r"^add_child\((g2), (i1)\)$")]
+async fn add_child_instance(world: &mut MyWorld, _group: String, name: String) {
+    let instance = world.instances.get(&name).unwrap().clone();
+    world.g2.add_instance(instance);
+}
+
+#[given(regex = r"^(i1|i2) is added to w$")]
+async fn add_instance_to_world(world: &mut MyWorld, name: String) {
+    let instance = world.instances.get(&name).unwrap().clone();
+    world.w.add_instance(instance);
+}
+
+#[when(regex = r"^xs ← intersect\((i1|i2), r\)$")]
+async fn intersect_instance(world: &mut MyWorld, name: String) {
+    let instance = Arc::new(world.instances.get(&name).unwrap().clone());
+    world.xs = Instance::intersect(&instance, &world.r);
+}
+
+#[when("xs ← intersect(g2, r)")]
+async fn intersect_outer_group(world: &mut MyWorld) {
+    world.xs = world.g2.intersect(&world.r);
+}
+
+#[when(regex = r"^box ← bounds_of\((i1|i2)\)$")]
+async fn assign_instance_bounds(world: &mut MyWorld, name: String) {
+    let AABB { min, max } = world
+        .instances
+        .get(&name)
+        .unwrap()
+        .bounds()
+        .clone()
+        .unwrap();
+    world.tuples.insert("box.min".to_string(), min);
+    world.tuples.insert("box.max".to_string(), max);
+}
+
+#[then("i1 and i2 share their group")]
+async fn instances_share_group(world: &mut MyWorld) {
+    let i1 = world.instances.get("i1").unwrap().member();
+    let i2 = world.instances.get("i2").unwrap().member();
+    match (i1, i2) {
+        (GroupMember::SubGroup(a), GroupMember::SubGroup(b)) => assert!(Arc::ptr_eq(a, b)),
+        _ => panic!("instances of groups expected"),
+    }
+}
+
+#[then(regex = r"^xs\[([0-9]+)\] was found through (i1|i2)$")]
+async fn found_through_instance(world: &mut MyWorld, index: usize, name: String) {
+    let instance = world.instances.get(&name).unwrap();
+    let found = &world.xs[index].instances;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].as_ref(), instance);
+}
+
+#[then("i was found through i1 then i2")]
+async fn found_through_nested_instances(world: &mut MyWorld) {
+    let found = &world.intersections.get("i").unwrap().instances;
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].as_ref(), world.instances.get("i1").unwrap());
+    assert_eq!(found[1].as_ref(), world.instances.get("i2").unwrap());
+}
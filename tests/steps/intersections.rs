@@ -17,6 +17,8 @@ async fn assign_intersection(world: &mut MyWorld, target: String, t: String, sha
         object,
         u: 0.0,
         v: 0.0,
+        face: 0,
+        instances: Vec::new(),
     };
     world.intersections.insert(target, intersection);
 }
@@ -31,7 +33,14 @@ async fn assign_intersection_with_uv(
     v: f64,
 ) {
     let object = world.objects.get(&shape).unwrap().clone();
-    let intersection = Intersection { t, object, u, v };
+    let intersection = Intersection {
+        t,
+        object,
+        u,
+        v,
+        face: 0,
+        instances: Vec::new(),
+    };
     world.intersections.insert(target, intersection);
 }
 
@@ -88,7 +97,7 @@ async fn assign_intersections_quad(
     world.xs = vec![i1, i2, i3, i4];
 }
 
-#[then(regex = r"^xs\[([-0-9.]+)\].(t|u|v) = ([-0-9.]+)$")]
+#[then(regex = r"^xs\[([-0-9.]+)\].(t|u|v|face) = ([-0-9.]+)$")]
 async fn compare_intersections_distance(
     world: &mut MyWorld,
     index: usize,
@@ -100,6 +109,7 @@ async fn compare_intersections_distance(
         "t" => i.t,
         "u" => i.u,
         "v" => i.v,
+        "face" => i.face as f64,
         _ => panic!("attribute not covered"),
     };
     assert_abs_diff_eq!(v, desired, epsilon = 0.0001);
@@ -207,36 +217,48 @@ async fn prepare_six_intersections(world: &mut MyWorld) {
             object: world.objects.get("A").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: 2.75,
             object: world.objects.get("B").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: 3.25,
             object: world.objects.get("C").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: 4.75,
             object: world.objects.get("B").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: 5.25,
             object: world.objects.get("C").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: 6.0,
             object: world.objects.get("A").unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
     ];
 }
@@ -262,24 +284,32 @@ async fn prepare_four_intersections(
             object: world.objects.get(&a_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: parse_float(&b_t),
             object: world.objects.get(&b_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: parse_float(&c_t),
             object: world.objects.get(&c_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: parse_float(&d_t),
             object: world.objects.get(&d_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
     ];
 }
@@ -298,12 +328,16 @@ async fn prepare_two_intersections(
             object: world.objects.get(&a_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
         Intersection {
             t: parse_float(&b_t),
             object: world.objects.get(&b_o).unwrap().clone(),
             u: 0.0,
             v: 0.0,
+            face: 0,
+            instances: Vec::new(),
         },
     ];
 }
@@ -315,6 +349,8 @@ async fn prepare_one_intersections(world: &mut MyWorld, a_t: String, a_o: String
         object: world.objects.get(&a_o).unwrap().clone(),
         u: 0.0,
         v: 0.0,
+        face: 0,
+        instances: Vec::new(),
     }];
 }
 
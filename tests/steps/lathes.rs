@@ -0,0 +1,29 @@
+use crate::MyWorld;
+use cucumber::given;
+use lab_raytracing_rs::{lathes::Lathe, objects::lathe};
+use std::sync::Arc;
+
+// "(1, 0), (1, 2)" as radius and y pairs
+fn parse_profile(profile: &str) -> Vec<(f64, f64)> {
+    profile
+        .split(')')
+        .map(|p| p.trim_start_matches([',', ' ', '(']))
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (r, y) = p.split_once(',').unwrap();
+            (r.trim().parse().unwrap(), y.trim().parse().unwrap())
+        })
+        .collect()
+}
+
+#[given(regex = r"^shape ← lathe\((.*)\)$")]
+async fn assign_lathe(world: &mut MyWorld, profile: String) {
+    let shape = lathe(Lathe::polyline(&parse_profile(&profile)));
+    world.objects.insert("shape".to_string(), Arc::new(shape));
+}
+
+#[given(regex = r"^shape ← spline_lathe\(([0-9]+), (.*)\)$")]
+async fn assign_spline_lathe(world: &mut MyWorld, steps: usize, profile: String) {
+    let shape = lathe(Lathe::spline(&parse_profile(&profile), steps));
+    world.objects.insert("shape".to_string(), Arc::new(shape));
+}
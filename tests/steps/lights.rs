@@ -58,7 +58,16 @@ async fn compute_lighting(world: &mut MyWorld) {
     let position = world.tuples.get("position").unwrap();
     let eyev = world.tuples.get("eyev").unwrap();
     let normalv = world.tuples.get("normalv").unwrap();
-    let result = lighting(material, &object, light, position, eyev, normalv, false);
+    let result = lighting(
+        material,
+        &object,
+        light,
+        position,
+        eyev,
+        normalv,
+        false,
+        &(0.0, 0.0),
+    );
     world.tuples.insert("result".to_string(), result);
 }
 
@@ -71,7 +80,16 @@ async fn compute_lighting_in_shadow(world: &mut MyWorld) {
     let eyev = world.tuples.get("eyev").unwrap();
     let normalv = world.tuples.get("normalv").unwrap();
     let in_shadow = world.in_shadow;
-    let result = lighting(material, &object, light, position, eyev, normalv, in_shadow);
+    let result = lighting(
+        material,
+        &object,
+        light,
+        position,
+        eyev,
+        normalv,
+        in_shadow,
+        &(0.0, 0.0),
+    );
     world.tuples.insert("result".to_string(), result);
 }
 
@@ -93,7 +111,14 @@ async fn compute_lighting_at_point(
     let normalv = world.tuples.get("normalv").unwrap();
     let in_shadow = false;
     let result = lighting(
-        material, &object, light, &position, eyev, normalv, in_shadow,
+        material,
+        &object,
+        light,
+        &position,
+        eyev,
+        normalv,
+        in_shadow,
+        &(0.0, 0.0),
     );
     world.tuples.insert(target, result);
 }
@@ -0,0 +1,113 @@
+use crate::MyWorld;
+use approx::assert_abs_diff_eq;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{obj_file::Parser, objects::Shape};
+use std::{fmt::Write, path::Path, sync::Arc};
+
+#[given(regex = r"^parser ← parse_obj_file_as_meshes\(file\)$")]
+#[when(regex = r"^parser ← parse_obj_file_as_meshes\(file\)$")]
+async fn parse_file_as_meshes(world: &mut MyWorld) {
+    let content = world.files.get("file").unwrap();
+    world.parser = Parser::parse_obj_file_as_meshes(content).unwrap();
+}
+
+#[when(regex = r#"^parser ← load_obj_file_as_meshes\("([\w\.]+)"\)$"#)]
+async fn load_file_as_meshes(world: &mut MyWorld, path: String) {
+    let path = Path::new("./features/").join(path);
+    world.parser = Parser::load_obj_file_as_meshes(&path).unwrap();
+}
+
+// squares of size 1 in the xz plane from the origin
+#[given(regex = r"^file ← a grid of ([0-9]+) by ([0-9]+) squares$")]
+async fn prepare_grid(world: &mut MyWorld, columns: usize, rows: usize) {
+    let mut content = String::new();
+    for z in 0..=rows {
+        for x in 0..=columns {
+            writeln!(content, "v {} 0 {}", x, z).unwrap();
+        }
+    }
+    for z in 0..rows {
+        for x in 0..columns {
+            let corner = z * (columns + 1) + x + 1;
+            let above = corner + columns + 1;
+            writeln!(
+                content,
+                "f {} {} {} {}",
+                corner,
+                corner + 1,
+                above + 1,
+                above
+            )
+            .unwrap();
+        }
+    }
+    world.files.insert("file".to_string(), content);
+}
+
+// squares of size 1 parallel to the xz plane, one at every height from 0
+#[given(regex = r"^file ← a stack of ([0-9]+) squares$")]
+async fn prepare_stack(world: &mut MyWorld, count: usize) {
+    let mut content = String::new();
+    for y in 0..count {
+        for (x, z) in [(0, 0), (1, 0), (1, 1), (0, 1)] {
+            writeln!(content, "v {} {} {}", x, y, z).unwrap();
+        }
+    }
+    for square in 0..count {
+        let corner = square * 4 + 1;
+        writeln!(
+            content,
+            "f {} {} {} {}",
+            corner,
+            corner + 1,
+            corner + 2,
+            corner + 3
+        )
+        .unwrap();
+    }
+    world.files.insert("file".to_string(), content);
+}
+
+#[when("xs ← local_intersect_nearest(t1, r)")]
+async fn local_intersect_nearest(world: &mut MyWorld) {
+    let obj = world.objects.get("t1").unwrap();
+    let mut hits = Vec::new();
+    obj.shape.intersect_nearest_into(&world.r, &mut hits);
+    world.xs = hits.iter().map(|hit| hit.with_object(obj)).collect();
+}
+
+#[then(regex = r"^xs.count is at most ([0-9]+)$")]
+async fn bound_intersections(world: &mut MyWorld, desired: usize) {
+    assert!(world.xs.len() <= desired, "{} hits", world.xs.len());
+}
+
+#[then(regex = r"^the last of xs has t = ([-0-9.]+)$")]
+async fn compare_last_intersection(world: &mut MyWorld, desired: f64) {
+    assert_abs_diff_eq!(world.xs.last().unwrap().t, desired, epsilon = 0.0001);
+}
+
+#[then(regex = r"^(t1|t2) is a mesh with ([0-9]+) faces?$")]
+async fn compare_mesh_faces(world: &mut MyWorld, object: String, desired: usize) {
+    match &world.objects.get(&object).unwrap().shape {
+        Shape::Mesh(m) => assert_eq!(m.len(), desired),
+        shape => panic!("{:?} is not a mesh", shape),
+    }
+}
+
+#[then("t1 and t2 share their vertices")]
+async fn compare_mesh_buffers(world: &mut MyWorld) {
+    let vertices = |name: &str| match &world.objects.get(name).unwrap().shape {
+        Shape::Mesh(m) => m.vertices.clone(),
+        shape => panic!("{:?} is not a mesh", shape),
+    };
+    assert!(Arc::ptr_eq(&vertices("t1"), &vertices("t2")));
+}
+
+#[then(regex = r"^comps.(u|v) = ([-0-9.]+)$")]
+async fn compare_comps_uv(world: &mut MyWorld, attribute: String, desired: f64) {
+    let value = match attribute.as_str() {
+        "u" => world.comps.u,
+        _ => world.comps.v,
+    };
+    assert_abs_diff_eq!(value, desired, epsilon = 0.0001);
+}
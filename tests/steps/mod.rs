@@ -1,19 +1,33 @@
+pub mod bezier_file;
+pub mod bvh;
 pub mod camera;
 pub mod canvas;
+pub mod compare;
 pub mod csg;
 pub mod cylinders;
 pub mod groups;
+pub mod heightfields;
+pub mod instances;
 pub mod intersections;
+pub mod lathes;
 pub mod lights;
 pub mod materials;
 pub mod matrices;
+pub mod meshes;
 pub mod obj_file;
 pub mod objects;
+pub mod patches;
 pub mod patterns;
 pub mod planes;
+pub mod ply_file;
 pub mod rays;
+pub mod scene_file;
+pub mod sdf;
 pub mod smooth_triangles;
 pub mod spheres;
+pub mod stl_file;
+pub mod texture_mapping;
+pub mod torus;
 pub mod transformations;
 pub mod triangles;
 pub mod tuples;
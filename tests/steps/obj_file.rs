@@ -1,16 +1,29 @@
 use crate::steps::tuples::parse_point;
 use crate::MyWorld;
 use cucumber::{gherkin::Step, given, then, when};
-use lab_raytracing_rs::{obj_file::Parser, objects::Shape};
+use lab_raytracing_rs::{
+    mtl_file::parse_mtl_file,
+    obj_file::Parser,
+    objects::Shape,
+    patterns::pattern_at_shape,
+    tuples::{color, point},
+};
 use std::fs;
 use std::path::Path;
 
-#[given(regex = r"^(gibberish|file) ← a file containing:$")]
+#[given(regex = r"^(gibberish|file|scene|ppm|mtl|ply|stl) ← a file containing:$")]
 async fn prepare_file(world: &mut MyWorld, target: String, step: &Step) {
     let content = step.docstring.clone().unwrap()[1..].to_string();
     world.files.insert(target, content);
 }
 
+#[given(regex = r#"^(file) ends with the line "(.*)"$"#)]
+async fn append_line(world: &mut MyWorld, target: String, line: String) {
+    let content = world.files.get_mut(&target).unwrap();
+    content.push_str(&line);
+    content.push('\n');
+}
+
 #[given(regex = r#"^(file) ← the file "([\w\.]+)"$"#)]
 async fn read_file(world: &mut MyWorld, target: String, path: String) {
     let path = Path::new("./features/").join(path);
@@ -22,7 +35,85 @@ async fn read_file(world: &mut MyWorld, target: String, path: String) {
 #[when(regex = r"^parser ← parse_obj_file\((gibberish|file)\)$")]
 async fn parse_file(world: &mut MyWorld, file: String) {
     let content = world.files.get(&file).unwrap();
-    world.parser = Parser::parse_obj_file(content);
+    match Parser::parse_obj_file(content) {
+        Ok(parser) => {
+            world.parser = parser;
+            world.obj_error = None;
+        }
+        Err(err) => world.obj_error = Some(err),
+    }
+}
+
+#[then(regex = r#"^parsing the obj file failed with "(.*)"$"#)]
+async fn compare_obj_error(world: &mut MyWorld, desired: String) {
+    let err = world.obj_error.as_ref().expect("parsing did not fail");
+    assert_eq!(err.to_string(), desired);
+}
+
+#[then(regex = r"^parser.texture_vertices\[([0-9]+)\] = \(([-0-9.]+), ([-0-9.]+)\)$")]
+async fn compare_parsed_texture_vertices(world: &mut MyWorld, index: usize, u: f64, v: f64) {
+    assert_eq!(world.parser.texture_vertices[index - 1], (u, v))
+}
+
+#[when(regex = r#"^parser ← load_obj_file\("([\w\.]+)"\)$"#)]
+async fn load_file(world: &mut MyWorld, path: String) {
+    let path = Path::new("./features/").join(path);
+    world.parser = Parser::load_obj_file(&path).unwrap();
+}
+
+#[then(regex = r#"^parser warned "(.+)"$"#)]
+async fn check_warning(world: &mut MyWorld, desired: String) {
+    let warnings: Vec<String> = world
+        .parser
+        .warnings
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with(&desired), "{:?}", warnings);
+}
+
+#[when(regex = r#"^m ← material "(\w+)" from parse_mtl_file\(mtl\)$"#)]
+async fn select_mtl_material(world: &mut MyWorld, name: String) {
+    let content = world.files.get("mtl").unwrap();
+    let materials = parse_mtl_file(content, Path::new("./features/")).unwrap();
+    world.m = materials.get(&name).expect("material missing").clone();
+}
+
+#[then(regex = r#"^parse_mtl_file\(mtl\) fails with "(.*)"$"#)]
+async fn compare_mtl_error(world: &mut MyWorld, desired: String) {
+    let content = world.files.get("mtl").unwrap();
+    let err = parse_mtl_file(content, Path::new("./features/")).unwrap_err();
+    assert_eq!(err.to_string(), desired);
+}
+
+#[when(regex = r"^m ← (t1|t2|t3).material$")]
+async fn select_object_material(world: &mut MyWorld, object: String) {
+    world.m = world.objects.get(&object).unwrap().material.clone();
+}
+
+#[then(regex = r"^parser.material_libraries = (.*)$")]
+async fn compare_material_libraries(world: &mut MyWorld, desired: String) {
+    assert_eq!(world.parser.material_libraries.join(", "), desired);
+}
+
+#[then(
+    regex = r"^the texture of (t1|t2|t3) at hit_uv\(([-0-9.]+), ([-0-9.]+)\) = color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn compare_texture_at(
+    world: &mut MyWorld,
+    object: String,
+    u: f64,
+    v: f64,
+    r: f64,
+    g: f64,
+    b: f64,
+) {
+    let object = world.objects.get(&object).unwrap();
+    let pattern = object.material.pattern.as_ref().expect("no texture");
+    let computed = pattern_at_shape(pattern, object, &point(0.0, 0.0, 0.0), &(u, v));
+    assert_eq!(computed, color(r, g, b));
 }
 
 #[given("g ← parser.default_group")]
@@ -31,7 +122,8 @@ async fn select_parser_default_group(world: &mut MyWorld) {
     world.g = world.parser.groups.get("default_group").unwrap().clone();
 }
 
-#[when(regex = r#"(g1|g2) ← "(\w+)" from parser"#)]
+#[given(regex = r#"(g1|g2) ← "([\w.-]+)" from parser"#)]
+#[when(regex = r#"(g1|g2) ← "([\w.-]+)" from parser"#)]
 async fn select_group_from_parser(world: &mut MyWorld, target: String, group_name: String) {
     let g = world
         .parser
@@ -0,0 +1,83 @@
+use crate::{
+    steps::tuples::{eq_tuples_similar, parse_point, parse_vector},
+    MyWorld,
+};
+use cucumber::{then, when};
+use lab_raytracing_rs::{
+    objects::{mesh, Shape},
+    patches::tessellate,
+};
+use std::{collections::HashMap, sync::Arc};
+
+#[then(
+    regex = r"^point_at\(patches\[([0-9]+)\], ([-0-9.]+), ([-0-9.]+)\) = point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_patch_point(
+    world: &mut MyWorld,
+    patch: usize,
+    u: f64,
+    v: f64,
+    x: String,
+    y: String,
+    z: String,
+) {
+    let value = world.patches[patch].point_at(u, v);
+    assert!(
+        eq_tuples_similar(&value, &parse_point(&[x, y, z])),
+        "{:?}",
+        value
+    );
+}
+
+#[then(
+    regex = r"^normal_at\(patches\[([0-9]+)\], ([-0-9.]+), ([-0-9.]+)\) = vector\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_patch_normal(
+    world: &mut MyWorld,
+    patch: usize,
+    u: f64,
+    v: f64,
+    x: String,
+    y: String,
+    z: String,
+) {
+    let value = world.patches[patch].normal_at(u, v);
+    assert!(
+        eq_tuples_similar(&value, &parse_vector(&[x, y, z])),
+        "{:?}",
+        value
+    );
+}
+
+#[when(regex = r"^shape ← mesh\(tessellate\(patches, ([0-9.]+)\)\)$")]
+async fn assign_tessellated(world: &mut MyWorld, tolerance: f64) {
+    let shape = mesh(tessellate(&world.patches, tolerance));
+    world.objects.insert("shape".to_string(), Arc::new(shape));
+}
+
+#[then(regex = r"^shape has ([0-9]+) faces$")]
+async fn compare_face_count(world: &mut MyWorld, desired: usize) {
+    match &world.objects.get("shape").unwrap().shape {
+        Shape::Mesh(m) => assert_eq!(m.len(), desired),
+        _ => panic!("not a mesh"),
+    }
+}
+
+// edges that only one face uses, corners at the same place count as the same
+// vertex
+#[then(regex = r"^shape has ([0-9]+) open edges$")]
+async fn compare_open_edges(world: &mut MyWorld, desired: usize) {
+    let Shape::Mesh(m) = &world.objects.get("shape").unwrap().shape else {
+        panic!("not a mesh");
+    };
+    let key = |c: f64| (c * 1e6).round() as i64;
+    let mut edges: HashMap<_, usize> = HashMap::new();
+    for face in 0..m.len() {
+        let corners = m.corners(face).map(|p| (key(p.x), key(p.y), key(p.z)));
+        for i in 0..3 {
+            let (a, b) = (corners[i], corners[(i + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    assert_eq!(edges.values().filter(|n| **n == 1).count(), desired);
+}
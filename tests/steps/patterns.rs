@@ -109,7 +109,7 @@ async fn assign_color_from_stripe_pattern(
 ) {
     let point = parse_point(&[x, y, z]);
     let object = world.objects.get(&object_name).unwrap();
-    let color = pattern_at_shape(&world.pattern, object, &point);
+    let color = pattern_at_shape(&world.pattern, object, &point, &(0.0, 0.0));
     world.tuples.insert("c".to_string(), color);
 }
 
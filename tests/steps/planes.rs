@@ -1,6 +1,9 @@
 use crate::{steps::tuples::parse_point, MyWorld};
 use cucumber::{given, then, when};
-use lab_raytracing_rs::{intersections::Intersection, objects::default_plane};
+use lab_raytracing_rs::{
+    intersections::Intersection,
+    objects::{annulus, default_plane},
+};
 use std::sync::Arc;
 
 #[given("p ← plane()")]
@@ -9,7 +12,14 @@ async fn assign_default_plane(world: &mut MyWorld) {
     world.objects.insert("p".to_string(), Arc::new(p));
 }
 
-#[when(regex = r"^xs ← local_intersect\((p|c|cyl|shape|t|tri), r\)$")]
+#[given(regex = r"^shape ← annulus\(([-0-9.]+)\)$")]
+async fn assign_annulus(world: &mut MyWorld, inner: f64) {
+    world
+        .objects
+        .insert("shape".to_string(), Arc::new(annulus(inner)));
+}
+
+#[when(regex = r"^xs ← local_intersect\((p|c|cyl|shape|t|tri|t1|t2), r\)$")]
 async fn local_intersect(world: &mut MyWorld, shape_name: String) {
     let obj = world.objects.get(&shape_name).unwrap();
     world.xs = obj.intersect(&world.r, obj);
@@ -45,6 +55,8 @@ async fn local_normal_at_point(
         object: obj.clone(),
         u: 0.0,
         v: 0.0,
+        face: 0,
+        instances: Vec::new(),
     };
     let normal = obj.shape.normal_at(&point, hit);
     world.tuples.insert(normal_name, normal);
@@ -64,6 +76,8 @@ async fn local_normal_at(
         object: obj.clone(),
         u: 0.0,
         v: 0.0,
+        face: 0,
+        instances: Vec::new(),
     };
     let normal = obj.shape.normal_at(point, hit);
     world.tuples.insert(normal_name, normal);
@@ -0,0 +1,96 @@
+use crate::{
+    steps::{
+        canvas::parse_bytes,
+        tuples::{eq_tuples_similar, parse_color, parse_point, parse_vector},
+    },
+    MyWorld,
+};
+use cucumber::{gherkin::Step, given, then, when};
+use lab_raytracing_rs::{objects::Shape, ply_file::parse_ply_file};
+
+#[given("ply continues with the bytes:")]
+async fn append_bytes(world: &mut MyWorld, step: &Step) {
+    let mut bytes = world.files.get("ply").unwrap().as_bytes().to_vec();
+    bytes.extend(parse_bytes(step));
+    world.image_file = bytes;
+}
+
+#[when("g ← parse_ply_file(ply)")]
+async fn parse_ply(world: &mut MyWorld) {
+    let bytes = match world.image_file.is_empty() {
+        true => world.files.get("ply").unwrap().as_bytes(),
+        false => world.image_file.as_slice(),
+    };
+    match parse_ply_file(&mut &bytes[..]) {
+        Ok(group) => {
+            world.g = group;
+            world.load_error = None;
+        }
+        Err(err) => world.load_error = Some(err.to_string()),
+    }
+}
+
+#[then(regex = r#"^parsing the (?:ply|stl|bezier) file failed with "(.*)"$"#)]
+async fn compare_ply_error(world: &mut MyWorld, desired: String) {
+    assert_eq!(world.load_error.as_deref(), Some(desired.as_str()));
+}
+
+#[then(regex = r"^g has ([0-9]+) children$")]
+async fn compare_group_size(world: &mut MyWorld, desired: usize) {
+    assert_eq!(world.g.len(), desired);
+}
+
+#[then(
+    regex = r"^(t1|t2|t3).(p1|p2|p3|n1|n2|n3) = (point|vector)\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_corner(
+    world: &mut MyWorld,
+    object: String,
+    attribute: String,
+    kind: String,
+    x: String,
+    y: String,
+    z: String,
+) {
+    let desired = match kind.as_str() {
+        "point" => parse_point(&[x, y, z]),
+        _ => parse_vector(&[x, y, z]),
+    };
+    let corner = match (
+        &world.objects.get(&object).unwrap().shape,
+        attribute.as_str(),
+    ) {
+        (Shape::Triangle(t), "p1") => t.p1.clone(),
+        (Shape::Triangle(t), "p2") => t.p2.clone(),
+        (Shape::Triangle(t), "p3") => t.p3.clone(),
+        (Shape::SmoothTriangle(t), "p1") => t.p1.clone(),
+        (Shape::SmoothTriangle(t), "p2") => t.p2.clone(),
+        (Shape::SmoothTriangle(t), "p3") => t.p3.clone(),
+        (Shape::SmoothTriangle(t), "n1") => t.n1.clone(),
+        (Shape::SmoothTriangle(t), "n2") => t.n2.clone(),
+        (Shape::SmoothTriangle(t), "n3") => t.n3.clone(),
+        _ => panic!("attribute not covered"),
+    };
+    assert_eq!(corner, desired);
+}
+
+#[then(regex = r"^(t1|t2|t3) is a (triangle|smooth triangle)$")]
+async fn compare_triangle_kind(world: &mut MyWorld, object: String, kind: String) {
+    let shape = &world.objects.get(&object).unwrap().shape;
+    match kind.as_str() {
+        "triangle" => assert!(matches!(shape, Shape::Triangle(_))),
+        _ => assert!(matches!(shape, Shape::SmoothTriangle(_))),
+    }
+}
+
+#[then(regex = r"^(t1|t2|t3).material.color = color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$")]
+async fn compare_triangle_color(
+    world: &mut MyWorld,
+    object: String,
+    r: String,
+    g: String,
+    b: String,
+) {
+    let material = &world.objects.get(&object).unwrap().material;
+    assert!(eq_tuples_similar(&material.color, &parse_color(&[r, g, b])));
+}
@@ -1,4 +1,4 @@
-use super::tuples::{parse_point, parse_vector};
+use super::tuples::{parse_float, parse_point, parse_vector};
 use crate::steps::tuples::eq_tuples_similar;
 use crate::MyWorld;
 use cucumber::{given, then, when};
@@ -95,3 +95,29 @@ async fn transform_ray(world: &mut MyWorld) {
     let transformation = world.get4x4("m");
     world.r2 = world.r.transform(transformation);
 }
+
+#[given(regex = r"^r ← between\(r, (-?infinity|[-0-9.]+), (-?infinity|[-0-9.]+)\)$")]
+async fn limit_ray(world: &mut MyWorld, t_min: String, t_max: String) {
+    world.r = world
+        .r
+        .clone()
+        .between(parse_float(&t_min), parse_float(&t_max));
+}
+
+#[then(regex = r"^(r|r2).(t_min|t_max) = (-?infinity|[-0-9.]+)$")]
+async fn compare_ray_interval(
+    world: &mut MyWorld,
+    ray: String,
+    attribute: String,
+    desired: String,
+) {
+    let ray = match ray.as_str() {
+        "r" => &world.r,
+        _ => &world.r2,
+    };
+    let value = match attribute.as_str() {
+        "t_min" => ray.t_min,
+        _ => ray.t_max,
+    };
+    assert_eq!(value, parse_float(&desired));
+}
@@ -0,0 +1,259 @@
+use crate::steps::sdf::parse_sdf;
+use crate::steps::transformations::{parse_scaling, parse_translation};
+use crate::steps::tuples::{parse_color, parse_point, parse_vector};
+use crate::MyWorld;
+use cucumber::{then, when};
+use lab_raytracing_rs::{
+    groups::GroupMember, lights::Pointlight, materials::Material, matrices::Matrix4x4,
+    objects::Shape, scene_file::Scene, transformations::view_transform,
+};
+use std::path::Path;
+
+#[when("w ← parse_scene_file(scene)")]
+async fn parse_scene(world: &mut MyWorld) {
+    let content = world.files.get("scene").unwrap();
+    match Scene::parse_scene_file(content, Path::new("./features/")) {
+        Ok(scene) => {
+            world.w = scene.world;
+            world.camera = scene.camera;
+            world.scene_error = None;
+        }
+        Err(err) => world.scene_error = Some(err),
+    }
+}
+
+#[then(
+    regex = r"^c.transform = view_transform\(point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\), point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\), vector\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)\)$"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn compare_camera_view(
+    world: &mut MyWorld,
+    from_x: String,
+    from_y: String,
+    from_z: String,
+    to_x: String,
+    to_y: String,
+    to_z: String,
+    up_x: String,
+    up_y: String,
+    up_z: String,
+) {
+    let from = parse_point(&[from_x, from_y, from_z]);
+    let to = parse_point(&[to_x, to_y, to_z]);
+    let up = parse_vector(&[up_x, up_y, up_z]);
+    assert_eq!(world.camera.transform(), &view_transform(&from, &to, &up));
+}
+
+#[then(
+    regex = r"^w.light = point_light\(point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\), color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)\)$"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn compare_scene_light(
+    world: &mut MyWorld,
+    x: String,
+    y: String,
+    z: String,
+    r: String,
+    g: String,
+    b: String,
+) {
+    let desired = Pointlight::new(parse_point(&[x, y, z]), parse_color(&[r, g, b]));
+    assert_eq!(world.w.light.as_ref().unwrap(), &desired);
+}
+
+#[then(regex = r"^w contains ([0-9]+) objects$")]
+async fn count_objects(world: &mut MyWorld, desired: usize) {
+    assert_eq!(world.w.objects().len(), desired);
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a (sphere|plane|cube|disk|rectangle)$")]
+async fn compare_shape(world: &mut MyWorld, index: usize, kind: String) {
+    let desired = match kind.as_str() {
+        "sphere" => Shape::Sphere,
+        "plane" => Shape::Plane,
+        "disk" => Shape::Disk,
+        "rectangle" => Shape::Rectangle,
+        _ => Shape::Cube,
+    };
+    assert_eq!(object_in_world(world, index).shape, desired);
+}
+
+#[then(
+    regex = r"^object ([0-9]+) of w is a (cylinder|cone)\(([-0-9.]+), ([-0-9.]+), (true|false)\)$"
+)]
+async fn compare_truncated_shape(
+    world: &mut MyWorld,
+    index: usize,
+    kind: String,
+    min: f64,
+    max: f64,
+    closed: bool,
+) {
+    let desired = match kind.as_str() {
+        "cylinder" => Shape::Cylinder(min, max, closed),
+        _ => Shape::Cone(min, max, closed),
+    };
+    assert_eq!(object_in_world(world, index).shape, desired);
+}
+
+#[then(regex = r"^object ([0-9]+) of w is an annulus\(([-0-9.]+)\)$")]
+async fn compare_annulus(world: &mut MyWorld, index: usize, inner: f64) {
+    assert_eq!(object_in_world(world, index).shape, Shape::Annulus(inner));
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a torus\(([-0-9.]+), ([-0-9.]+)\)$")]
+async fn compare_torus(world: &mut MyWorld, index: usize, major: f64, minor: f64) {
+    assert_eq!(
+        object_in_world(world, index).shape,
+        Shape::Torus(major, minor)
+    );
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a lathe with ([0-9]+) segments$")]
+async fn compare_lathe(world: &mut MyWorld, index: usize, segments: usize) {
+    match &object_in_world(world, index).shape {
+        Shape::Lathe(l) => assert_eq!(l.len(), segments),
+        _ => panic!("not a lathe"),
+    }
+}
+
+#[then(regex = r"^object ([0-9]+) of w is an sdf (.*)$")]
+async fn compare_sdf(world: &mut MyWorld, index: usize, text: String) {
+    assert_eq!(
+        object_in_world(world, index).shape,
+        Shape::Sdf(parse_sdf(&text))
+    );
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a mesh with ([0-9]+) faces$")]
+async fn compare_mesh(world: &mut MyWorld, index: usize, faces: usize) {
+    match &object_in_world(world, index).shape {
+        Shape::Mesh(m) => assert_eq!(m.len(), faces),
+        _ => panic!("not a mesh"),
+    }
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a heightfield with ([0-9]+) by ([0-9]+) samples$")]
+async fn compare_heightfield(world: &mut MyWorld, index: usize, columns: usize, rows: usize) {
+    match &object_in_world(world, index).shape {
+        Shape::Heightfield(h) => assert_eq!((h.columns(), h.rows()), (columns, rows)),
+        _ => panic!("not a heightfield"),
+    }
+}
+
+#[then(regex = r"^object ([0-9]+) of w is a group with ([0-9]+) children$")]
+async fn compare_group(world: &mut MyWorld, index: usize, children: usize) {
+    match &world.w.objects()[index - 1] {
+        GroupMember::SubGroup(g) => assert_eq!(g.len(), children),
+        _ => panic!("not a group"),
+    }
+}
+
+#[then(
+    regex = r"^(object [0-9]+|child [0-9]+ of object [0-9]+) of w has material.(ambient|diffuse|specular|shininess|reflective|transparency) = ([-0-9.]+)$"
+)]
+async fn compare_material_attribute(
+    world: &mut MyWorld,
+    object: String,
+    attribute: String,
+    desired: f64,
+) {
+    let material = material_of(world, &object);
+    let value = match attribute.as_str() {
+        "ambient" => material.ambient,
+        "diffuse" => material.diffuse,
+        "specular" => material.specular,
+        "shininess" => material.shininess,
+        "reflective" => material.reflective,
+        _ => material.transparency,
+    };
+    assert_eq!(value, desired);
+}
+
+#[then(
+    regex = r"^(object [0-9]+) of w has material.color = color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_material_color(
+    world: &mut MyWorld,
+    object: String,
+    r: String,
+    g: String,
+    b: String,
+) {
+    let material = material_of(world, &object);
+    assert_eq!(material.color, parse_color(&[r, g, b]));
+}
+
+#[then(
+    regex = r"^(object [0-9]+|child [0-9]+ of object [0-9]+) of w has transform translation\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\) \* scaling\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn compare_transform(
+    world: &mut MyWorld,
+    object: String,
+    tx: String,
+    ty: String,
+    tz: String,
+    sx: String,
+    sy: String,
+    sz: String,
+) {
+    let desired: Matrix4x4 = parse_translation(&[tx, ty, tz]) * parse_scaling(&[sx, sy, sz]);
+    let transform = match object.split(' ').collect::<Vec<&str>>()[..] {
+        ["object", i] => object_in_world(world, i.parse().unwrap())
+            .transform()
+            .clone(),
+        ["child", c, "of", "object", i] => {
+            child_in_world(world, i.parse().unwrap(), c.parse().unwrap())
+                .transform()
+                .clone()
+        }
+        _ => panic!("object not covered"),
+    };
+    assert_eq!(transform, desired);
+}
+
+#[then(regex = r#"^parsing the scene failed with "(.*)"$"#)]
+async fn compare_scene_error(world: &mut MyWorld, desired: String) {
+    let err = world
+        .scene_error
+        .as_ref()
+        .expect("scene was parsed successfully");
+    assert_eq!(err.to_string(), desired);
+}
+
+fn object_in_world(world: &MyWorld, index: usize) -> &lab_raytracing_rs::objects::Object {
+    match &world.w.objects()[index - 1] {
+        GroupMember::Object(o) => o,
+        _ => panic!("not an object"),
+    }
+}
+
+fn child_in_world(
+    world: &MyWorld,
+    index: usize,
+    child: usize,
+) -> std::sync::Arc<lab_raytracing_rs::objects::Object> {
+    match &world.w.objects()[index - 1] {
+        GroupMember::SubGroup(g) => g.get_object(child - 1),
+        _ => panic!("not a group"),
+    }
+}
+
+fn material_of(world: &MyWorld, object: &str) -> Material {
+    match object.split(' ').collect::<Vec<&str>>()[..] {
+        ["object", i] => object_in_world(world, i.parse().unwrap()).material.clone(),
+        ["child", c, "of", "object", i] => {
+            child_in_world(world, i.parse().unwrap(), c.parse().unwrap())
+                .material
+                .clone()
+        }
+        _ => panic!("object not covered"),
+    }
+}
+
+#[when(regex = r"^pattern ← the pattern of (object [0-9]+) of w$")]
+async fn assign_scene_pattern(world: &mut MyWorld, object: String) {
+    let material = material_of(world, &object);
+    world.pattern = *material.pattern.unwrap();
+}
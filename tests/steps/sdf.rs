@@ -0,0 +1,54 @@
+use crate::MyWorld;
+use cucumber::given;
+use lab_raytracing_rs::{objects::sdf, sdf::Sdf};
+use std::sync::Arc;
+
+// "smooth_union(sphere(1), translate(box(1, 1, 1), 2, 0, 0), 0.5)"
+pub fn parse_sdf(text: &str) -> Sdf {
+    let text = text.trim();
+    let (name, rest) = text.split_once('(').unwrap();
+    let inner = rest.strip_suffix(')').unwrap();
+    let mut args = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(&inner[start..]);
+    let number = |i: usize| args[i].trim().parse::<f64>().unwrap();
+    match name {
+        "sphere" => Sdf::Sphere(number(0)),
+        "box" => Sdf::Box(number(0), number(1), number(2)),
+        "torus" => Sdf::Torus(number(0), number(1)),
+        "cylinder" => Sdf::Cylinder(number(0), number(1)),
+        "union" => Sdf::union(parse_sdf(args[0]), parse_sdf(args[1])),
+        "intersection" => Sdf::intersection(parse_sdf(args[0]), parse_sdf(args[1])),
+        "subtraction" => Sdf::subtraction(parse_sdf(args[0]), parse_sdf(args[1])),
+        "smooth_union" => Sdf::smooth_union(parse_sdf(args[0]), parse_sdf(args[1]), number(2)),
+        "smooth_subtraction" => {
+            Sdf::smooth_subtraction(parse_sdf(args[0]), parse_sdf(args[1]), number(2))
+        }
+        "translate" => parse_sdf(args[0]).translate(number(1), number(2), number(3)),
+        "scale" => parse_sdf(args[0]).scale(number(1)),
+        "twist" => parse_sdf(args[0]).twist(number(1)),
+        "bend" => parse_sdf(args[0]).bend(number(1)),
+        "repeat" => parse_sdf(args[0]).repeat(
+            [number(1), number(2), number(3)],
+            [4, 5, 6].map(|i| number(i) as u32),
+        ),
+        _ => panic!("unknown distance function {}", name),
+    }
+}
+
+#[given(regex = r"^(s|shape|s1|s2) ← sdf\((.*)\)$")]
+async fn assign_sdf(world: &mut MyWorld, name: String, text: String) {
+    let shape = sdf(parse_sdf(&text));
+    world.objects.insert(name, Arc::new(shape));
+}
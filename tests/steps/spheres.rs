@@ -2,12 +2,13 @@ use super::tuples::parse_point;
 use crate::MyWorld;
 use approx::assert_abs_diff_eq;
 use cucumber::{gherkin::Step, given, then, when};
-use lab_raytracing_rs::intersections::Intersection;
+use lab_raytracing_rs::intersections::{Intersection, ShapeHit};
 use lab_raytracing_rs::transformations::scaling;
 use lab_raytracing_rs::{
     matrices::Matrix4x4,
     objects::{
-        default_cone, default_cube, default_cylinder, default_plane, default_sphere, glass_sphere,
+        default_cone, default_cube, default_cylinder, default_disk, default_plane,
+        default_rectangle, default_sphere, glass_sphere,
     },
     patterns::test_pattern,
     transformations::translation,
@@ -17,7 +18,7 @@ use regex::Regex;
 use std::{ops::Deref, sync::Arc};
 
 #[given(
-    regex = r"^(s|shape|s1|object|c|cyl|s2|s3) ← (sphere|plane|glass_sphere|cube|cylinder|cone)\(\)$"
+    regex = r"^(s|shape|s1|object|c|cyl|s2|s3) ← (sphere|plane|glass_sphere|cube|cylinder|cone|disk|rectangle)\(\)$"
 )]
 async fn create_shape(world: &mut MyWorld, name: String, kind: String) {
     let s = match kind.as_str() {
@@ -27,6 +28,8 @@ async fn create_shape(world: &mut MyWorld, name: String, kind: String) {
         "cube" => default_cube(),
         "cylinder" => default_cylinder(),
         "cone" => default_cone(),
+        "disk" => default_disk(),
+        "rectangle" => default_rectangle(),
         _ => panic!("object kind not covered"),
     };
     world.objects.insert(name, Arc::new(s));
@@ -43,7 +46,7 @@ async fn create_shape_with(world: &mut MyWorld, name: String, kind: String, step
         _ => panic!("object kind not covered"),
     };
     for row in &step.table.as_ref().unwrap().rows {
-        let key = row.get(0).unwrap();
+        let key = row.first().unwrap();
         let value = row.get(1).unwrap();
         match key.as_str() {
             "material.color" => s.material.color = color_from_string(value),
@@ -56,6 +59,7 @@ async fn create_shape_with(world: &mut MyWorld, name: String, kind: String, step
                 s.material.refractive_index = value.parse::<f64>().unwrap()
             }
             "transform" => s.set_transform(transform_from_string(value)),
+            "throws_shadow" => s.throws_shaddow = value.parse::<bool>().unwrap(),
             _ => panic!("object property not covered"),
         }
     }
@@ -66,7 +70,7 @@ async fn create_shape_with(world: &mut MyWorld, name: String, kind: String, step
 async fn shape_with(world: &mut MyWorld, name: String, step: &Step) {
     let mut s = world.objects.get(&name).unwrap().deref().clone();
     for row in &step.table.as_ref().unwrap().rows {
-        let key = row.get(0).unwrap();
+        let key = row.first().unwrap();
         let value = row.get(1).unwrap();
         match (key.as_str(), value.as_str()) {
             ("material.ambient", value) => s.material.ambient = value.parse::<f64>().unwrap(),
@@ -161,6 +165,14 @@ async fn calculate_intersections(world: &mut MyWorld) {
     world.xs = obj.intersect(&world.r, obj);
 }
 
+#[when(regex = r"^xs ← intersect_into\(s, r, xs\)$")]
+async fn calculate_intersections_into(world: &mut MyWorld) {
+    let obj = world.objects.get("s").unwrap();
+    let mut hits: Vec<ShapeHit> = world.xs.iter().map(|i| ShapeHit::at(i.t)).collect();
+    obj.intersect_into(&world.r, &mut hits);
+    world.xs = hits.iter().map(|hit| hit.with_object(obj)).collect();
+}
+
 #[then(regex = r"^xs.count = ([-0-9.]+)$")]
 async fn count_intersections(world: &mut MyWorld, desired: usize) {
     assert_eq!(world.xs.len(), desired);
@@ -200,6 +212,8 @@ async fn calculate_normal(world: &mut MyWorld, name: String, x: String, y: Strin
         object: s.clone(),
         u: 0.0,
         v: 0.0,
+        face: 0,
+        instances: Vec::new(),
     };
     let normal = s.normal_at(&point, hit);
     world.tuples.insert(name, normal);
@@ -0,0 +1,37 @@
+use crate::{steps::canvas::parse_bytes, MyWorld};
+use cucumber::{gherkin::Step, given, when};
+use lab_raytracing_rs::stl_file::{parse_stl_file, write_stl, write_stl_ascii};
+
+#[given("stl ← an 80 byte header followed by the bytes:")]
+async fn prepare_binary_stl(world: &mut MyWorld, step: &Step) {
+    let mut bytes = vec![0; 80];
+    bytes.extend(parse_bytes(step));
+    world.image_file = bytes;
+}
+
+#[when("g ← parse_stl_file(stl)")]
+async fn parse_stl(world: &mut MyWorld) {
+    let bytes = match world.files.get("stl") {
+        Some(content) => content.as_bytes(),
+        None => world.image_file.as_slice(),
+    };
+    match parse_stl_file(&mut &bytes[..]) {
+        Ok(group) => {
+            world.g = group;
+            world.load_error = None;
+        }
+        Err(err) => world.load_error = Some(err.to_string()),
+    }
+}
+
+#[when(regex = r#"^stl ← (write_stl|write_stl_ascii)\(g\)$"#)]
+async fn write_group(world: &mut MyWorld, writer: String) {
+    let mut bytes = Vec::new();
+    match writer.as_str() {
+        "write_stl" => write_stl(&world.g, &mut bytes),
+        _ => write_stl_ascii(&world.g, &mut bytes, "test"),
+    }
+    .expect("failed to write stl");
+    world.files.remove("stl");
+    world.image_file = bytes;
+}
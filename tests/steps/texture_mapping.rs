@@ -0,0 +1,98 @@
+use crate::{
+    steps::tuples::{eq_tuples_similar, parse_point},
+    MyWorld,
+};
+use approx::assert_abs_diff_eq;
+use cucumber::{then, when};
+use lab_raytracing_rs::{
+    canvas::Canvas,
+    patterns::{
+        cube_face, cube_map, cylindrical_map, image_pattern, pattern_at_shape, planar_map,
+        spherical_map, CubeFace, Filter, Wrap,
+    },
+    tuples::{color, point},
+};
+
+#[when(regex = r"^pattern ← image_pattern\(c, (nearest|bilinear), (repeat|clamp)\)$")]
+async fn assign_image_pattern(world: &mut MyWorld, filter: String, wrap: String) {
+    let filter = match filter.as_str() {
+        "nearest" => Filter::Nearest,
+        _ => Filter::Bilinear,
+    };
+    let wrap = match wrap.as_str() {
+        "repeat" => Wrap::Repeat,
+        _ => Wrap::Clamp,
+    };
+    let canvas = std::mem::replace(&mut world.canvas, Canvas::new(0, 0));
+    world.pattern = image_pattern(canvas, filter, wrap);
+}
+
+#[then(
+    regex = r"^uv_pattern_at\(pattern, ([-0-9.]+), ([-0-9.]+)\) = color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn compare_uv_pattern(world: &mut MyWorld, u: f64, v: f64, r: f64, g: f64, b: f64) {
+    let computed = world.pattern.color_at_uv(&point(0.0, 0.0, 0.0), &(u, v));
+    assert!(
+        eq_tuples_similar(&computed, &color(r, g, b)),
+        "{:?}",
+        computed
+    );
+}
+
+#[when(regex = r"^\(u, v\) ← (spherical|planar|cylindrical|cube)_map\(p\)$")]
+async fn map_point(world: &mut MyWorld, mapping: String) {
+    let p = world.tuples.get("p").unwrap();
+    let (u, v) = match mapping.as_str() {
+        "spherical" => spherical_map(p),
+        "planar" => planar_map(p),
+        "cylindrical" => cylindrical_map(p),
+        _ => cube_map(p),
+    };
+    world.floats.insert("u".to_string(), u);
+    world.floats.insert("v".to_string(), v);
+}
+
+#[then(regex = r"^(u|v) = ([-0-9.]+)$")]
+async fn compare_uv(world: &mut MyWorld, name: String, desired: f64) {
+    let value = *world.floats.get(&name).unwrap();
+    assert_abs_diff_eq!(value, desired, epsilon = 0.0001);
+}
+
+#[then(
+    regex = r"^pattern_at_shape\(pattern, (shape|t), point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\), hit_uv\(([-0-9.]+), ([-0-9.]+)\)\) = color\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn compare_pattern_at_hit(
+    world: &mut MyWorld,
+    object: String,
+    x: String,
+    y: String,
+    z: String,
+    u: f64,
+    v: f64,
+    r: f64,
+    g: f64,
+    b: f64,
+) {
+    let object = world.objects.get(&object).unwrap();
+    let point = parse_point(&[x, y, z]);
+    let computed = pattern_at_shape(&world.pattern, object, &point, &(u, v));
+    assert!(
+        eq_tuples_similar(&computed, &color(r, g, b)),
+        "{:?}",
+        computed
+    );
+}
+
+#[then(regex = r"^cube_face\(p\) = (left|right|front|back|up|down)$")]
+async fn compare_cube_face(world: &mut MyWorld, face: String) {
+    let desired = match face.as_str() {
+        "left" => CubeFace::Left,
+        "right" => CubeFace::Right,
+        "front" => CubeFace::Front,
+        "back" => CubeFace::Back,
+        "up" => CubeFace::Up,
+        _ => CubeFace::Down,
+    };
+    assert_eq!(cube_face(world.tuples.get("p").unwrap()), desired);
+}
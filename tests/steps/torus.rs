@@ -0,0 +1,68 @@
+use crate::{
+    steps::tuples::{eq_tuples_similar, parse_point, parse_vector},
+    MyWorld,
+};
+use approx::assert_abs_diff_eq;
+use cucumber::{given, then, when};
+use lab_raytracing_rs::{objects::torus, quartic::solve_quartic};
+use std::sync::Arc;
+
+#[when(
+    regex = r"^roots ← solve_quartic\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$"
+)]
+async fn assign_quartic_roots(world: &mut MyWorld, a: f64, b: f64, c: f64, d: f64, e: f64) {
+    world.roots = solve_quartic(a, b, c, d, e).to_vec();
+}
+
+#[then(regex = r"^roots =(.*)$")]
+async fn compare_roots(world: &mut MyWorld, desired: String) {
+    let desired: Vec<f64> = desired
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap())
+        .collect();
+    assert_eq!(world.roots.len(), desired.len(), "{:?}", world.roots);
+    for (root, desired) in world.roots.iter().zip(desired) {
+        assert_abs_diff_eq!(*root, desired, epsilon = 0.000001);
+    }
+}
+
+#[given(regex = r"^shape ← torus\(([-0-9.]+), ([-0-9.]+)\)$")]
+async fn assign_torus(world: &mut MyWorld, major: f64, minor: f64) {
+    world
+        .objects
+        .insert("shape".to_string(), Arc::new(torus(major, minor)));
+}
+
+#[then(regex = r"^normalize\(n\) = vector\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$")]
+async fn compare_normalized(world: &mut MyWorld, x: String, y: String, z: String) {
+    let n = world.tuples.get("n").unwrap().normalize();
+    assert!(eq_tuples_similar(&n, &parse_vector(&[x, y, z])), "{:?}", n);
+}
+
+#[when("box ← bounds_of(shape)")]
+async fn assign_shape_bounds(world: &mut MyWorld) {
+    let bounds = world.objects.get("shape").unwrap().shape.bounds();
+    world.tuples.insert("box.min".to_string(), bounds.min);
+    world.tuples.insert("box.max".to_string(), bounds.max);
+}
+
+#[then(regex = r"^(box.min|box.max) = point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)$")]
+async fn compare_bounds(world: &mut MyWorld, name: String, x: String, y: String, z: String) {
+    let value = world.tuples.get(&name).unwrap();
+    assert!(
+        eq_tuples_similar(value, &parse_point(&[x, y, z])),
+        "{:?}",
+        value
+    );
+}
+
+#[when(regex = r"^\(u, v\) ← uv_at\(shape, point\(([-0-9.]+), ([-0-9.]+), ([-0-9.]+)\)\)$")]
+async fn assign_shape_uv(world: &mut MyWorld, x: String, y: String, z: String) {
+    let p = parse_point(&[x, y, z]);
+    let shape = &world.objects.get("shape").unwrap().shape;
+    let (u, v) = shape.uv_at(&p, &(0.0, 0.0));
+    world.floats.insert("u".to_string(), u);
+    world.floats.insert("v".to_string(), v);
+}
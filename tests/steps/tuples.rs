@@ -11,6 +11,8 @@ pub fn parse_float(s: &str) -> f64 {
         "-√2/2" => -(2.0_f64.sqrt()) / 2.0_f64,
         "√3/3" => 3.0_f64.sqrt() / 3.0_f64,
         "-√3/3" => -(3.0_f64.sqrt()) / 3.0_f64,
+        "infinity" => f64::INFINITY,
+        "-infinity" => f64::NEG_INFINITY,
         s => s.parse::<f64>().unwrap(),
     }
 }
@@ -145,7 +147,7 @@ async fn add_tuples(
 async fn compare_point(world: &mut MyWorld, name: String, x: String, y: String, z: String) {
     let point = world.tuples.get(&name).unwrap().clone();
     let desired_color = parse_color(&[x, y, z]);
-    eq_tuples_similar(&point, &desired_color);  // TODO: use makro instead of eq_tuples_similar
+    eq_tuples_similar(&point, &desired_color); // TODO: use makro instead of eq_tuples_similar
 }
 
 #[then(
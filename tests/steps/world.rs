@@ -25,7 +25,7 @@ async fn create_default_world(world: &mut MyWorld) {
 
 #[then("w contains no objects")]
 async fn world_is_empty(world: &mut MyWorld) {
-    assert_eq!(world.w.objects.len(), 0);
+    assert_eq!(world.w.objects().len(), 0);
 }
 
 #[then("w has no light source")]
@@ -41,11 +41,12 @@ async fn compare_world_light(world: &mut MyWorld) {
 #[then(regex = r"^w contains (s1|s2)$")]
 async fn world_contains(world: &mut MyWorld, shape: String) {
     let object = world.objects.get(&shape).unwrap().as_ref().clone();
-    assert!(world.w.objects.iter().any(|i| {
+    assert!(world.w.objects().iter().any(|i| {
         match i {
             GroupMember::Object(o) => object == o.as_ref().clone(),
             GroupMember::SubGroup(_) => panic!("matching groups is not supported"),
             GroupMember::CSG(_) => panic!("matching csg is not supported"),
+            GroupMember::Instance(_) => panic!("matching instances is not supported"),
         }
     }));
 }
@@ -55,6 +56,27 @@ async fn intersect_world(world: &mut MyWorld) {
     world.xs = world.w.insersect(&world.r);
 }
 
+#[then(regex = r"^intersect_into\(w, r\) appends ([0-9]+) hits on the objects of w$")]
+async fn intersect_world_into(world: &mut MyWorld, desired: usize) {
+    let mut hits = Vec::new();
+    world.w.intersect_into(&world.r, &mut hits);
+    assert_eq!(hits.len(), desired);
+    for hit in hits {
+        assert!(world.w.objects().iter().any(|member| match member {
+            GroupMember::Object(o) => Arc::ptr_eq(o, hit.object),
+            _ => false,
+        }));
+    }
+}
+
+#[when("i ← closest_hit(w, r)")]
+async fn closest_hit_in_world(world: &mut MyWorld) {
+    match world.w.closest_hit(&world.r) {
+        None => world.intersections.remove("i"),
+        Some(i) => world.intersections.insert("i".to_string(), i),
+    };
+}
+
 #[given(regex = r"^(shape|outer|inner|A|B) ← the (first|second) object in w$")]
 async fn extract_from_world(world: &mut MyWorld, shape_name: String, position: String) {
     let index = match position.as_str() {
@@ -62,13 +84,14 @@ async fn extract_from_world(world: &mut MyWorld, shape_name: String, position: S
         "second" => 1,
         _ => panic!("position not covered"),
     };
-    let shape = world.w.objects.get(index).unwrap();
+    let shape = world.w.objects().get(index).unwrap();
     match shape {
         GroupMember::Object(o) => world
             .objects
             .insert(shape_name, Arc::new(o.as_ref().clone())),
         GroupMember::SubGroup(_) => panic!("only objects are supported"),
         GroupMember::CSG(_) => panic!("only objects are supported"),
+        GroupMember::Instance(_) => panic!("only objects are supported"),
     };
 }
 
@@ -80,7 +103,7 @@ async fn replace_in_world(world: &mut MyWorld, shape: String, position: String)
         _ => panic!("position not covered"),
     };
     let object = world.objects.get(&shape).unwrap();
-    world.w.objects[index] = GroupMember::Object(Arc::new(object.as_ref().clone()));
+    world.w.objects_mut()[index] = GroupMember::Object(Arc::new(object.as_ref().clone()));
 }
 
 #[when(regex = r"^(c|color) ← shade_hit\(w, comps\)$")]